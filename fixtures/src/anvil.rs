@@ -0,0 +1,124 @@
+//! Anvil loaded with the LocalTestnet state snapshot.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::transports::http::reqwest::Url;
+
+use crate::snapshot::LOCALTESTNET;
+use crate::{DeployedContracts, FixtureError, LocalTestnet, ScenarioBuilder, Snapshot};
+
+/// How long to wait for anvil to load the snapshot and start listening.
+const STARTUP_TIMEOUT_MS: u64 = 60_000;
+
+/// A running anvil with the LocalTestnet deployment.
+///
+/// The anvil process is killed when the handle is dropped.
+///
+/// ```no_run
+/// let anvil = tnt_core_fixtures::LocalTestnetAnvil::spawn()?;
+/// println!("Tangle at {} on {}", anvil.contracts().tangle, anvil.rpc_url());
+/// # Ok::<(), tnt_core_fixtures::FixtureError>(())
+/// ```
+#[derive(Debug)]
+pub struct LocalTestnetAnvil {
+    testnet: LocalTestnet,
+    instance: AnvilInstance,
+}
+
+impl LocalTestnetAnvil {
+    /// Spawns `anvil` from `PATH` on a free port.
+    pub fn spawn() -> Result<Self, FixtureError> {
+        Self::spawn_with(Anvil::new())
+    }
+
+    /// Spawns a customized anvil, e.g. `Anvil::at(path)` or one with a block time.
+    ///
+    /// The snapshot, chain id and the settings the snapshot was recorded with are added
+    /// to `anvil`.
+    pub fn spawn_with(anvil: Anvil) -> Result<Self, FixtureError> {
+        Self::spawn_snapshot(&LOCALTESTNET, anvil)
+    }
+
+    /// Spawns `anvil` with another packaged [`Snapshot`], e.g. one from
+    /// [`snapshot`](crate::snapshot()).
+    pub fn spawn_snapshot(snapshot: &Snapshot, anvil: Anvil) -> Result<Self, FixtureError> {
+        let testnet = snapshot.testnet()?;
+        let state_path = write_state(snapshot)?;
+
+        let anvil = anvil
+            .chain_id(testnet.chain_id)
+            .cancun()
+            .args([
+                "--base-fee",
+                "0",
+                "--gas-price",
+                "0",
+                "--disable-code-size-limit",
+            ])
+            .arg("--load-state")
+            .arg(&state_path)
+            .timeout(STARTUP_TIMEOUT_MS);
+        // Anvil reads the snapshot before it starts listening.
+        let instance = anvil.try_spawn();
+        let _ = std::fs::remove_file(&state_path);
+
+        Ok(Self {
+            testnet,
+            instance: instance?,
+        })
+    }
+
+    /// HTTP RPC endpoint, e.g. `http://localhost:41523`.
+    pub fn rpc_url(&self) -> String {
+        self.instance.endpoint()
+    }
+
+    /// HTTP RPC endpoint as a URL, ready for `ProviderBuilder::connect_http`.
+    pub fn endpoint_url(&self) -> Url {
+        self.instance.endpoint_url()
+    }
+
+    /// WebSocket RPC endpoint.
+    pub fn ws_url(&self) -> String {
+        self.instance.ws_endpoint()
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.instance.chain_id()
+    }
+
+    pub fn contracts(&self) -> &DeployedContracts {
+        &self.testnet.contracts
+    }
+
+    /// The deployment, with its accounts and contract bindings.
+    pub fn testnet(&self) -> &LocalTestnet {
+        &self.testnet
+    }
+
+    /// Starts a [`ScenarioBuilder`] against this anvil.
+    pub fn scenario(&self) -> ScenarioBuilder {
+        ScenarioBuilder::new(self.testnet.clone(), self.endpoint_url())
+    }
+
+    /// The underlying anvil process.
+    pub fn instance(&self) -> &AnvilInstance {
+        &self.instance
+    }
+}
+
+/// Decompresses the state snapshot to a file unique to this spawn.
+fn write_state(snapshot: &Snapshot) -> Result<PathBuf, FixtureError> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    let path = std::env::temp_dir().join(format!(
+        "tnt-core-{}-{}-{}.json",
+        snapshot.name,
+        std::process::id(),
+        SPAWNED.fetch_add(1, Ordering::Relaxed)
+    ));
+    snapshot.state().write_json(&path)?;
+    Ok(path)
+}
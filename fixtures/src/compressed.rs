@@ -0,0 +1,88 @@
+//! zstd-compressed fixture payloads.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use ruzstd::decoding::StreamingDecoder;
+
+/// A zstd-compressed JSON fixture embedded in the crate.
+///
+/// Only the compressed bytes are compiled in. The JSON is decompressed the first time
+/// [`json`](Self::json) is called and kept for the rest of the process.
+pub struct CompressedJson {
+    zstd: &'static [u8],
+    json: OnceLock<String>,
+}
+
+impl CompressedJson {
+    pub const fn new(zstd: &'static [u8]) -> Self {
+        Self {
+            zstd,
+            json: OnceLock::new(),
+        }
+    }
+
+    /// The compressed bytes, as committed under `fixtures/`.
+    pub fn compressed(&self) -> &'static [u8] {
+        self.zstd
+    }
+
+    /// Streams the JSON without decompressing all of it up front.
+    pub fn reader(&self) -> io::Result<impl Read + 'static> {
+        StreamingDecoder::new(self.zstd)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Decompresses the JSON into a file, without keeping it in memory.
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        io::copy(&mut self.reader()?, &mut file)?;
+        Ok(())
+    }
+
+    /// The decompressed JSON.
+    ///
+    /// # Panics
+    ///
+    /// If the embedded bytes are not zstd-compressed UTF-8, which would be a packaging bug.
+    pub fn json(&self) -> &str {
+        self.json.get_or_init(|| {
+            let mut json = String::new();
+            self.reader()
+                .and_then(|mut reader| reader.read_to_string(&mut json))
+                .expect("embedded fixture is zstd-compressed JSON");
+            json
+        })
+    }
+}
+
+impl fmt::Debug for CompressedJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressedJson")
+            .field("compressed_len", &self.zstd.len())
+            .field("decompressed", &self.json.get().is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LOCALTESTNET_BROADCAST;
+
+    #[test]
+    fn test_write_json_matches_json() {
+        let path = std::env::temp_dir().join(format!(
+            "tnt-core-fixtures-broadcast-{}.json",
+            std::process::id()
+        ));
+        LOCALTESTNET_BROADCAST.write_json(&path).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, LOCALTESTNET_BROADCAST.json());
+        assert!(format!("{LOCALTESTNET_BROADCAST:?}").contains("decompressed: true"));
+    }
+}
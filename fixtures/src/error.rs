@@ -0,0 +1,101 @@
+//! Errors returned by the fixture APIs.
+
+use std::fmt;
+use std::io;
+
+use alloy::primitives::B256;
+
+/// Error returned when a fixture cannot be parsed or started.
+#[derive(Debug)]
+pub enum FixtureError {
+    /// The broadcast is not valid JSON or has an unexpected shape.
+    Json(serde_json::Error),
+    /// A required contract was not created by the broadcast.
+    MissingContract(&'static str),
+    Io(io::Error),
+    /// A contract call failed, e.g. because it would revert.
+    Contract(alloy::contract::Error),
+    /// A transaction was sent but its receipt could not be fetched.
+    PendingTransaction(alloy::providers::PendingTransactionError),
+    /// A scenario transaction was mined but reverted.
+    Reverted {
+        step: &'static str,
+        tx: B256,
+    },
+    /// A receipt lacks the event a scenario step expects.
+    MissingEvent(&'static str),
+    /// The fixture was generated for different contracts than the bindings.
+    Incompatible {
+        fixture: String,
+        bindings: String,
+    },
+    /// Anvil could not be started.
+    #[cfg(feature = "anvil")]
+    Anvil(alloy::node_bindings::NodeError),
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid broadcast JSON: {err}"),
+            Self::MissingContract(name) => write!(f, "broadcast does not deploy {name}"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Contract(err) => write!(f, "contract call failed: {err}"),
+            Self::PendingTransaction(err) => write!(f, "transaction failed: {err}"),
+            Self::Reverted { step, tx } => write!(f, "{step} transaction {tx} reverted"),
+            Self::MissingEvent(event) => write!(f, "receipt has no {event} event"),
+            Self::Incompatible { fixture, bindings } => write!(
+                f,
+                "fixture was generated for tnt-core {fixture}, bindings are for {bindings}"
+            ),
+            #[cfg(feature = "anvil")]
+            Self::Anvil(err) => write!(f, "failed to start anvil: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            Self::MissingContract(_) => None,
+            Self::Io(err) => Some(err),
+            Self::Contract(err) => Some(err),
+            Self::PendingTransaction(err) => Some(err),
+            Self::Reverted { .. } | Self::MissingEvent(_) | Self::Incompatible { .. } => None,
+            #[cfg(feature = "anvil")]
+            Self::Anvil(err) => Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for FixtureError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<io::Error> for FixtureError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<alloy::contract::Error> for FixtureError {
+    fn from(err: alloy::contract::Error) -> Self {
+        Self::Contract(err)
+    }
+}
+
+impl From<alloy::providers::PendingTransactionError> for FixtureError {
+    fn from(err: alloy::providers::PendingTransactionError) -> Self {
+        Self::PendingTransaction(err)
+    }
+}
+
+#[cfg(feature = "anvil")]
+impl From<alloy::node_bindings::NodeError> for FixtureError {
+    fn from(err: alloy::node_bindings::NodeError) -> Self {
+        Self::Anvil(err)
+    }
+}
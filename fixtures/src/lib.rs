@@ -3,40 +3,69 @@
 use std::io;
 use std::path::{Path, PathBuf};
 
+#[cfg(feature = "anvil")]
+pub mod anvil;
+mod compressed;
+mod error;
+pub mod localtestnet;
+pub mod scenario;
+pub mod snapshot;
+
+#[cfg(feature = "anvil")]
+pub use anvil::LocalTestnetAnvil;
+pub use compressed::CompressedJson;
+pub use error::FixtureError;
+pub use localtestnet::{AccountRole, DeployedContracts, DevAccount, LocalTestnet, DEV_ACCOUNTS};
+pub use scenario::{Scenario, ScenarioBuilder, ScenarioOperator, ScenarioStage};
+pub use snapshot::{snapshot, FixtureMetadata, Snapshot, SNAPSHOTS};
+
 /// Default filename for the LocalTestnet state snapshot.
 pub const LOCALTESTNET_STATE_FILENAME: &str = "localtestnet-state.json";
 
 /// Default filename for the LocalTestnet broadcast snapshot.
 pub const LOCALTESTNET_BROADCAST_FILENAME: &str = "localtestnet-broadcast.json";
 
-/// Raw JSON for the LocalTestnet anvil state snapshot.
-pub const LOCALTESTNET_STATE_JSON: &str = include_str!("../fixtures/localtestnet-state.json");
+/// Default filename for the LocalTestnet metadata record.
+pub const LOCALTESTNET_METADATA_FILENAME: &str = "localtestnet-metadata.json";
+
+/// The LocalTestnet anvil state snapshot, zstd-compressed.
+pub static LOCALTESTNET_STATE: CompressedJson =
+    CompressedJson::new(include_bytes!("../fixtures/localtestnet-state.json.zst"));
 
-/// Raw JSON for the LocalTestnet deployment broadcast.
-pub const LOCALTESTNET_BROADCAST_JSON: &str =
-    include_str!("../fixtures/localtestnet-broadcast.json");
+/// The LocalTestnet deployment broadcast, zstd-compressed.
+pub static LOCALTESTNET_BROADCAST: CompressedJson = CompressedJson::new(include_bytes!(
+    "../fixtures/localtestnet-broadcast.json.zst"
+));
 
-/// Returns the LocalTestnet state snapshot JSON.
+/// Raw JSON for the LocalTestnet metadata record.
+pub const LOCALTESTNET_METADATA_JSON: &str = include_str!("../fixtures/localtestnet-metadata.json");
+
+/// Returns the LocalTestnet state snapshot JSON, decompressing it on first use.
 pub fn localtestnet_state_json() -> &'static str {
-    LOCALTESTNET_STATE_JSON
+    LOCALTESTNET_STATE.json()
 }
 
 /// Returns the LocalTestnet state snapshot JSON as bytes.
 pub fn localtestnet_state_bytes() -> &'static [u8] {
-    LOCALTESTNET_STATE_JSON.as_bytes()
+    localtestnet_state_json().as_bytes()
 }
 
-/// Returns the LocalTestnet broadcast JSON.
+/// Returns the LocalTestnet broadcast JSON, decompressing it on first use.
 pub fn localtestnet_broadcast_json() -> &'static str {
-    LOCALTESTNET_BROADCAST_JSON
+    LOCALTESTNET_BROADCAST.json()
 }
 
 /// Returns the LocalTestnet broadcast JSON as bytes.
 pub fn localtestnet_broadcast_bytes() -> &'static [u8] {
-    LOCALTESTNET_BROADCAST_JSON.as_bytes()
+    localtestnet_broadcast_json().as_bytes()
+}
+
+/// Returns the LocalTestnet metadata record JSON.
+pub fn localtestnet_metadata_json() -> &'static str {
+    LOCALTESTNET_METADATA_JSON
 }
 
-/// Writes both fixtures to disk using the default filenames.
+/// Writes both fixtures, decompressed, to disk using the default filenames.
 pub fn write_localtestnet_fixtures<P: AsRef<Path>>(dir: P) -> io::Result<(PathBuf, PathBuf)> {
     let dir = dir.as_ref();
     std::fs::create_dir_all(dir)?;
@@ -44,8 +73,8 @@ pub fn write_localtestnet_fixtures<P: AsRef<Path>>(dir: P) -> io::Result<(PathBu
     let state_path = dir.join(LOCALTESTNET_STATE_FILENAME);
     let broadcast_path = dir.join(LOCALTESTNET_BROADCAST_FILENAME);
 
-    std::fs::write(&state_path, LOCALTESTNET_STATE_JSON)?;
-    std::fs::write(&broadcast_path, LOCALTESTNET_BROADCAST_JSON)?;
+    LOCALTESTNET_STATE.write_json(&state_path)?;
+    LOCALTESTNET_BROADCAST.write_json(&broadcast_path)?;
 
     Ok((state_path, broadcast_path))
 }
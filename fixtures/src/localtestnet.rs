@@ -0,0 +1,430 @@
+//! Typed view of the LocalTestnet deployment.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufReader, Read};
+
+use alloy::network::Network;
+use alloy::primitives::{address, b256, Address, B256};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+use tnt_core_bindings::{ITangleFull, MultiAssetDelegation, OperatorStatusRegistry};
+
+use crate::{FixtureError, LOCALTESTNET_BROADCAST};
+
+/// Role an account plays in the LocalTestnet setup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountRole {
+    /// Deploys every contract and holds the admin roles and the TNT supply.
+    Deployer,
+    /// Registered in `MultiAssetDelegation` with a 100 TNT bond.
+    Operator,
+    /// Stakes native and ERC20 assets with the operators.
+    Delegator,
+    /// Whitelisted by the operator that uses whitelist delegation.
+    WhitelistedDelegator,
+}
+
+/// A default anvil account used by `script/LocalTestnet.s.sol`.
+///
+/// Anvil funds each of them with 10,000 ETH.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevAccount {
+    /// Index of the account in anvil's default mnemonic.
+    pub index: u32,
+    pub role: AccountRole,
+    pub address: Address,
+    pub private_key: B256,
+}
+
+impl DevAccount {
+    /// Returns a local signer for the account.
+    pub fn signer(&self) -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&self.private_key).expect("anvil keys are valid")
+    }
+}
+
+/// Deployer and admin (anvil account 0).
+pub const DEPLOYER: DevAccount = DevAccount {
+    index: 0,
+    role: AccountRole::Deployer,
+    address: address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+    private_key: b256!("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"),
+};
+
+/// Operator 1 (anvil account 1), delegation disabled.
+pub const OPERATOR1: DevAccount = DevAccount {
+    index: 1,
+    role: AccountRole::Operator,
+    address: address!("70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+    private_key: b256!("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"),
+};
+
+/// Operator 2 (anvil account 2), open delegation.
+pub const OPERATOR2: DevAccount = DevAccount {
+    index: 2,
+    role: AccountRole::Operator,
+    address: address!("3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"),
+    private_key: b256!("5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a"),
+};
+
+/// Delegator (anvil account 3).
+pub const DELEGATOR: DevAccount = DevAccount {
+    index: 3,
+    role: AccountRole::Delegator,
+    address: address!("90F79bf6EB2c4f870365E785982E1f101E93b906"),
+    private_key: b256!("7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6"),
+};
+
+/// Operator 3 (anvil account 4), whitelist delegation.
+pub const OPERATOR3: DevAccount = DevAccount {
+    index: 4,
+    role: AccountRole::Operator,
+    address: address!("15d34AAf54267DB7D7c367839AAf71A00a2C6A65"),
+    private_key: b256!("47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a"),
+};
+
+/// Delegator whitelisted by operator 3 (anvil account 5).
+pub const WHITELISTED_DELEGATOR: DevAccount = DevAccount {
+    index: 5,
+    role: AccountRole::WhitelistedDelegator,
+    address: address!("9965507D1a55bcC2695C58ba16FB37d819B0A4dc"),
+    private_key: b256!("8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba"),
+};
+
+/// All accounts used by the LocalTestnet setup, in anvil order.
+pub const DEV_ACCOUNTS: [DevAccount; 6] = [
+    DEPLOYER,
+    OPERATOR1,
+    OPERATOR2,
+    DELEGATOR,
+    OPERATOR3,
+    WHITELISTED_DELEGATOR,
+];
+
+/// The parts of a forge broadcast file needed to find deployed contracts.
+///
+/// Receipts and the other top-level fields are skipped while parsing, and only the
+/// contract creations of `transactions` are kept.
+#[derive(Debug, Deserialize)]
+struct Broadcast {
+    #[serde(deserialize_with = "creations")]
+    transactions: Vec<BroadcastTransaction>,
+    chain: u64,
+}
+
+impl Broadcast {
+    fn from_json(json: &str) -> Result<Self, FixtureError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<Self, FixtureError> {
+        Ok(serde_json::from_reader(BufReader::new(reader))?)
+    }
+}
+
+/// Deserializes the `CREATE` transactions of a list, dropping the rest as they are read.
+fn creations<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<BroadcastTransaction>, D::Error> {
+    struct Creations;
+
+    impl<'de> Visitor<'de> for Creations {
+        type Value = Vec<BroadcastTransaction>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a list of broadcast transactions")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut creations = Vec::new();
+            while let Some(tx) = seq.next_element::<BroadcastTransaction>()? {
+                if tx.transaction_type == "CREATE" {
+                    creations.push(tx);
+                }
+            }
+            Ok(creations)
+        }
+    }
+
+    deserializer.deserialize_seq(Creations)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastTransaction {
+    transaction_type: String,
+    contract_name: Option<String>,
+    contract_address: Option<Address>,
+    arguments: Option<Vec<String>>,
+}
+
+/// Addresses of the contracts deployed by the LocalTestnet broadcast.
+///
+/// Upgradeable contracts are listed at their `ERC1967Proxy` address under the name of
+/// their implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployedContracts {
+    /// Tangle proxy (`ITangleFull`).
+    pub tangle: Address,
+    /// MultiAssetDelegation proxy.
+    pub multi_asset_delegation: Address,
+    pub operator_status_registry: Address,
+    pub master_blueprint_service_manager: Address,
+    /// MBSMRegistry proxy.
+    pub mbsm_registry: Address,
+    /// TangleToken proxy (TNT).
+    pub tnt_token: Address,
+    /// Mock ERC20 staking tokens keyed by symbol (`USDC`, `WETH`, ...).
+    pub tokens: BTreeMap<String, Address>,
+    /// Every named contract creation, keyed by contract name.
+    pub contracts: BTreeMap<String, Address>,
+}
+
+impl DeployedContracts {
+    /// Collects the contract creations of a forge broadcast.
+    pub fn from_broadcast_json(json: &str) -> Result<Self, FixtureError> {
+        Self::from_broadcast(&Broadcast::from_json(json)?)
+    }
+
+    /// Like [`from_broadcast_json`](Self::from_broadcast_json), but parses the broadcast
+    /// as it is read.
+    pub fn from_broadcast_reader<R: Read>(reader: R) -> Result<Self, FixtureError> {
+        Self::from_broadcast(&Broadcast::from_reader(reader)?)
+    }
+
+    fn from_broadcast(broadcast: &Broadcast) -> Result<Self, FixtureError> {
+        let mut names: BTreeMap<Address, String> = BTreeMap::new();
+        let mut contracts = BTreeMap::new();
+        let mut tokens = BTreeMap::new();
+
+        for tx in &broadcast.transactions {
+            let (Some(name), Some(address)) = (&tx.contract_name, tx.contract_address) else {
+                continue;
+            };
+            let arguments = tx.arguments.as_deref().unwrap_or_default();
+            match name.as_str() {
+                // Proxies take their implementation as the first constructor argument.
+                "ERC1967Proxy" => {
+                    let implementation = arguments.first().and_then(|arg| arg.parse().ok());
+                    if let Some(name) = implementation.and_then(|imp: Address| names.get(&imp)) {
+                        contracts.insert(name.clone(), address);
+                    }
+                }
+                // MockToken(name, symbol, decimals)
+                "MockToken" => {
+                    if let Some(symbol) = arguments.get(1) {
+                        tokens.insert(symbol.clone(), address);
+                    }
+                }
+                _ => {
+                    names.insert(address, name.clone());
+                    contracts.insert(name.clone(), address);
+                }
+            }
+        }
+
+        let require = |name: &'static str| {
+            contracts
+                .get(name)
+                .copied()
+                .ok_or(FixtureError::MissingContract(name))
+        };
+        Ok(Self {
+            tangle: require("Tangle")?,
+            multi_asset_delegation: require("MultiAssetDelegation")?,
+            operator_status_registry: require("OperatorStatusRegistry")?,
+            master_blueprint_service_manager: require("MasterBlueprintServiceManager")?,
+            mbsm_registry: require("MBSMRegistry")?,
+            tnt_token: require("TangleToken")?,
+            tokens,
+            contracts,
+        })
+    }
+
+    /// Looks up a contract by name, e.g. `RewardVaults` or `TangleJobsFacet`.
+    pub fn get(&self, name: &str) -> Option<Address> {
+        self.contracts.get(name).copied()
+    }
+}
+
+/// The LocalTestnet deployment: chain id, contracts and default accounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTestnet {
+    pub chain_id: u64,
+    pub contracts: DeployedContracts,
+}
+
+impl LocalTestnet {
+    /// Parses the embedded LocalTestnet broadcast while decompressing it.
+    pub fn load() -> Result<Self, FixtureError> {
+        Self::from_broadcast_reader(LOCALTESTNET_BROADCAST.reader()?)
+    }
+
+    /// Parses a LocalTestnet broadcast, e.g. a freshly generated `run-latest.json`.
+    pub fn from_broadcast_json(json: &str) -> Result<Self, FixtureError> {
+        Self::from_broadcast(&Broadcast::from_json(json)?)
+    }
+
+    /// Parses a LocalTestnet broadcast as it is read, e.g. from a file.
+    pub fn from_broadcast_reader<R: Read>(reader: R) -> Result<Self, FixtureError> {
+        Self::from_broadcast(&Broadcast::from_reader(reader)?)
+    }
+
+    fn from_broadcast(broadcast: &Broadcast) -> Result<Self, FixtureError> {
+        Ok(Self {
+            chain_id: broadcast.chain,
+            contracts: DeployedContracts::from_broadcast(broadcast)?,
+        })
+    }
+
+    /// Default accounts used by the setup script.
+    pub fn accounts(&self) -> &'static [DevAccount] {
+        &DEV_ACCOUNTS
+    }
+
+    pub fn deployer(&self) -> DevAccount {
+        DEPLOYER
+    }
+
+    /// Operators registered in `MultiAssetDelegation`, in registration order.
+    pub fn operators(&self) -> [DevAccount; 3] {
+        [OPERATOR1, OPERATOR2, OPERATOR3]
+    }
+
+    pub fn delegator(&self) -> DevAccount {
+        DELEGATOR
+    }
+
+    pub fn whitelisted_delegator(&self) -> DevAccount {
+        WHITELISTED_DELEGATOR
+    }
+
+    /// Returns the Tangle proxy bound to `provider`.
+    pub fn tangle<P: Provider<N>, N: Network>(
+        &self,
+        provider: P,
+    ) -> ITangleFull::ITangleFullInstance<P, N> {
+        ITangleFull::new(self.contracts.tangle, provider)
+    }
+
+    /// Returns the MultiAssetDelegation proxy bound to `provider`.
+    pub fn multi_asset_delegation<P: Provider<N>, N: Network>(
+        &self,
+        provider: P,
+    ) -> MultiAssetDelegation::MultiAssetDelegationInstance<P, N> {
+        MultiAssetDelegation::new(self.contracts.multi_asset_delegation, provider)
+    }
+
+    /// Returns the OperatorStatusRegistry bound to `provider`.
+    pub fn operator_status_registry<P: Provider<N>, N: Network>(
+        &self,
+        provider: P,
+    ) -> OperatorStatusRegistry::OperatorStatusRegistryInstance<P, N> {
+        OperatorStatusRegistry::new(self.contracts.operator_status_registry, provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const IMPL: Address = address!("0x1000000000000000000000000000000000000001");
+
+    fn create(name: &str, address: Address, arguments: Value) -> Value {
+        json!({
+            "transactionType": "CREATE",
+            "contractName": name,
+            "contractAddress": address,
+            "arguments": arguments,
+        })
+    }
+
+    fn broadcast(transactions: Vec<Value>) -> String {
+        json!({ "transactions": transactions, "receipts": [], "chain": 31337 }).to_string()
+    }
+
+    /// Creations of every contract `from_broadcast` requires, all deployed directly.
+    fn required() -> Vec<Value> {
+        [
+            "MultiAssetDelegation",
+            "OperatorStatusRegistry",
+            "MasterBlueprintServiceManager",
+            "MBSMRegistry",
+            "TangleToken",
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| create(name, Address::with_last_byte(0x20 + i as u8), Value::Null))
+        .collect()
+    }
+
+    #[test]
+    fn test_proxy_resolves_to_implementation_name() {
+        let proxy = address!("0x2000000000000000000000000000000000000002");
+        let mut transactions = required();
+        transactions.push(create("Tangle", IMPL, Value::Null));
+        transactions.push(create(
+            "ERC1967Proxy",
+            proxy,
+            json!([IMPL.to_string(), "0x"]),
+        ));
+
+        let contracts = DeployedContracts::from_broadcast_json(&broadcast(transactions)).unwrap();
+        assert_eq!(contracts.tangle, proxy);
+        assert_eq!(contracts.get("Tangle"), Some(proxy));
+        assert_eq!(contracts.get("ERC1967Proxy"), None);
+    }
+
+    #[test]
+    fn test_mock_tokens_are_keyed_by_symbol() {
+        let usdc = address!("0x3000000000000000000000000000000000000003");
+        let mut transactions = required();
+        transactions.push(create("Tangle", IMPL, Value::Null));
+        transactions.push(create("MockToken", usdc, json!(["USD Coin", "USDC", "6"])));
+
+        let contracts = DeployedContracts::from_broadcast_json(&broadcast(transactions)).unwrap();
+        assert_eq!(contracts.tokens.len(), 1);
+        assert_eq!(contracts.tokens["USDC"], usdc);
+        assert_eq!(contracts.get("MockToken"), None);
+    }
+
+    #[test]
+    fn test_broadcast_keeps_only_creations() {
+        let call = json!({
+            "transactionType": "CALL",
+            "contractName": "Tangle",
+            "contractAddress": IMPL,
+            "arguments": ["1"],
+        });
+        let mut transactions = vec![call.clone()];
+        transactions.extend(required());
+        transactions.push(call);
+
+        let broadcast = Broadcast::from_json(&broadcast(transactions)).unwrap();
+        assert_eq!(broadcast.chain, 31337);
+        assert_eq!(broadcast.transactions.len(), required().len());
+        assert!(broadcast
+            .transactions
+            .iter()
+            .all(|tx| tx.transaction_type == "CREATE"));
+    }
+
+    #[test]
+    fn test_load_matches_broadcast_json() {
+        let loaded = LocalTestnet::load().unwrap();
+        let parsed =
+            LocalTestnet::from_broadcast_json(crate::localtestnet_broadcast_json()).unwrap();
+        assert_eq!(loaded, parsed);
+        assert_eq!(loaded.chain_id, 31337);
+    }
+
+    #[test]
+    fn test_missing_tangle_is_an_error() {
+        let err = DeployedContracts::from_broadcast_json(&broadcast(required())).unwrap_err();
+        assert!(matches!(err, FixtureError::MissingContract("Tangle")));
+    }
+}
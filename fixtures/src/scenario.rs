@@ -0,0 +1,380 @@
+//! Service lifecycle setup against the LocalTestnet deployment.
+//!
+//! Mirrors what `script/LocalTestnet.s.sol` does in Solidity: operators join
+//! `MultiAssetDelegation`, a blueprint is created and registered for, a service is
+//! requested and approved, heartbeats are configured and submitted, and jobs run.
+
+use alloy::contract::{CallBuilder, CallDecoder};
+use alloy::network::EthereumWallet;
+use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionReceipt;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::SolEvent;
+use alloy::transports::http::reqwest::Url;
+use tnt_core_bindings::bindings::i_tangle_full::Types;
+use tnt_core_bindings::{IMultiAssetDelegation, ITangleFull, OperatorStatusRegistry};
+
+use crate::localtestnet::{DEPLOYER, OPERATOR1, OPERATOR2};
+use crate::{FixtureError, LocalTestnet};
+
+/// Number of jobs in [`default_blueprint_definition`].
+const DEFAULT_JOB_COUNT: usize = 8;
+
+/// Lifecycle stage a scenario stops after, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScenarioStage {
+    /// Operators are registered in `MultiAssetDelegation`.
+    OperatorsStaked,
+    BlueprintCreated,
+    /// Operators are registered for the blueprint.
+    OperatorsRegistered,
+    ServiceRequested,
+    /// Every operator approved the request and the service is active.
+    ServiceActive,
+    /// Heartbeats are configured and every operator submitted one.
+    Heartbeats,
+    JobsSubmitted,
+    /// Operators submitted results until every job completed.
+    JobsCompleted,
+}
+
+/// An operator of a scenario.
+#[derive(Debug, Clone)]
+pub struct ScenarioOperator {
+    pub signer: PrivateKeySigner,
+    pub address: Address,
+    /// Uncompressed ECDSA key registered for the blueprint (the operator's wallet key).
+    pub ecdsa_public_key: Bytes,
+}
+
+/// Ids produced by a scenario; those of stages that did not run are `None` or empty.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    /// Blueprint and service owner.
+    pub owner: Address,
+    pub operators: Vec<ScenarioOperator>,
+    pub blueprint_id: Option<u64>,
+    pub request_id: Option<u64>,
+    pub service_id: Option<u64>,
+    /// Call ids of the submitted jobs, in submission order.
+    pub call_ids: Vec<u64>,
+}
+
+/// Drives the service lifecycle through the bindings, up to a chosen stage.
+///
+/// Defaults to the setup script's actors: the deployer owns the blueprint and service,
+/// and operators 1 and 2 serve it. Operators already registered in
+/// `MultiAssetDelegation` (as the fixture operators are) are not staked again.
+///
+/// ```no_run
+/// # async fn run(testnet: tnt_core_fixtures::LocalTestnet) -> Result<(), tnt_core_fixtures::FixtureError> {
+/// use tnt_core_fixtures::{ScenarioBuilder, ScenarioStage};
+///
+/// let scenario = ScenarioBuilder::new(testnet, "http://127.0.0.1:8545".parse().unwrap())
+///     .stage(ScenarioStage::JobsSubmitted)
+///     .build()
+///     .await?;
+/// let service_id = scenario.service_id.unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScenarioBuilder {
+    testnet: LocalTestnet,
+    rpc_url: Url,
+    stage: ScenarioStage,
+    owner: PrivateKeySigner,
+    operators: Vec<PrivateKeySigner>,
+    operator_stake: U256,
+    blueprint: Types::BlueprintDefinition,
+    heartbeat_interval: u64,
+    heartbeat_max_missed: u8,
+    jobs: Vec<(u8, Bytes)>,
+}
+
+impl ScenarioBuilder {
+    /// Creates a builder for the deployment reachable at `rpc_url`.
+    pub fn new(testnet: LocalTestnet, rpc_url: Url) -> Self {
+        Self {
+            testnet,
+            rpc_url,
+            stage: ScenarioStage::ServiceActive,
+            owner: DEPLOYER.signer(),
+            operators: vec![OPERATOR1.signer(), OPERATOR2.signer()],
+            operator_stake: U256::from(10u128 * 10u128.pow(18)),
+            blueprint: default_blueprint_definition(),
+            heartbeat_interval: 60,
+            heartbeat_max_missed: 3,
+            jobs: vec![(0, Bytes::new())],
+        }
+    }
+
+    /// Stage to stop after. Defaults to [`ScenarioStage::ServiceActive`].
+    pub fn stage(mut self, stage: ScenarioStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Account that creates the blueprint, requests the service and submits jobs.
+    pub fn owner(mut self, owner: PrivateKeySigner) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Operators of the service, replacing the defaults.
+    pub fn operators(mut self, operators: impl IntoIterator<Item = PrivateKeySigner>) -> Self {
+        self.operators = operators.into_iter().collect();
+        self
+    }
+
+    /// Native stake for operators not yet in `MultiAssetDelegation`. Defaults to 10 ETH.
+    pub fn operator_stake(mut self, stake: U256) -> Self {
+        self.operator_stake = stake;
+        self
+    }
+
+    /// Blueprint to create instead of [`default_blueprint_definition`].
+    pub fn blueprint(mut self, definition: Types::BlueprintDefinition) -> Self {
+        self.blueprint = definition;
+        self
+    }
+
+    /// Heartbeat interval in seconds (at least 60) and missed beats before offline.
+    pub fn heartbeat(mut self, interval: u64, max_missed: u8) -> Self {
+        self.heartbeat_interval = interval;
+        self.heartbeat_max_missed = max_missed;
+        self
+    }
+
+    /// Jobs to submit as `(job index, inputs)`. Defaults to job 0 with empty inputs.
+    pub fn jobs(mut self, jobs: impl IntoIterator<Item = (u8, Bytes)>) -> Self {
+        self.jobs = jobs.into_iter().collect();
+        self
+    }
+
+    /// Runs every stage up to and including the configured one.
+    pub async fn build(self) -> Result<Scenario, FixtureError> {
+        let mut wallet = EthereumWallet::new(self.owner.clone());
+        for operator in &self.operators {
+            wallet.register_signer(operator.clone());
+        }
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .connect_http(self.rpc_url.clone())
+            .erased();
+
+        let contracts = &self.testnet.contracts;
+        let staking =
+            IMultiAssetDelegation::new(contracts.multi_asset_delegation, provider.clone());
+        let tangle = ITangleFull::new(contracts.tangle, provider.clone());
+        let registry = OperatorStatusRegistry::new(contracts.operator_status_registry, provider);
+
+        let owner = self.owner.address();
+        let mut scenario = Scenario {
+            owner,
+            operators: self.operators.iter().map(scenario_operator).collect(),
+            blueprint_id: None,
+            request_id: None,
+            service_id: None,
+            call_ids: Vec::new(),
+        };
+        let operators: Vec<Address> = scenario.operators.iter().map(|op| op.address).collect();
+
+        for &operator in &operators {
+            if !staking.isOperator(operator).call().await? {
+                let call = staking.registerOperator().value(self.operator_stake);
+                send("registerOperator", call.from(operator)).await?;
+            }
+        }
+        if self.stage == ScenarioStage::OperatorsStaked {
+            return Ok(scenario);
+        }
+
+        let call = tangle.createBlueprint(self.blueprint.clone()).from(owner);
+        let receipt = send("createBlueprint", call).await?;
+        let blueprint_id = event::<ITangleFull::BlueprintCreated>(&receipt)?.blueprintId;
+        scenario.blueprint_id = Some(blueprint_id);
+        if self.stage == ScenarioStage::BlueprintCreated {
+            return Ok(scenario);
+        }
+
+        for operator in &scenario.operators {
+            let call = tangle.registerOperator_1(
+                blueprint_id,
+                operator.ecdsa_public_key.clone(),
+                format!("http://{}.local:8545", operator.address),
+            );
+            send("registerOperator", call.from(operator.address)).await?;
+        }
+        if self.stage == ScenarioStage::OperatorsRegistered {
+            return Ok(scenario);
+        }
+
+        let call = tangle.requestService(
+            blueprint_id,
+            operators.clone(),
+            Bytes::new(),
+            vec![owner],
+            0,
+            Address::ZERO,
+            U256::ZERO,
+            0, // ConfidentialityPolicy.Any
+        );
+        let receipt = send("requestService", call.from(owner)).await?;
+        let request_id = event::<ITangleFull::ServiceRequested>(&receipt)?.requestId;
+        scenario.request_id = Some(request_id);
+        if self.stage == ScenarioStage::ServiceRequested {
+            return Ok(scenario);
+        }
+
+        let mut receipt = None;
+        for &operator in &operators {
+            let params = Types::ApprovalParams {
+                requestId: request_id,
+                ..Default::default()
+            };
+            let call = tangle.approveService(params).from(operator);
+            receipt = Some(send("approveService", call).await?);
+        }
+        let receipt = receipt.ok_or(FixtureError::MissingEvent(
+            ITangleFull::ServiceActivated::SIGNATURE,
+        ))?;
+        let service_id = event::<ITangleFull::ServiceActivated>(&receipt)?.serviceId;
+        scenario.service_id = Some(service_id);
+        if self.stage == ScenarioStage::ServiceActive {
+            return Ok(scenario);
+        }
+
+        let call = registry.configureHeartbeat(
+            service_id,
+            self.heartbeat_interval,
+            self.heartbeat_max_missed,
+        );
+        send("configureHeartbeat", call.from(owner)).await?;
+        for &operator in &operators {
+            // Status code 0 is healthy.
+            let call = registry.submitHeartbeatDirect(service_id, blueprint_id, 0, Bytes::new());
+            send("submitHeartbeatDirect", call.from(operator)).await?;
+        }
+        if self.stage == ScenarioStage::Heartbeats {
+            return Ok(scenario);
+        }
+
+        for (job_index, inputs) in &self.jobs {
+            let call = tangle.submitJob(service_id, *job_index, inputs.clone());
+            let receipt = send("submitJob", call.from(owner)).await?;
+            let call_id = event::<ITangleFull::JobSubmitted>(&receipt)?.callId;
+            scenario.call_ids.push(call_id);
+        }
+        if self.stage == ScenarioStage::JobsSubmitted {
+            return Ok(scenario);
+        }
+
+        for &call_id in &scenario.call_ids {
+            let mut completed = false;
+            for &operator in &operators {
+                let call = tangle.submitResult(service_id, call_id, Bytes::new());
+                let receipt = send("submitResult", call.from(operator)).await?;
+                if receipt.decoded_log::<ITangleFull::JobCompleted>().is_some() {
+                    completed = true;
+                    break;
+                }
+            }
+            if !completed {
+                return Err(FixtureError::MissingEvent(
+                    ITangleFull::JobCompleted::SIGNATURE,
+                ));
+            }
+        }
+        Ok(scenario)
+    }
+}
+
+/// The blueprint `test/support/BlueprintDefinitionHelper.sol` builds by default: fixed
+/// or dynamic membership, pay-once pricing, eight jobs and empty schemas.
+pub fn default_blueprint_definition() -> Types::BlueprintDefinition {
+    let metadata_uri = "http://localhost:3333".to_string();
+    let job = Types::JobDefinition {
+        name: "Test Job".into(),
+        description: "Default job for tests".into(),
+        ..Default::default()
+    };
+    let source = Types::BlueprintSource {
+        kind: 0, // Container
+        container: Types::ImageRegistrySource {
+            registry: "registry.tangle.local".into(),
+            image: "blueprint".into(),
+            tag: "latest".into(),
+        },
+        wasm: Types::WasmSource {
+            runtime: 1, // Wasmtime
+            ..Default::default()
+        },
+        binaries: vec![Types::BlueprintBinary {
+            arch: 5, // Amd64
+            os: 1,   // Linux
+            name: "blueprint-binary".into(),
+            sha256: FixedBytes::from(U256::from(0x1234)),
+        }],
+        ..Default::default()
+    };
+
+    Types::BlueprintDefinition {
+        metadataHash: keccak256(&metadata_uri),
+        metadataUri: metadata_uri,
+        hasConfig: true,
+        config: Types::BlueprintConfig {
+            membership: 1, // Dynamic
+            minOperators: 1,
+            ..Default::default()
+        },
+        metadata: Types::BlueprintMetadata {
+            name: "Test Blueprint".into(),
+            description: "Test blueprint definition".into(),
+            author: "Tangle".into(),
+            category: "Test".into(),
+            codeRepository: "https://github.com/tangle-network/tnt-core".into(),
+            website: "https://tangle.network".into(),
+            license: "MIT".into(),
+            ..Default::default()
+        },
+        jobs: vec![job; DEFAULT_JOB_COUNT],
+        sources: vec![source],
+        supportedMemberships: vec![0, 1],
+        ..Default::default()
+    }
+}
+
+fn scenario_operator(signer: &PrivateKeySigner) -> ScenarioOperator {
+    let mut ecdsa_public_key = vec![0x04];
+    ecdsa_public_key.extend_from_slice(signer.public_key().as_slice());
+    ScenarioOperator {
+        signer: signer.clone(),
+        address: signer.address(),
+        ecdsa_public_key: ecdsa_public_key.into(),
+    }
+}
+
+/// Sends a transaction and waits for it to succeed.
+async fn send<D: CallDecoder>(
+    step: &'static str,
+    call: CallBuilder<&DynProvider, D>,
+) -> Result<TransactionReceipt, FixtureError> {
+    let receipt = call.send().await?.get_receipt().await?;
+    if !receipt.status() {
+        return Err(FixtureError::Reverted {
+            step,
+            tx: receipt.transaction_hash,
+        });
+    }
+    Ok(receipt)
+}
+
+/// Decodes the first `E` event of a receipt.
+fn event<E: SolEvent>(receipt: &TransactionReceipt) -> Result<E, FixtureError> {
+    receipt
+        .decoded_log::<E>()
+        .map(|log| log.data)
+        .ok_or(FixtureError::MissingEvent(E::SIGNATURE))
+}
@@ -0,0 +1,197 @@
+//! Named fixture snapshots and the metadata recorded with them.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use alloy::primitives::Address;
+use serde::Deserialize;
+use tnt_core_bindings::TNT_CORE_VERSION;
+
+use crate::{
+    CompressedJson, FixtureError, LocalTestnet, LOCALTESTNET_BROADCAST, LOCALTESTNET_METADATA_JSON,
+    LOCALTESTNET_STATE,
+};
+
+/// What `update-localtestnet-fixtures.sh` recorded about a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureMetadata {
+    /// Snapshot name, e.g. `localtestnet`.
+    pub name: String,
+    /// Commit of tnt-core the snapshot was generated from.
+    pub git_rev: String,
+    /// `TNT_CORE_VERSION` of the bindings at that commit.
+    pub bindings_version: String,
+    pub chain_id: u64,
+    /// Block number of anvil when the state was dumped.
+    pub block_number: u64,
+    /// Contract creations keyed by name, with proxies under their implementation.
+    pub contracts: BTreeMap<String, Address>,
+    /// Mock ERC20 tokens keyed by symbol.
+    pub tokens: BTreeMap<String, Address>,
+}
+
+impl FixtureMetadata {
+    pub fn from_json(json: &str) -> Result<Self, FixtureError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Whether the snapshot was generated for the contracts these bindings were built from.
+    pub fn is_compatible(&self) -> bool {
+        is_same_rev(&self.bindings_version, TNT_CORE_VERSION)
+    }
+
+    /// Like [`is_compatible`](Self::is_compatible), but returns the mismatch as an error.
+    pub fn check_compatible(&self) -> Result<(), FixtureError> {
+        if self.is_compatible() {
+            Ok(())
+        } else {
+            Err(FixtureError::Incompatible {
+                fixture: self.bindings_version.clone(),
+                bindings: TNT_CORE_VERSION.trim().to_string(),
+            })
+        }
+    }
+}
+
+/// Compares two commits, allowing either one to be abbreviated.
+fn is_same_rev(a: &str, b: &str) -> bool {
+    let a = a.trim().to_ascii_lowercase();
+    let b = b.trim().to_ascii_lowercase();
+    !a.is_empty() && !b.is_empty() && (a.starts_with(&b) || b.starts_with(&a))
+}
+
+/// An anvil state snapshot with the broadcast and metadata it was generated with.
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot {
+    /// Name used for the fixture files, e.g. `localtestnet-state.json.zst`.
+    pub name: &'static str,
+    pub description: &'static str,
+    state: &'static CompressedJson,
+    broadcast: &'static CompressedJson,
+    metadata_json: &'static str,
+}
+
+impl Snapshot {
+    /// The compressed state for anvil's `--load-state`.
+    pub fn state(&self) -> &'static CompressedJson {
+        self.state
+    }
+
+    /// The compressed forge broadcast.
+    pub fn broadcast(&self) -> &'static CompressedJson {
+        self.broadcast
+    }
+
+    /// Raw JSON for anvil's `--load-state`, decompressed on first use.
+    pub fn state_json(&self) -> &'static str {
+        self.state.json()
+    }
+
+    /// Raw JSON of the forge broadcast, decompressed on first use.
+    pub fn broadcast_json(&self) -> &'static str {
+        self.broadcast.json()
+    }
+
+    /// Raw JSON of the metadata record.
+    pub fn metadata_json(&self) -> &'static str {
+        self.metadata_json
+    }
+
+    pub fn metadata(&self) -> Result<FixtureMetadata, FixtureError> {
+        FixtureMetadata::from_json(self.metadata_json)
+    }
+
+    /// Parses the broadcast into the deployment while decompressing it.
+    pub fn testnet(&self) -> Result<LocalTestnet, FixtureError> {
+        LocalTestnet::from_broadcast_reader(self.broadcast.reader()?)
+    }
+
+    /// Writes `<name>-state.json`, `<name>-broadcast.json` and `<name>-metadata.json`
+    /// to `dir`, decompressed, returning their paths in that order.
+    pub fn write_to<P: AsRef<Path>>(&self, dir: P) -> io::Result<[PathBuf; 3]> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let paths = ["state", "broadcast", "metadata"]
+            .map(|kind| dir.join(format!("{}-{kind}.json", self.name)));
+        self.state.write_json(&paths[0])?;
+        self.broadcast.write_json(&paths[1])?;
+        std::fs::write(&paths[2], self.metadata_json)?;
+
+        Ok(paths)
+    }
+}
+
+/// Every snapshot packaged with this crate.
+///
+/// Only `localtestnet` is packaged. `update-localtestnet-fixtures.sh` can also
+/// generate `SNAPSHOT=localtestnet-bare` (contracts only) and
+/// `SNAPSHOT=localtestnet-slashing` (a pending slash), which are not shipped.
+pub static SNAPSHOTS: &[Snapshot] = &[LOCALTESTNET];
+
+/// Operators, delegations, blueprints and an active service.
+pub static LOCALTESTNET: Snapshot = Snapshot {
+    name: "localtestnet",
+    description: "Full deployment with operators, delegations and seeded services",
+    state: &LOCALTESTNET_STATE,
+    broadcast: &LOCALTESTNET_BROADCAST,
+    metadata_json: LOCALTESTNET_METADATA_JSON,
+};
+
+/// Looks up a packaged snapshot by name.
+pub fn snapshot(name: &str) -> Option<&'static Snapshot> {
+    SNAPSHOTS.iter().find(|snapshot| snapshot.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(bindings_version: &str) -> FixtureMetadata {
+        FixtureMetadata {
+            bindings_version: bindings_version.to_string(),
+            ..LOCALTESTNET.metadata().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_packaged_metadata_is_compatible() {
+        for snapshot in SNAPSHOTS {
+            let metadata = snapshot.metadata().unwrap();
+            assert_eq!(metadata.name, snapshot.name);
+            assert!(metadata.check_compatible().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_other_rev_is_incompatible() {
+        let err = metadata("0000000000000000000000000000000000000000")
+            .check_compatible()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FixtureError::Incompatible { fixture, bindings }
+                if fixture == "0000000000000000000000000000000000000000"
+                    && bindings == TNT_CORE_VERSION.trim()
+        ));
+    }
+
+    #[test]
+    fn test_is_same_rev() {
+        let rev = "7cdda757d58cc659ab8f8a37b71f6d9bb1b0f623";
+        assert!(is_same_rev(rev, rev));
+        assert!(is_same_rev("7cdda75", rev));
+        assert!(is_same_rev(&rev.to_uppercase(), &format!("{rev}\n")));
+        assert!(!is_same_rev("85e0897", rev));
+    }
+
+    #[test]
+    fn test_empty_rev_never_matches() {
+        assert!(!is_same_rev("", ""));
+        assert!(!is_same_rev("", "7cdda75"));
+        assert!(!is_same_rev("7cdda75", "  "));
+        assert!(!metadata("").is_compatible());
+    }
+}
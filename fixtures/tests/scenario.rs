@@ -0,0 +1,26 @@
+//! Runs the service lifecycle against a spawned anvil.
+//!
+//! Needs `anvil` on `PATH`:
+//! `cargo test -p tnt-core-fixtures --features anvil -- --ignored`
+#![cfg(feature = "anvil")]
+
+use tnt_core_fixtures::{LocalTestnetAnvil, ScenarioStage};
+
+#[tokio::test]
+#[ignore = "requires anvil on PATH"]
+async fn test_scenario_completes_jobs() {
+    let anvil = LocalTestnetAnvil::spawn().expect("anvil starts with the LocalTestnet state");
+
+    let scenario = anvil
+        .scenario()
+        .stage(ScenarioStage::JobsCompleted)
+        .build()
+        .await
+        .expect("scenario runs to JobsCompleted");
+
+    assert_eq!(scenario.operators.len(), 2);
+    assert!(scenario.blueprint_id.is_some());
+    assert!(scenario.request_id.is_some());
+    assert!(scenario.service_id.is_some());
+    assert_eq!(scenario.call_ids.len(), 1);
+}
@@ -5,25 +5,292 @@
 //!
 //! The public values must match what the on-chain verifier expects.
 //! Current format:
-//! abi.encode(bytes32 pubkey, address evmAddress, uint256 amount, bytes32 challenge)
+//! abi.encode(bytes32 pubkey, address evmAddress, uint256 amount, bytes32 challenge, uint8 scheme)
 
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::SolValue;
-use blake2::{Blake2b512, Digest};
+use blake2::{digest::consts::U32, Blake2b, Blake2b512, Digest};
 use serde::{Deserialize, Serialize};
-use serde_big_array::BigArray;
 
 /// SS58 checksum prefix
 const SS58_PREFIX: &[u8] = b"SS58PRE";
 
-/// The inputs to the SR25519 verification program
+/// The signing context used by Substrate for SR25519 signatures
+/// This must match what the polkadot.js extension uses
+const SUBSTRATE_CONTEXT: &[u8] = b"substrate";
+
+/// Tangle network SS58 prefix (addresses start with "tg")
+pub const TANGLE_SS58_PREFIX: u16 = 5845;
+
+/// Network ids must fit the 14 bits of the two-byte prefix
+const SS58_MAX_NETWORK_ID: u16 = 1 << 14;
+
+/// Network ids reserved by the SS58 registry
+const SS58_RESERVED_PREFIXES: [u16; 2] = [46, 47];
+
+/// Errors from SS58 and public values decoding and signature verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimLibError {
+    /// Address is not valid base58
+    InvalidBase58,
+    /// Decoded address has the wrong number of bytes
+    InvalidLength(usize),
+    /// First byte is not a valid SS58 prefix (must be below 128)
+    InvalidPrefix(u8),
+    /// Two-byte prefix encoding a network id below 64, which has a one-byte form
+    NonCanonicalPrefix(u16),
+    /// Network id is reserved by the SS58 registry
+    ReservedPrefix(u16),
+    /// Network id does not fit in 14 bits
+    NetworkIdTooLarge(u16),
+    /// Checksum does not match the prefix and public key
+    InvalidChecksum,
+    /// Public values are not a valid ABI-encoded tuple
+    AbiDecode,
+    /// Public values carry a signature scheme this version doesn't know
+    UnknownSignatureScheme,
+    /// ZK proof has no scheme byte
+    EmptyProof,
+    /// Account id is not a valid public key for the signature scheme
+    InvalidPublicKey,
+    /// Signature bytes can't be parsed for the signature scheme
+    InvalidSignature,
+    /// Signature has the wrong number of bytes for the signature scheme
+    InvalidSignatureLength(usize),
+    /// ECDSA recovery id is not 0, 1, 27 or 28
+    InvalidRecoveryId,
+    /// Signature was not made over the message by the account
+    SignatureMismatch,
+}
+
+impl core::fmt::Display for ClaimLibError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidBase58 => f.write_str("Invalid base58 encoding"),
+            Self::InvalidLength(len) => write!(f, "Invalid SS58 address length: {len} bytes"),
+            Self::InvalidPrefix(byte) => write!(f, "Invalid SS58 prefix byte: {byte}"),
+            Self::NonCanonicalPrefix(id) => {
+                write!(f, "Network id {id} must use the one-byte SS58 prefix")
+            }
+            Self::ReservedPrefix(id) => write!(f, "Network id {id} is reserved"),
+            Self::NetworkIdTooLarge(id) => write!(f, "Network id {id} is too large"),
+            Self::InvalidChecksum => f.write_str("Invalid SS58 checksum"),
+            Self::AbiDecode => f.write_str("Failed to ABI decode public values"),
+            Self::UnknownSignatureScheme => f.write_str("Unknown signature scheme"),
+            Self::EmptyProof => f.write_str("ZK proof is empty"),
+            Self::InvalidPublicKey => f.write_str("Invalid public key"),
+            Self::InvalidSignature => f.write_str("Invalid signature format"),
+            Self::InvalidSignatureLength(len) => {
+                write!(f, "Invalid signature length: {len} bytes")
+            }
+            Self::InvalidRecoveryId => f.write_str("Invalid ECDSA recovery id"),
+            Self::SignatureMismatch => f.write_str("Signature verification failed"),
+        }
+    }
+}
+
+impl core::error::Error for ClaimLibError {}
+
+/// Substrate signature scheme of the claiming account
+///
+/// The discriminant is the `uint8 scheme` committed in the public values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum SignatureScheme {
+    /// Schnorrkel/Ristretto (polkadot.js default). The account id is the public key.
+    #[default]
+    Sr25519 = 0,
+    /// Ed25519. The account id is the public key.
+    Ed25519 = 1,
+    /// ECDSA over secp256k1 (Ledger and some hardware wallets). The account id is
+    /// `blake2_256` of the 33-byte compressed public key.
+    Ecdsa = 2,
+}
+
+impl SignatureScheme {
+    pub const ALL: [SignatureScheme; 3] = [
+        SignatureScheme::Sr25519,
+        SignatureScheme::Ed25519,
+        SignatureScheme::Ecdsa,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureScheme::Sr25519 => "sr25519",
+            SignatureScheme::Ed25519 => "ed25519",
+            SignatureScheme::Ecdsa => "ecdsa",
+        }
+    }
+
+    /// Expected signature length in bytes (ECDSA carries a trailing recovery id)
+    pub fn signature_len(&self) -> usize {
+        match self {
+            SignatureScheme::Sr25519 | SignatureScheme::Ed25519 => 64,
+            SignatureScheme::Ecdsa => 65,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|scheme| *scheme as u8 == value)
+    }
+}
+
+/// Substrate wallet extensions wrap messages with <Bytes>...</Bytes> when signing
+/// with signRaw({ type: 'bytes' })
+pub const WRAP_PREFIX: &[u8] = b"<Bytes>";
+pub const WRAP_POSTFIX: &[u8] = b"</Bytes>";
+
+/// How the challenge was framed before signing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageWrapping {
+    /// `<Bytes>challenge</Bytes>`, as polkadot.js `signRaw` produces
+    #[default]
+    Wrapped,
+    /// The bare 32-byte challenge, as subkey and most CLI signers produce
+    Raw,
+}
+
+impl MessageWrapping {
+    pub const ALL: [MessageWrapping; 2] = [MessageWrapping::Wrapped, MessageWrapping::Raw];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageWrapping::Wrapped => "wrapped",
+            MessageWrapping::Raw => "raw",
+        }
+    }
+
+    /// The message that was signed for `challenge`
+    pub fn message(&self, challenge: &[u8; 32]) -> Vec<u8> {
+        match self {
+            MessageWrapping::Wrapped => [WRAP_PREFIX, challenge, WRAP_POSTFIX].concat(),
+            MessageWrapping::Raw => challenge.to_vec(),
+        }
+    }
+}
+
+/// The challenge a claimant signs, as `TangleMigration` recomputes it:
+/// `keccak256(abi.encode(migrationContract, chainId, evmAddress, amount))`
+pub fn compute_claim_challenge(
+    contract: [u8; 20],
+    chain_id: u64,
+    evm_address: [u8; 20],
+    amount: [u8; 32],
+) -> [u8; 32] {
+    let encoded = (
+        Address::from(contract),
+        U256::from(chain_id),
+        Address::from(evm_address),
+        U256::from_be_bytes(amount),
+    )
+        .abi_encode();
+    alloy_primitives::keccak256(encoded).0
+}
+
+/// Substrate's `blake2_256` hash
+pub fn blake2_256(data: &[u8]) -> [u8; 32] {
+    Blake2b::<U32>::digest(data).into()
+}
+
+/// Substrate account id of an ECDSA key: `blake2_256(compressed_pubkey)`
+pub fn ecdsa_account_id(compressed_pubkey: &[u8; 33]) -> [u8; 32] {
+    blake2_256(compressed_pubkey)
+}
+
+/// SR25519: the account id is the public key, signed with the "substrate" context
+///
+/// This and the other `verify_*` functions are shared by the SP1 program and the
+/// prover API's pre-check, so both accept exactly the same signatures. `message` is
+/// the framed challenge (see [`MessageWrapping::message`]).
+pub fn verify_sr25519(
+    account_id: &[u8; 32],
+    signature: &[u8],
+    message: &[u8],
+) -> Result<(), ClaimLibError> {
+    use schnorrkel::{signing_context, PublicKey, Signature};
+
+    let public_key =
+        PublicKey::from_bytes(account_id).map_err(|_| ClaimLibError::InvalidPublicKey)?;
+    let signature =
+        Signature::from_bytes(signature).map_err(|_| ClaimLibError::InvalidSignature)?;
+
+    public_key
+        .verify(
+            signing_context(SUBSTRATE_CONTEXT).bytes(message),
+            &signature,
+        )
+        .map_err(|_| ClaimLibError::SignatureMismatch)
+}
+
+/// Ed25519: the account id is the public key, signed over the message directly
+pub fn verify_ed25519(
+    account_id: &[u8; 32],
+    signature: &[u8],
+    message: &[u8],
+) -> Result<(), ClaimLibError> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let public_key =
+        VerifyingKey::from_bytes(account_id).map_err(|_| ClaimLibError::InvalidPublicKey)?;
+    let signature =
+        Signature::from_slice(signature).map_err(|_| ClaimLibError::InvalidSignature)?;
+
+    public_key
+        .verify(message, &signature)
+        .map_err(|_| ClaimLibError::SignatureMismatch)
+}
+
+/// ECDSA: Substrate signs `blake2_256(message)` with a 65-byte `r || s || v`
+/// signature, and the account id is `blake2_256` of the compressed public key
+pub fn verify_ecdsa(
+    account_id: &[u8; 32],
+    signature: &[u8],
+    message: &[u8],
+) -> Result<(), ClaimLibError> {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    if signature.len() != SignatureScheme::Ecdsa.signature_len() {
+        return Err(ClaimLibError::InvalidSignatureLength(signature.len()));
+    }
+    let mut sig =
+        Signature::from_slice(&signature[..64]).map_err(|_| ClaimLibError::InvalidSignature)?;
+    // Accept both 0/1 and Ethereum-style 27/28 recovery ids
+    let v = signature[64];
+    let mut recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })
+        .ok_or(ClaimLibError::InvalidRecoveryId)?;
+    // Recovery requires low-s; flipping s also flips the parity of R's y
+    if let Some(normalized) = sig.normalize_s() {
+        sig = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+
+    let public_key = VerifyingKey::recover_from_prehash(&blake2_256(message), &sig, recovery_id)
+        .map_err(|_| ClaimLibError::SignatureMismatch)?;
+    let compressed: [u8; 33] = public_key
+        .to_encoded_point(true)
+        .as_bytes()
+        .try_into()
+        .map_err(|_| ClaimLibError::InvalidPublicKey)?;
+
+    if &ecdsa_account_id(&compressed) != account_id {
+        return Err(ClaimLibError::SignatureMismatch);
+    }
+    Ok(())
+}
+
+/// The inputs to the claim verification program
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgramInput {
-    /// The SS58 Substrate address (string) - public key is derived from this
+    /// The SS58 Substrate address (string) - account id is derived from this
     pub substrate_address: String,
-    /// The SR25519 signature (64 bytes)
-    #[serde(with = "BigArray")]
-    pub signature: [u8; 64],
+    /// Signature scheme of the account
+    pub scheme: SignatureScheme,
+    /// The signature (64 bytes, or 65 bytes `r || s || v` for ECDSA)
+    pub signature: Vec<u8>,
+    /// Whether the challenge was signed wrapped in `<Bytes>...</Bytes>` or raw
+    pub wrapping: MessageWrapping,
     /// The EVM address claiming the tokens (20 bytes)
     pub evm_address: [u8; 20],
     /// The claim amount in wei
@@ -37,7 +304,8 @@ pub struct ProgramInput {
 /// These must match what SP1ZKVerifier.sol decodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicValues {
-    /// The SR25519 public key (32 bytes, decoded from SS58)
+    /// The account id (32 bytes, decoded from SS58): the public key for SR25519 and
+    /// Ed25519, the hashed public key for ECDSA
     pub pubkey: [u8; 32],
     /// The EVM address to receive tokens
     pub evm_address: [u8; 20],
@@ -45,11 +313,13 @@ pub struct PublicValues {
     pub amount: [u8; 32],
     /// The signed challenge hash committed on-chain
     pub challenge: [u8; 32],
+    /// The signature scheme the ownership was proven with
+    pub scheme: SignatureScheme,
 }
 
 impl PublicValues {
     /// Encode the public values for on-chain verification
-    /// Format: abi.encode(bytes32, address, uint256)
+    /// Format: abi.encode(bytes32, address, uint256, bytes32, uint8)
     /// This matches what SP1ZKVerifier.sol expects
     pub fn abi_encode(&self) -> Vec<u8> {
         use alloy_primitives::FixedBytes;
@@ -58,121 +328,166 @@ impl PublicValues {
         let address = Address::from_slice(&self.evm_address);
         let amount = U256::from_be_bytes(self.amount);
         let challenge = FixedBytes::<32>::from_slice(&self.challenge);
+        // A uint8 occupies a full word, the same as a uint256
+        let scheme = U256::from(self.scheme as u8);
 
-        // Encode as Solidity tuple: (bytes32, address, uint256, bytes32)
-        (pubkey, address, amount, challenge).abi_encode()
+        // Encode as Solidity tuple: (bytes32, address, uint256, bytes32, uint8)
+        (pubkey, address, amount, challenge, scheme).abi_encode()
     }
 
     /// Decode public values from ABI-encoded bytes
-    pub fn abi_decode(data: &[u8]) -> Result<Self, &'static str> {
+    pub fn abi_decode(data: &[u8]) -> Result<Self, ClaimLibError> {
         use alloy_primitives::FixedBytes;
 
-        let decoded = <(FixedBytes<32>, Address, U256, FixedBytes<32>)>::abi_decode(data)
-            .map_err(|_| "Failed to ABI decode public values")?;
+        let decoded = <(FixedBytes<32>, Address, U256, FixedBytes<32>, U256)>::abi_decode(data)
+            .map_err(|_| ClaimLibError::AbiDecode)?;
 
-        let (pubkey, evm_address, amount, challenge) = decoded;
+        let (pubkey, evm_address, amount, challenge, scheme) = decoded;
 
         Ok(Self {
             pubkey: pubkey.0,
             evm_address: evm_address.0 .0,
             amount: amount.to_be_bytes(),
             challenge: challenge.0,
+            scheme: u8::try_from(scheme)
+                .ok()
+                .and_then(SignatureScheme::from_u8)
+                .ok_or(ClaimLibError::UnknownSignatureScheme)?,
         })
     }
 }
 
+/// Prefix an on-chain proof with its signature scheme byte
+///
+/// This is the `proof` argument of `SP1ZKVerifier.verifyProof`, which reads the scheme
+/// from the first byte to rebuild the public values and passes the rest to the SP1
+/// verifier gateway.
+pub fn encode_zk_proof(scheme: SignatureScheme, proof: &[u8]) -> Vec<u8> {
+    let mut zk_proof = Vec::with_capacity(proof.len() + 1);
+    zk_proof.push(scheme as u8);
+    zk_proof.extend_from_slice(proof);
+    zk_proof
+}
+
+/// Split a proof built by [`encode_zk_proof`] into its scheme and the SP1 proof bytes
+pub fn decode_zk_proof(zk_proof: &[u8]) -> Result<(SignatureScheme, &[u8]), ClaimLibError> {
+    let (&scheme, proof) = zk_proof.split_first().ok_or(ClaimLibError::EmptyProof)?;
+    let scheme = SignatureScheme::from_u8(scheme).ok_or(ClaimLibError::UnknownSignatureScheme)?;
+    Ok((scheme, proof))
+}
+
 /// Decode an SS58 address to extract the 32-byte public key
 ///
+/// Accepts any valid network prefix; see [`ss58_decode_with_prefix`] to also get
+/// the network id.
+pub fn ss58_decode(address: &str) -> Result<[u8; 32], ClaimLibError> {
+    ss58_decode_with_prefix(address).map(|(pubkey, _)| pubkey)
+}
+
+/// Decode an SS58 address to its 32-byte public key and network id
+///
 /// SS58 format:
-/// - 1 or 2 byte network prefix
+/// - 1 byte network prefix for ids 0-63, 2 bytes for ids 64-16383
 /// - 32 byte public key
 /// - 2 byte checksum (blake2b of SS58PRE || prefix || pubkey)
 ///
-/// Returns the 32-byte public key if valid, error otherwise
-pub fn ss58_decode(address: &str) -> Result<[u8; 32], &'static str> {
+/// Reserved network ids and two-byte encodings of ids below 64 are rejected.
+pub fn ss58_decode_with_prefix(address: &str) -> Result<([u8; 32], u16), ClaimLibError> {
     // Base58 decode
     let decoded = bs58::decode(address)
         .into_vec()
-        .map_err(|_| "Invalid base58 encoding")?;
+        .map_err(|_| ClaimLibError::InvalidBase58)?;
 
-    if decoded.len() < 35 {
-        return Err("SS58 address too short");
+    if decoded.is_empty() {
+        return Err(ClaimLibError::InvalidLength(0));
     }
 
     // Determine prefix length (1 or 2 bytes)
-    let (prefix_len, _network_id) = if decoded[0] < 64 {
+    let (prefix_len, network_id) = if decoded[0] < 64 {
         // Single byte prefix (0-63)
         (1, decoded[0] as u16)
     } else if decoded[0] < 128 {
         // Two byte prefix (64-16383)
-        if decoded.len() < 36 {
-            return Err("SS58 address too short for two-byte prefix");
+        if decoded.len() < 2 {
+            return Err(ClaimLibError::InvalidLength(decoded.len()));
         }
         let lower = ((decoded[0] & 0x3f) as u16) << 2;
         let upper = (decoded[1] as u16) >> 6;
-        (2, lower | upper | ((decoded[1] as u16 & 0x3f) << 8))
+        let network_id = lower | upper | ((decoded[1] as u16 & 0x3f) << 8);
+        if network_id < 64 {
+            return Err(ClaimLibError::NonCanonicalPrefix(network_id));
+        }
+        (2, network_id)
     } else {
-        return Err("Invalid SS58 prefix");
+        return Err(ClaimLibError::InvalidPrefix(decoded[0]));
     };
 
-    // Extract public key (32 bytes after prefix)
-    let pubkey_start = prefix_len;
-    let pubkey_end = pubkey_start + 32;
-
-    if decoded.len() < pubkey_end + 2 {
-        return Err("SS58 address missing checksum");
+    // Prefix, 32-byte public key and 2-byte checksum, nothing more
+    let pubkey_end = prefix_len + 32;
+    if decoded.len() != pubkey_end + 2 {
+        return Err(ClaimLibError::InvalidLength(decoded.len()));
+    }
+    if is_reserved_prefix(network_id) {
+        return Err(ClaimLibError::ReservedPrefix(network_id));
     }
 
-    let pubkey_slice = &decoded[pubkey_start..pubkey_end];
-    let checksum = &decoded[pubkey_end..pubkey_end + 2];
+    let pubkey_slice = &decoded[prefix_len..pubkey_end];
+    let checksum = &decoded[pubkey_end..];
 
     // Verify checksum
-    let mut hasher = Blake2b512::new();
-    hasher.update(SS58_PREFIX);
-    hasher.update(&decoded[..pubkey_end]);
-    let hash = hasher.finalize();
-
-    if checksum != &hash[..2] {
-        return Err("Invalid SS58 checksum");
+    if checksum != &ss58_checksum(&decoded[..pubkey_end])[..] {
+        return Err(ClaimLibError::InvalidChecksum);
     }
 
     // Copy to fixed array
     let mut pubkey = [0u8; 32];
     pubkey.copy_from_slice(pubkey_slice);
 
-    Ok(pubkey)
+    Ok((pubkey, network_id))
 }
 
 /// Encode a 32-byte public key to SS58 format with the given network prefix
-pub fn ss58_encode(pubkey: &[u8; 32], network_id: u16) -> String {
+pub fn ss58_encode(pubkey: &[u8; 32], network_id: u16) -> Result<String, ClaimLibError> {
     let mut data = Vec::with_capacity(37);
 
     // Encode network prefix
     if network_id < 64 {
         data.push(network_id as u8);
-    } else if network_id < 16384 {
+    } else if network_id < SS58_MAX_NETWORK_ID {
         let first = ((network_id & 0xfc) >> 2) as u8 | 0x40;
         let second = ((network_id >> 8) as u8) | ((network_id & 0x03) as u8) << 6;
         data.push(first);
         data.push(second);
     } else {
-        panic!("Network ID too large");
+        return Err(ClaimLibError::NetworkIdTooLarge(network_id));
+    }
+    if is_reserved_prefix(network_id) {
+        return Err(ClaimLibError::ReservedPrefix(network_id));
     }
 
     // Add public key
     data.extend_from_slice(pubkey);
 
-    // Calculate checksum
-    let mut hasher = Blake2b512::new();
-    hasher.update(SS58_PREFIX);
-    hasher.update(&data);
-    let hash = hasher.finalize();
-
     // Append 2-byte checksum
-    data.extend_from_slice(&hash[..2]);
+    let checksum = ss58_checksum(&data);
+    data.extend_from_slice(&checksum);
 
     // Base58 encode
-    bs58::encode(data).into_string()
+    Ok(bs58::encode(data).into_string())
+}
+
+/// Whether a network id is reserved by the SS58 registry and never used for accounts
+pub fn is_reserved_prefix(network_id: u16) -> bool {
+    SS58_RESERVED_PREFIXES.contains(&network_id)
+}
+
+/// First two bytes of blake2b-512(SS58PRE || prefix || pubkey)
+fn ss58_checksum(data: &[u8]) -> [u8; 2] {
+    let mut hasher = Blake2b512::new();
+    hasher.update(SS58_PREFIX);
+    hasher.update(data);
+    let hash = hasher.finalize();
+    [hash[0], hash[1]]
 }
 
 #[cfg(test)]
@@ -190,6 +505,7 @@ mod tests {
                 arr
             },
             challenge: [0x99; 32],
+            scheme: SignatureScheme::Ecdsa,
         };
 
         let encoded = values.abi_encode();
@@ -198,6 +514,128 @@ mod tests {
         assert_eq!(decoded.pubkey, values.pubkey);
         assert_eq!(decoded.evm_address, values.evm_address);
         assert_eq!(decoded.amount, values.amount);
+        assert_eq!(decoded.scheme, SignatureScheme::Ecdsa);
+    }
+
+    #[test]
+    fn test_zk_proof_roundtrip() {
+        let zk_proof = encode_zk_proof(SignatureScheme::Ed25519, &[0xaa, 0xbb]);
+        assert_eq!(zk_proof, vec![1, 0xaa, 0xbb]);
+        assert_eq!(
+            decode_zk_proof(&zk_proof),
+            Ok((SignatureScheme::Ed25519, &[0xaa, 0xbb][..]))
+        );
+
+        // A mock proof is just the scheme byte
+        assert_eq!(decode_zk_proof(&[2]), Ok((SignatureScheme::Ecdsa, &[][..])));
+        assert_eq!(decode_zk_proof(&[]), Err(ClaimLibError::EmptyProof));
+        assert_eq!(
+            decode_zk_proof(&[3, 0xaa]),
+            Err(ClaimLibError::UnknownSignatureScheme)
+        );
+    }
+
+    #[test]
+    fn test_public_values_reject_unknown_scheme() {
+        let mut encoded = PublicValues {
+            pubkey: [0x42; 32],
+            evm_address: [0x12; 20],
+            amount: [0u8; 32],
+            challenge: [0x99; 32],
+            scheme: SignatureScheme::Sr25519,
+        }
+        .abi_encode();
+        encoded[159] = 3;
+        assert!(PublicValues::abi_decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_account_id() {
+        // Alice's ECDSA dev key (`subkey inspect --scheme ecdsa //Alice`)
+        let mut compressed = [0u8; 33];
+        hex::decode_to_slice(
+            "020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1",
+            &mut compressed,
+        )
+        .unwrap();
+        assert_eq!(
+            ss58_encode(&ecdsa_account_id(&compressed), 42).unwrap(),
+            "5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X"
+        );
+    }
+
+    #[test]
+    fn test_verify_signature_errors() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let message = MessageWrapping::Wrapped.message(&[0x42; 32]);
+        let key = SigningKey::from_bytes(&[0x07; 32]);
+        let account_id = key.verifying_key().to_bytes();
+        let signature = key.sign(&message).to_bytes();
+
+        assert_eq!(verify_ed25519(&account_id, &signature, &message), Ok(()));
+        assert_eq!(
+            verify_ed25519(&account_id, &signature, b"other message"),
+            Err(ClaimLibError::SignatureMismatch)
+        );
+        assert_eq!(
+            verify_ed25519(&account_id, &signature[..63], &message),
+            Err(ClaimLibError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_ecdsa(&account_id, &signature, &message),
+            Err(ClaimLibError::InvalidSignatureLength(64))
+        );
+
+        let ecdsa = k256::ecdsa::SigningKey::from_slice(&[0x07; 32]).unwrap();
+        let (sig, _) = ecdsa
+            .sign_prehash_recoverable(&blake2_256(&message))
+            .unwrap();
+        let mut signature = sig.to_bytes().to_vec();
+        signature.push(5);
+        assert_eq!(
+            verify_ecdsa(&account_id, &signature, &message),
+            Err(ClaimLibError::InvalidRecoveryId)
+        );
+    }
+
+    #[test]
+    fn test_compute_claim_challenge() {
+        let contract = [0x11; 20];
+        let evm_address = [0x22; 20];
+        let mut amount = [0u8; 32];
+        amount[31] = 100;
+
+        // keccak256(abi.encode(address(0x11..), 31337, address(0x22..), 100))
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(&[0u8; 12]);
+        encoded.extend_from_slice(&contract);
+        encoded.extend_from_slice(&U256::from(31337u64).to_be_bytes::<32>());
+        encoded.extend_from_slice(&[0u8; 12]);
+        encoded.extend_from_slice(&evm_address);
+        encoded.extend_from_slice(&amount);
+        let expected = alloy_primitives::keccak256(&encoded).0;
+
+        let challenge = compute_claim_challenge(contract, 31337, evm_address, amount);
+        assert_eq!(challenge, expected);
+        assert_ne!(
+            challenge,
+            compute_claim_challenge(contract, 1, evm_address, amount),
+            "The chain id is bound into the challenge"
+        );
+    }
+
+    #[test]
+    fn test_message_wrapping() {
+        let challenge = [0x42u8; 32];
+
+        let wrapped = MessageWrapping::Wrapped.message(&challenge);
+        assert_eq!(wrapped.len(), 7 + 32 + 8);
+        assert!(wrapped.starts_with(b"<Bytes>"));
+        assert!(wrapped.ends_with(b"</Bytes>"));
+        assert_eq!(&wrapped[7..39], &challenge);
+
+        assert_eq!(MessageWrapping::Raw.message(&challenge), challenge.to_vec());
     }
 
     #[test]
@@ -209,7 +647,7 @@ mod tests {
         let pubkey = ss58_decode(address).expect("Failed to decode SS58");
 
         // Tangle network prefix is 5845
-        let re_encoded = ss58_encode(&pubkey, 5845);
+        let re_encoded = ss58_encode(&pubkey, 5845).unwrap();
 
         assert_eq!(address, re_encoded);
     }
@@ -230,7 +668,7 @@ mod tests {
         assert_eq!(pubkey.len(), 32);
 
         // Re-encode with prefix 42
-        let re_encoded = ss58_encode(&pubkey, 42);
+        let re_encoded = ss58_encode(&pubkey, 42).unwrap();
         assert_eq!(address, re_encoded);
     }
 
@@ -239,7 +677,7 @@ mod tests {
         // Take a valid address and modify a character
         let address = "tgFbShs5bUXZ8bcFfHkRm5vDbUQbUR3QQNhQktuK2mCW19qCX"; // Changed last char
         let result = ss58_decode(address);
-        assert!(result.is_err());
+        assert_eq!(result, Err(ClaimLibError::InvalidChecksum));
     }
 
     #[test]
@@ -247,7 +685,7 @@ mod tests {
         // Invalid base58 characters (0, O, I, l)
         let address = "0invalid";
         let result = ss58_decode(address);
-        assert!(result.is_err());
+        assert_eq!(result, Err(ClaimLibError::InvalidBase58));
     }
 
     #[test]
@@ -257,11 +695,68 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Base58 of prefix bytes, public key and a valid checksum, bypassing encoder checks
+    fn raw_ss58(prefix: &[u8], pubkey: &[u8; 32]) -> String {
+        let data = [prefix, &pubkey[..]].concat();
+        let checksum = ss58_checksum(&data);
+        bs58::encode([&data[..], &checksum[..]].concat()).into_string()
+    }
+
+    #[test]
+    fn test_ss58_decode_with_prefix() {
+        let pubkey = [0x42u8; 32];
+        for network_id in [0, 2, 42, 63, 64, 5845, 16383] {
+            let address = ss58_encode(&pubkey, network_id).unwrap();
+            assert_eq!(
+                ss58_decode_with_prefix(&address).unwrap(),
+                (pubkey, network_id)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ss58_prefix_rules() {
+        let pubkey = [0x42u8; 32];
+
+        assert_eq!(
+            ss58_encode(&pubkey, 46),
+            Err(ClaimLibError::ReservedPrefix(46))
+        );
+        assert_eq!(
+            ss58_encode(&pubkey, 16384),
+            Err(ClaimLibError::NetworkIdTooLarge(16384))
+        );
+        assert_eq!(
+            ss58_decode(&raw_ss58(&[47], &pubkey)),
+            Err(ClaimLibError::ReservedPrefix(47))
+        );
+        // 42 encoded in the two-byte form
+        assert_eq!(
+            ss58_decode(&raw_ss58(&[0x4a, 0x80], &pubkey)),
+            Err(ClaimLibError::NonCanonicalPrefix(42))
+        );
+        assert_eq!(
+            ss58_decode(&raw_ss58(&[0x80], &pubkey)),
+            Err(ClaimLibError::InvalidPrefix(0x80))
+        );
+        // Trailing bytes after the checksum
+        let mut long = bs58::decode(ss58_encode(&pubkey, 42).unwrap())
+            .into_vec()
+            .unwrap();
+        long.push(0);
+        assert_eq!(
+            ss58_decode(&bs58::encode(long).into_string()),
+            Err(ClaimLibError::InvalidLength(36))
+        );
+    }
+
     #[test]
     fn test_program_input_serialization() {
         let input = ProgramInput {
             substrate_address: "tgFbShs5bUXZ8bcFfHkRm5vDbUQbUR3QQNhQktuK2mCW19qCR".to_string(),
-            signature: [0xAB; 64],
+            scheme: SignatureScheme::Ecdsa,
+            signature: vec![0xAB; 65],
+            wrapping: MessageWrapping::Raw,
             evm_address: [0x12; 20],
             amount: {
                 let mut arr = [0u8; 32];
@@ -276,6 +771,8 @@ mod tests {
         let deserialized: ProgramInput = bincode::deserialize(&serialized).expect("Failed to deserialize");
 
         assert_eq!(input.substrate_address, deserialized.substrate_address);
+        assert_eq!(input.scheme, deserialized.scheme);
+        assert_eq!(input.wrapping, deserialized.wrapping);
         assert_eq!(input.signature, deserialized.signature);
         assert_eq!(input.evm_address, deserialized.evm_address);
         assert_eq!(input.amount, deserialized.amount);
@@ -294,12 +791,18 @@ mod tests {
                 arr
             },
             challenge: [0x42; 32],
+            scheme: SignatureScheme::Ed25519,
         };
 
         let encoded = values.abi_encode();
 
-        // ABI encoding should be exactly 128 bytes (4 words): bytes32 + address (padded) + uint256 + bytes32
-        assert_eq!(encoded.len(), 128, "ABI encoding should be exactly 128 bytes");
+        // ABI encoding should be exactly 160 bytes (5 words): bytes32 + address (padded) + uint256 + bytes32 + uint8 (padded)
+        assert_eq!(
+            encoded.len(),
+            160,
+            "ABI encoding should be exactly 160 bytes"
+        );
+        assert_eq!(encoded[159], 1);
 
         // Decode and verify
         let decoded = PublicValues::abi_decode(&encoded).unwrap();
@@ -307,6 +810,7 @@ mod tests {
         assert_eq!(decoded.evm_address, [0x01; 20]);
         assert_eq!(decoded.amount[31], 1);
         assert_eq!(decoded.challenge, [0x42; 32]);
+        assert_eq!(decoded.scheme, SignatureScheme::Ed25519);
     }
 
     #[test]
@@ -315,22 +819,22 @@ mod tests {
         let test_pubkey = [0x42u8; 32];
 
         // Polkadot (0)
-        let polkadot_addr = ss58_encode(&test_pubkey, 0);
+        let polkadot_addr = ss58_encode(&test_pubkey, 0).unwrap();
         let decoded = ss58_decode(&polkadot_addr).unwrap();
         assert_eq!(decoded, test_pubkey);
 
         // Kusama (2)
-        let kusama_addr = ss58_encode(&test_pubkey, 2);
+        let kusama_addr = ss58_encode(&test_pubkey, 2).unwrap();
         let decoded = ss58_decode(&kusama_addr).unwrap();
         assert_eq!(decoded, test_pubkey);
 
         // Generic Substrate (42)
-        let substrate_addr = ss58_encode(&test_pubkey, 42);
+        let substrate_addr = ss58_encode(&test_pubkey, 42).unwrap();
         let decoded = ss58_decode(&substrate_addr).unwrap();
         assert_eq!(decoded, test_pubkey);
 
         // Tangle (5845) - uses 2-byte prefix
-        let tangle_addr = ss58_encode(&test_pubkey, 5845);
+        let tangle_addr = ss58_encode(&test_pubkey, 5845).unwrap();
         let decoded = ss58_decode(&tangle_addr).unwrap();
         assert_eq!(decoded, test_pubkey);
     }
@@ -1,10 +1,15 @@
-//! SP1 Guest Program: SR25519 Signature Verification
+//! SP1 Guest Program: Substrate Signature Verification
 //!
 //! This program verifies that a user possesses the private key corresponding
-//! to a Substrate SR25519 public key by verifying a signature over a challenge.
+//! to a Substrate account by verifying a signature over a challenge. SR25519,
+//! Ed25519 and ECDSA (secp256k1) accounts are supported.
 //!
-//! Security: The public key is DERIVED from the SS58 address (not user-provided),
-//! ensuring the signature verification is bound to the claimed address.
+//! Security: The account id is DERIVED from the SS58 address (not user-provided),
+//! ensuring the signature verification is bound to the claimed address. For ECDSA
+//! the public key is recovered from the signature and must hash to the account id.
+//!
+//! The challenge is signed either wrapped in <Bytes>...</Bytes> (polkadot.js signRaw)
+//! or raw (subkey, CLI signers); the input says which, and only that framing verifies.
 //!
 //! The challenge includes:
 //! - The migration contract address
@@ -13,73 +18,56 @@
 //! - The claim amount
 //!
 //! Public outputs (committed on-chain):
-//! - The Substrate SS58 address
+//! - The Substrate account id
 //! - The EVM address
 //! - The claim amount
+//! - The challenge
+//! - The signature scheme
 
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use schnorrkel::{signing_context, PublicKey, Signature};
-use sr25519_claim_lib::{ss58_decode, ProgramInput, PublicValues};
-
-/// The signing context used by Substrate for SR25519 signatures
-/// This must match what the polkadot.js extension uses
-const SUBSTRATE_CONTEXT: &[u8] = b"substrate";
-
-/// Substrate wallet extensions wraps messages with <Bytes>...</Bytes> when signing
-/// with signRaw({ type: 'bytes' }). We must wrap the challenge the same way.
-const WRAP_PREFIX: &[u8] = b"<Bytes>";
-const WRAP_POSTFIX: &[u8] = b"</Bytes>";
+use sr25519_claim_lib::{
+    ss58_decode, verify_ecdsa, verify_ed25519, verify_sr25519, ProgramInput, PublicValues,
+    SignatureScheme,
+};
 
 pub fn main() {
     // Read the program inputs from the host
     let input: ProgramInput = sp1_zkvm::io::read();
 
-    // SECURITY: Derive the public key from the SS58 address
+    // SECURITY: Derive the account id from the SS58 address
     // This ensures we verify the signature against the address being claimed,
     // not a user-provided key that could be spoofed
-    let pubkey_bytes = ss58_decode(&input.substrate_address)
-        .expect("Failed to decode SS58 address");
-
-    // Parse the SR25519 public key from the decoded bytes
-    let public_key = PublicKey::from_bytes(&pubkey_bytes)
-        .expect("Failed to parse SR25519 public key from SS58");
+    let account_id = ss58_decode(&input.substrate_address).expect("Failed to decode SS58 address");
 
-    // Parse the signature
-    let signature = Signature::from_bytes(&input.signature)
-        .expect("Failed to parse SR25519 signature");
+    // Frame the challenge the way it was signed: wrapped with <Bytes>...</Bytes> as
+    // Substrate wallet extensions do when using signRaw with type: 'bytes', or raw
+    // as subkey and CLI signers do. Only the requested framing is accepted.
+    let message = input.wrapping.message(&input.challenge);
 
-    // Create the signing context (must match what was used to sign)
-    let ctx = signing_context(SUBSTRATE_CONTEXT);
-
-    // Wrap the challenge with <Bytes>...</Bytes> as Substrate Wallet extensions does
-    // when using signRaw with type: 'bytes'
-    let mut wrapped_challenge = Vec::with_capacity(
-        WRAP_PREFIX.len() + input.challenge.len() + WRAP_POSTFIX.len()
-    );
-    wrapped_challenge.extend_from_slice(WRAP_PREFIX);
-    wrapped_challenge.extend_from_slice(&input.challenge);
-    wrapped_challenge.extend_from_slice(WRAP_POSTFIX);
-
-    // Verify the signature over the WRAPPED challenge
+    // Verify the signature over the framed challenge
     // This is the core ZK computation - proving knowledge of the private key
     // that corresponds to the SS58 address
-    public_key
-        .verify(ctx.bytes(&wrapped_challenge), &signature)
-        .expect("SR25519 signature verification failed");
+    let verified = match input.scheme {
+        SignatureScheme::Sr25519 => verify_sr25519(&account_id, &input.signature, &message),
+        SignatureScheme::Ed25519 => verify_ed25519(&account_id, &input.signature, &message),
+        SignatureScheme::Ecdsa => verify_ecdsa(&account_id, &input.signature, &message),
+    };
+    verified.expect("Signature verification failed");
 
     // Create the public values to commit on-chain
     // These values will be verified by the TangleMigration contract
     let public_values = PublicValues {
-        pubkey: pubkey_bytes,  // The derived public key (32 bytes)
+        pubkey: account_id, // The derived account id (32 bytes)
         evm_address: input.evm_address,
         amount: input.amount,
         challenge: input.challenge,
+        scheme: input.scheme,
     };
 
     // Commit the public values as ABI-encoded bytes
-    // Format: abi.encode(bytes32 pubkey, address evmAddress, uint256 amount)
+    // Format: abi.encode(bytes32 pubkey, address evmAddress, uint256 amount, bytes32 challenge, uint8 scheme)
     // These will be visible on-chain and used by the TangleMigration contract
     sp1_zkvm::io::commit_slice(&public_values.abi_encode());
 }
@@ -0,0 +1,322 @@
+//! API key authentication and tiered quotas
+//!
+//! Keys are loaded from a JSON file at startup (`API_KEYS_FILE`). Each key carries
+//! its own quota, queue priority and batch size, separate from the anonymous IP
+//! limits. Settings can be shared through named tiers and overridden per key:
+//!
+//! ```json
+//! {
+//!   "tiers": {
+//!     "partner": { "rateLimitMaxRequests": 600, "rateLimitWindowSeconds": 60, "priority": 10 }
+//!   },
+//!   "keys": [
+//!     { "name": "wallet-a", "key": "sk_live_...", "tier": "partner" },
+//!     { "name": "exchange-b", "key": "sk_live_...", "tier": "partner", "maxBatchSize": 500 }
+//!   ]
+//! }
+//! ```
+
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+/// Header carrying the API key (alternative to `Authorization: Bearer <key>`)
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Defaults for settings left unset by both the key and its tier
+const DEFAULT_RATE_LIMIT_MAX_REQUESTS: u32 = 60;
+const DEFAULT_RATE_LIMIT_WINDOW_SECONDS: u64 = 60;
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+const DEFAULT_PRIORITY: u8 = 0;
+
+/// Resolved settings for a single API key
+#[derive(Debug, Clone)]
+pub struct ApiKeyConfig {
+    /// Human-readable identifier used in logs and rate limit keys (never the secret)
+    pub name: String,
+    /// The secret presented by the client
+    pub key: String,
+    /// Tier the settings were inherited from, if any
+    pub tier: Option<String>,
+    /// Maximum requests per window for this key
+    pub rate_limit_max_requests: u32,
+    /// Rate limit window in seconds for this key
+    pub rate_limit_window_seconds: u64,
+    /// Maximum number of claims in a single batch submission
+    pub max_batch_size: usize,
+    /// Queue priority for this key's jobs (higher runs first; anonymous jobs are 0)
+    pub priority: u8,
+}
+
+/// Quota settings of a tier, or per-key overrides of its tier
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuotaSettings {
+    rate_limit_max_requests: Option<u32>,
+    rate_limit_window_seconds: Option<u64>,
+    max_batch_size: Option<usize>,
+    priority: Option<u8>,
+}
+
+/// Key entry as written in the API keys file
+#[derive(Deserialize)]
+struct ApiKeyEntry {
+    name: String,
+    key: String,
+    tier: Option<String>,
+    #[serde(flatten)]
+    overrides: QuotaSettings,
+}
+
+/// JSON structure for parsing the API keys file
+#[derive(Deserialize)]
+struct ApiKeysFile {
+    #[serde(default)]
+    tiers: HashMap<String, QuotaSettings>,
+    keys: Vec<ApiKeyEntry>,
+}
+
+impl ApiKeysFile {
+    /// Resolve every key against its tier and the defaults
+    fn resolve(self) -> Result<Vec<ApiKeyConfig>, String> {
+        self.keys
+            .into_iter()
+            .map(|entry| {
+                let tier = match &entry.tier {
+                    Some(tier) => self.tiers.get(tier).cloned().ok_or_else(|| {
+                        format!(
+                            "API key '{}' references unknown tier '{}'",
+                            entry.name, tier
+                        )
+                    })?,
+                    None => QuotaSettings::default(),
+                };
+                let overrides = entry.overrides;
+
+                Ok(ApiKeyConfig {
+                    name: entry.name,
+                    key: entry.key,
+                    tier: entry.tier,
+                    rate_limit_max_requests: overrides
+                        .rate_limit_max_requests
+                        .or(tier.rate_limit_max_requests)
+                        .unwrap_or(DEFAULT_RATE_LIMIT_MAX_REQUESTS),
+                    rate_limit_window_seconds: overrides
+                        .rate_limit_window_seconds
+                        .or(tier.rate_limit_window_seconds)
+                        .unwrap_or(DEFAULT_RATE_LIMIT_WINDOW_SECONDS),
+                    max_batch_size: overrides
+                        .max_batch_size
+                        .or(tier.max_batch_size)
+                        .unwrap_or(DEFAULT_MAX_BATCH_SIZE),
+                    priority: overrides
+                        .priority
+                        .or(tier.priority)
+                        .unwrap_or(DEFAULT_PRIORITY),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Configured API keys, indexed by secret
+#[derive(Debug, Default)]
+pub struct ApiKeyStore {
+    keys: HashMap<String, ApiKeyConfig>,
+}
+
+impl ApiKeyStore {
+    /// Store with no keys (every authenticated endpoint rejects requests)
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Build a store from key configs, rejecting empty or duplicate keys and names
+    pub fn from_keys(keys: Vec<ApiKeyConfig>) -> Result<Self, String> {
+        let mut by_key = HashMap::with_capacity(keys.len());
+        let mut names = std::collections::HashSet::with_capacity(keys.len());
+
+        for config in keys {
+            if config.name.trim().is_empty() {
+                return Err("API key entry is missing a name".to_string());
+            }
+            if config.key.trim().is_empty() {
+                return Err(format!("API key '{}' has an empty key", config.name));
+            }
+            if config.max_batch_size == 0 {
+                return Err(format!("API key '{}' has maxBatchSize 0", config.name));
+            }
+            if !names.insert(config.name.clone()) {
+                return Err(format!("Duplicate API key name '{}'", config.name));
+            }
+            if by_key.contains_key(&config.key) {
+                return Err(format!("API key '{}' reuses another key", config.name));
+            }
+            by_key.insert(config.key.clone(), config);
+        }
+
+        Ok(Self { keys: by_key })
+    }
+
+    /// Load API keys from a JSON file
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read API keys file '{}': {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    /// Parse the contents of an API keys file
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let parsed: ApiKeysFile = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to parse API keys JSON: {}", e))?;
+        Self::from_keys(parsed.resolve()?)
+    }
+
+    /// Look up the settings for a presented key
+    pub fn authenticate(&self, key: &str) -> Option<&ApiKeyConfig> {
+        self.keys.get(key)
+    }
+
+    /// Configured keys, in no particular order
+    pub fn keys(&self) -> impl Iterator<Item = &ApiKeyConfig> {
+        self.keys.values()
+    }
+
+    /// Number of configured keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Extract an API key from `X-API-Key` or `Authorization: Bearer <key>`
+pub fn extract_api_key(headers: &HeaderMap) -> Option<String> {
+    if let Some(value) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Some(trimmed.to_string());
+        }
+    }
+
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().strip_prefix("Bearer "))
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+/// Generate rate limit key for an API key (by name, so secrets never sit in the map)
+pub fn rate_limit_key_api_key(config: &ApiKeyConfig) -> String {
+    format!("apikey:{}", config.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str, secret: &str) -> ApiKeyConfig {
+        ApiKeyConfig {
+            name: name.to_string(),
+            key: secret.to_string(),
+            tier: None,
+            rate_limit_max_requests: 10,
+            rate_limit_window_seconds: 60,
+            max_batch_size: 50,
+            priority: 0,
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let store = ApiKeyStore::from_keys(vec![key("exchange-a", "secret-a")]).unwrap();
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.authenticate("secret-a").unwrap().name, "exchange-a");
+        assert!(store.authenticate("secret-b").is_none());
+        assert!(ApiKeyStore::empty().authenticate("secret-a").is_none());
+    }
+
+    #[test]
+    fn test_rejects_duplicates() {
+        let err = ApiKeyStore::from_keys(vec![key("a", "s1"), key("a", "s2")]).unwrap_err();
+        assert!(err.contains("Duplicate API key name"));
+
+        let err = ApiKeyStore::from_keys(vec![key("a", "s1"), key("b", "s1")]).unwrap_err();
+        assert!(err.contains("reuses another key"));
+
+        let err = ApiKeyStore::from_keys(vec![key("a", " ")]).unwrap_err();
+        assert!(err.contains("empty key"));
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let store = ApiKeyStore::parse(r#"{"keys":[{"name":"wallet","key":"k"}]}"#).unwrap();
+        let config = store.authenticate("k").unwrap();
+        assert_eq!(config.rate_limit_max_requests, 60);
+        assert_eq!(config.rate_limit_window_seconds, 60);
+        assert_eq!(config.max_batch_size, 100);
+        assert_eq!(config.priority, 0);
+        assert!(config.tier.is_none());
+    }
+
+    #[test]
+    fn test_parse_tiers_with_overrides() {
+        let store = ApiKeyStore::parse(
+            r#"{
+                "tiers": {
+                    "partner": { "rateLimitMaxRequests": 600, "maxBatchSize": 200, "priority": 10 }
+                },
+                "keys": [
+                    { "name": "wallet", "key": "k1", "tier": "partner" },
+                    { "name": "exchange", "key": "k2", "tier": "partner", "maxBatchSize": 500 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let wallet = store.authenticate("k1").unwrap();
+        assert_eq!(wallet.tier.as_deref(), Some("partner"));
+        assert_eq!(wallet.rate_limit_max_requests, 600);
+        assert_eq!(wallet.rate_limit_window_seconds, 60); // default, tier leaves it unset
+        assert_eq!(wallet.max_batch_size, 200);
+        assert_eq!(wallet.priority, 10);
+
+        let exchange = store.authenticate("k2").unwrap();
+        assert_eq!(exchange.max_batch_size, 500);
+        assert_eq!(exchange.priority, 10);
+    }
+
+    #[test]
+    fn test_parse_unknown_tier() {
+        let err =
+            ApiKeyStore::parse(r#"{"keys":[{"name":"w","key":"k","tier":"gold"}]}"#).unwrap_err();
+        assert!(err.contains("unknown tier 'gold'"));
+    }
+
+    #[test]
+    fn test_extract_api_key() {
+        let mut headers = HeaderMap::new();
+        assert!(extract_api_key(&headers).is_none());
+
+        headers.insert("authorization", "Bearer abc123".parse().unwrap());
+        assert_eq!(extract_api_key(&headers).as_deref(), Some("abc123"));
+
+        // X-API-Key takes precedence
+        headers.insert(API_KEY_HEADER, "xyz".parse().unwrap());
+        assert_eq!(extract_api_key(&headers).as_deref(), Some("xyz"));
+
+        let mut basic = HeaderMap::new();
+        basic.insert("authorization", "Basic abc123".parse().unwrap());
+        assert!(extract_api_key(&basic).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_key_uses_name() {
+        let config = key("exchange-a", "secret-a");
+        assert_eq!(rate_limit_key_api_key(&config), "apikey:exchange-a");
+    }
+}
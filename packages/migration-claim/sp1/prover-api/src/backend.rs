@@ -0,0 +1,721 @@
+//! Proving backends
+//!
+//! The backend is chosen once at startup from `SP1_PROVER` and prepared per worker,
+//! so every worker reuses its own proving key for all of its jobs:
+//!
+//! - `mock`: SP1 mock prover (no real proof, for testing)
+//! - `local`: SP1 CPU prover
+//! - `remote`: an external prover service speaking the HTTP protocol below
+//!
+//! # Remote prover protocol
+//!
+//! `POST {url}/proofs` submits a job and returns `{ "id": "..." }`. `proofSystem` is
+//! `groth16`, `plonk`, `compressed` or `core`:
+//!
+//! ```json
+//! { "program": "sr25519-claim-program", "vkey": "0x...", "proofSystem": "groth16", "stdin": ["0x..."] }
+//! ```
+//!
+//! `vkey` is the embedded program's verification key (as the on-chain verifier checks
+//! it), so the service can refuse to prove with a different build of the program.
+//! `stdin` holds the SP1 stdin buffers in order. `GET {url}/proofs/{id}` is then polled
+//! until it reports `completed` or `failed`:
+//!
+//! ```json
+//! { "status": "completed", "proof": "0x...", "publicValues": "0x..." }
+//! { "status": "failed", "error": "..." }
+//! ```
+//!
+//! `proof` has the format of [`ProofOutput::proof`]. With `VERIFY_PROOF=true` it is
+//! verified against the embedded program's vkey before it is accepted.
+//!
+//! Requests carry `Authorization: Bearer <key>` when `REMOTE_PROVER_API_KEY` is set.
+
+use serde::{Deserialize, Serialize};
+use sp1_sdk::blocking::{CpuProver, LightProver, MockProver, ProveRequest as _, Prover, SP1Stdin};
+use sp1_sdk::{HashableKey, ProvingKey, SP1ProofWithPublicValues, SP1VerifyingKey};
+use sp1_verifier::{Groth16Verifier, PlonkVerifier, GROTH16_VK_BYTES, PLONK_VK_BYTES};
+use sr25519_claim_lib::{
+    ss58_decode, MessageWrapping, ProgramInput, PublicValues, SignatureScheme,
+};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::prover::{err_to_string, ELF};
+use crate::retry::{ErrorClass, ProveError};
+use crate::types::{
+    ProofMetrics, ProofSystem, ProverBackendConfig, RemoteProverConfig, VerifyOnchainConfig,
+};
+
+/// Program name sent to remote provers
+const PROGRAM_NAME: &str = "sr25519-claim-program";
+
+/// Proof produced by a backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofOutput {
+    /// Proof bytes: the on-chain verifier format for Groth16 and PLONK, and the
+    /// bincode-encoded `SP1ProofWithPublicValues` for compressed and core proofs
+    pub proof: Vec<u8>,
+    /// Public values committed by the program
+    pub public_values: Vec<u8>,
+}
+
+/// A prover ready to prove claims
+///
+/// Implementations are blocking and are called from `spawn_blocking`.
+pub trait ProverBackend: Send + Sync {
+    fn prove(
+        &self,
+        input: &ProgramInput,
+        proof_system: ProofSystem,
+    ) -> Result<ProofOutput, ProveError>;
+}
+
+/// Prepare one backend per worker
+///
+/// SP1 backends share one prover client and set up a proving key per worker. This
+/// blocks (and must not run on the async runtime) while the keys are set up. Each
+/// setup is recorded in `metrics`. Remote backends check proofs against `program_vkey`.
+pub fn prepare_backends(
+    config: &ProverBackendConfig,
+    program_vkey: [u8; 32],
+    verify_proof: bool,
+    count: usize,
+    metrics: &ProofMetrics,
+) -> Result<Vec<Arc<dyn ProverBackend>>, String> {
+    let timed = |prepare: &dyn Fn() -> Result<Arc<dyn ProverBackend>, String>| {
+        let started = Instant::now();
+        let backend = prepare()?;
+        metrics.record_setup_duration(config.mode(), started.elapsed());
+        Ok(backend)
+    };
+    match config {
+        ProverBackendConfig::Mock => {
+            let client = MockProver::new();
+            (0..count)
+                .map(|_| {
+                    timed(&|| Sp1Backend::prepare(client.clone(), verify_proof).map(into_shared))
+                })
+                .collect()
+        }
+        ProverBackendConfig::Local => {
+            let client = CpuProver::new();
+            (0..count)
+                .map(|_| {
+                    timed(&|| Sp1Backend::prepare(client.clone(), verify_proof).map(into_shared))
+                })
+                .collect()
+        }
+        ProverBackendConfig::Remote(remote) => (0..count)
+            .map(|_| {
+                timed(&|| {
+                    RemoteBackend::new(remote.clone(), program_vkey, verify_proof).map(into_shared)
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Canned claim for the startup self-test, signed by a fixed test key
+fn self_test_input() -> ProgramInput {
+    let signature = hex::decode(
+        "989855e783e86dc2122e9b4602a88b7cefb9708339ebe18fd8df06e646af515f\
+         1d64ec2414dc4887e5e8196a8f07108f6a2891aec61c1e8d94b789e7c763e88c",
+    )
+    .expect("valid signature hex");
+    let mut amount = [0u8; 32];
+    amount[31] = 1;
+    ProgramInput {
+        substrate_address: "5HY6kuXimmLcHxCVXPq33g5DNRLu1puhAKJUr8YheRZEQRBc".to_string(),
+        scheme: SignatureScheme::Sr25519,
+        signature,
+        wrapping: MessageWrapping::Wrapped,
+        evm_address: [0x11; 20],
+        amount,
+        challenge: [0x42; 32],
+    }
+}
+
+/// Prove and verify a canned claim with the mock prover
+///
+/// Executes the embedded program end to end, so a broken ELF or SDK setup fails at
+/// startup instead of on the first claim. Blocking; returns how long it took.
+pub fn self_test() -> Result<Duration, String> {
+    let started = Instant::now();
+    let input = self_test_input();
+    let backend = Sp1Backend::prepare(MockProver::new(), true)?;
+    let output = backend
+        .prove(&input, ProofSystem::Groth16)
+        .map_err(err_to_string)?;
+    if output.public_values != expected_public_values(&input)? {
+        return Err("Self-test proof committed unexpected public values".to_string());
+    }
+    Ok(started.elapsed())
+}
+
+/// Verification key of the embedded program, as the on-chain verifier checks it
+///
+/// Sets up the program with the mock prover, so it needs no proving resources. Blocking.
+pub fn program_vkey() -> Result<[u8; 32], String> {
+    let pk = MockProver::new().setup(ELF).map_err(err_to_string)?;
+    Ok(pk.verifying_key().bytes32_raw())
+}
+
+/// Refuse an on-chain verification vkey that isn't the embedded program's
+///
+/// Proofs of the embedded program would all fail `verifyProof` against another vkey.
+pub fn check_program_vkey(
+    verify_onchain: Option<&VerifyOnchainConfig>,
+    program_vkey: &[u8; 32],
+) -> Result<(), String> {
+    match verify_onchain {
+        Some(config) if config.program_vkey != *program_vkey => Err(format!(
+            "SP1_PROGRAM_VKEY 0x{} does not match the embedded program's vkey 0x{}",
+            hex::encode(config.program_vkey),
+            hex::encode(program_vkey)
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn into_shared(backend: impl ProverBackend + 'static) -> Arc<dyn ProverBackend> {
+    Arc::new(backend)
+}
+
+/// SP1 SDK prover with a proving key set up for the claim program
+pub struct Sp1Backend<P: Prover> {
+    client: P,
+    pk: P::ProvingKey,
+    verify_proof: bool,
+}
+
+impl<P: Prover> Sp1Backend<P> {
+    /// Set up the proving key for the claim program
+    pub fn prepare(client: P, verify_proof: bool) -> Result<Self, String> {
+        let pk = client.setup(ELF).map_err(err_to_string)?;
+        Ok(Self {
+            client,
+            pk,
+            verify_proof,
+        })
+    }
+}
+
+impl<P> ProverBackend for Sp1Backend<P>
+where
+    P: Prover,
+    P::ProvingKey: Send + Sync,
+{
+    fn prove(
+        &self,
+        input: &ProgramInput,
+        proof_system: ProofSystem,
+    ) -> Result<ProofOutput, ProveError> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(input);
+
+        let request = self.client.prove(&self.pk, stdin);
+        let request = match proof_system {
+            ProofSystem::Groth16 => request.groth16(),
+            ProofSystem::Plonk => request.plonk(),
+            ProofSystem::Compressed => request.compressed(),
+            ProofSystem::Core => request.core(),
+        };
+        let proof = request.run().map_err(err_to_string)?;
+        if self.verify_proof {
+            self.client
+                .verify(&proof, self.pk.verifying_key(), None)
+                .map_err(err_to_string)?;
+        }
+
+        // `bytes()` only exists for proofs the on-chain verifier accepts
+        let proof_bytes = if proof_system.is_onchain() {
+            proof.bytes()
+        } else {
+            bincode::serialize(&proof).map_err(err_to_string)?
+        };
+        Ok(ProofOutput {
+            proof: proof_bytes,
+            public_values: proof.public_values.to_vec(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteProveRequest<'a> {
+    program: &'a str,
+    vkey: String,
+    proof_system: &'a str,
+    stdin: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RemoteSubmitResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteStatusResponse {
+    status: String,
+    proof: Option<String>,
+    public_values: Option<String>,
+    error: Option<String>,
+}
+
+/// External prover service speaking the remote prover protocol
+///
+/// The service is not trusted to prove the right claim: the public values it returns
+/// must match the ones expected for the input, and with `verify_proof` the proof must
+/// verify against the embedded program's vkey.
+pub struct RemoteBackend {
+    config: RemoteProverConfig,
+    client: reqwest::blocking::Client,
+    program_vkey: [u8; 32],
+    verify_proof: bool,
+    /// Verifier for compressed and core proofs, set up on first use
+    light: OnceLock<Result<(LightProver, SP1VerifyingKey), String>>,
+}
+
+impl RemoteBackend {
+    pub fn new(
+        config: RemoteProverConfig,
+        program_vkey: [u8; 32],
+        verify_proof: bool,
+    ) -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(config.request_timeout_seconds))
+            .build()
+            .map_err(err_to_string)?;
+        Ok(Self {
+            config,
+            client,
+            program_vkey,
+            verify_proof,
+            light: OnceLock::new(),
+        })
+    }
+
+    fn vkey(&self) -> String {
+        format!("0x{}", hex::encode(self.program_vkey))
+    }
+
+    /// Verify a returned proof against the embedded program
+    ///
+    /// Groth16 and PLONK proofs come in the on-chain verifier format and are checked
+    /// like the gateway would. Compressed and core proofs are full SP1 proofs.
+    fn verify(
+        &self,
+        proof_system: ProofSystem,
+        proof: &[u8],
+        public_values: &[u8],
+    ) -> Result<(), String> {
+        match proof_system {
+            ProofSystem::Groth16 => {
+                Groth16Verifier::verify(proof, public_values, &self.vkey(), *GROTH16_VK_BYTES)
+                    .map_err(err_to_string)
+            }
+            ProofSystem::Plonk => {
+                PlonkVerifier::verify(proof, public_values, &self.vkey(), *PLONK_VK_BYTES)
+                    .map_err(err_to_string)
+            }
+            ProofSystem::Compressed | ProofSystem::Core => {
+                let proof: SP1ProofWithPublicValues =
+                    bincode::deserialize(proof).map_err(err_to_string)?;
+                if proof.public_values.to_vec() != public_values {
+                    return Err("proof commits different public values".to_string());
+                }
+                let (client, vk) = self
+                    .light
+                    .get_or_init(|| {
+                        let client = LightProver::new();
+                        let pk = client.setup(ELF).map_err(err_to_string)?;
+                        let vk = pk.verifying_key().clone();
+                        Ok((client, vk))
+                    })
+                    .as_ref()?;
+                client.verify(&proof, vk, None).map_err(err_to_string)
+            }
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.url.trim_end_matches('/'), path)
+    }
+
+    fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<T, ProveError> {
+        let request = match &self.config.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        let response = request
+            .send()
+            .map_err(|err| ProveError::transient(err.to_string()))?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(ProveError::transient(format!(
+                "Remote prover HTTP error {status}"
+            )));
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(ProveError::proof(format!(
+                "Remote prover rejected the request ({status}): {body}"
+            )));
+        }
+        response
+            .json()
+            .map_err(|err| ProveError::transient(err.to_string()))
+    }
+}
+
+impl ProverBackend for RemoteBackend {
+    fn prove(
+        &self,
+        input: &ProgramInput,
+        proof_system: ProofSystem,
+    ) -> Result<ProofOutput, ProveError> {
+        let expected_public_values = expected_public_values(input)?;
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(input);
+        let body = RemoteProveRequest {
+            program: PROGRAM_NAME,
+            vkey: self.vkey(),
+            proof_system: proof_system.as_str(),
+            stdin: stdin
+                .buffer
+                .iter()
+                .map(|chunk| format!("0x{}", hex::encode(chunk)))
+                .collect(),
+        };
+
+        let submitted: RemoteSubmitResponse =
+            self.send(self.client.post(self.url("/proofs")).json(&body))?;
+        let status_url = self.url(&format!("/proofs/{}", submitted.id));
+
+        let deadline = Instant::now() + Duration::from_secs(self.config.timeout_seconds);
+        loop {
+            let status: RemoteStatusResponse = self.send(self.client.get(&status_url))?;
+            match status.status.as_str() {
+                "completed" => {
+                    let proof = decode_hex_field(status.proof, "proof")?;
+                    let public_values = decode_hex_field(status.public_values, "publicValues")?;
+                    if public_values != expected_public_values {
+                        return Err(ProveError::proof(
+                            "Remote prover returned public values for a different claim",
+                        ));
+                    }
+                    if self.verify_proof {
+                        self.verify(proof_system, &proof, &public_values)
+                            .map_err(|e| {
+                                ProveError::proof(format!("Remote proof failed verification: {e}"))
+                            })?;
+                    }
+                    return Ok(ProofOutput {
+                        proof,
+                        public_values,
+                    });
+                }
+                "failed" => {
+                    return Err(ProveError::proof(format!(
+                        "Remote prover failed: {}",
+                        status.error.unwrap_or_else(|| "unknown error".to_string())
+                    )));
+                }
+                _ if Instant::now() >= deadline => {
+                    return Err(ProveError::new(
+                        ErrorClass::Timeout,
+                        format!(
+                            "Remote proof {} timed out after {} seconds",
+                            submitted.id, self.config.timeout_seconds
+                        ),
+                    ));
+                }
+                _ => std::thread::sleep(Duration::from_millis(self.config.poll_interval_ms)),
+            }
+        }
+    }
+}
+
+/// ABI-encoded public values the program commits for an input
+fn expected_public_values(input: &ProgramInput) -> Result<Vec<u8>, String> {
+    let pubkey = ss58_decode(&input.substrate_address).map_err(err_to_string)?;
+    Ok(PublicValues {
+        pubkey,
+        evm_address: input.evm_address,
+        amount: input.amount,
+        challenge: input.challenge,
+        scheme: input.scheme,
+    }
+    .abi_encode())
+}
+
+fn decode_hex_field(value: Option<String>, field: &str) -> Result<Vec<u8>, String> {
+    let value = value.ok_or_else(|| format!("Remote prover response is missing {field}"))?;
+    hex::decode(value.strip_prefix("0x").unwrap_or(&value))
+        .map_err(|e| format!("Remote prover returned invalid {field}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{
+        Method::{GET, POST},
+        MockServer,
+    };
+    use serde_json::json;
+
+    fn test_input() -> ProgramInput {
+        ProgramInput {
+            substrate_address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+            scheme: SignatureScheme::Sr25519,
+            signature: vec![0xab; 64],
+            wrapping: MessageWrapping::Wrapped,
+            evm_address: [0x44; 20],
+            amount: [0x55; 32],
+            challenge: [0x66; 32],
+        }
+    }
+
+    #[test]
+    fn test_self_test_input_is_signed() {
+        let input = self_test_input();
+        let pubkey = ss58_decode(&input.substrate_address).unwrap();
+        crate::signature::verify_signature(
+            input.scheme,
+            input.wrapping,
+            &pubkey,
+            &input.signature,
+            &input.challenge,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_prepare_backends_records_setup() {
+        let config = ProverBackendConfig::Remote(RemoteProverConfig {
+            url: "http://localhost:1".to_string(),
+            api_key: None,
+            poll_interval_ms: 10,
+            request_timeout_seconds: 5,
+            timeout_seconds: 1,
+        });
+        let metrics = ProofMetrics::new();
+
+        let backends = prepare_backends(&config, [0x22; 32], true, 2, &metrics).unwrap();
+
+        assert_eq!(backends.len(), 2);
+        assert_eq!(metrics.snapshot().setups["remote"].count, 2);
+    }
+
+    fn remote(server: &MockServer, api_key: Option<&str>) -> RemoteBackend {
+        RemoteBackend::new(
+            RemoteProverConfig {
+                url: server.base_url(),
+                api_key: api_key.map(str::to_string),
+                poll_interval_ms: 10,
+                request_timeout_seconds: 5,
+                timeout_seconds: 5,
+            },
+            [0x22; 32],
+            false,
+        )
+        .unwrap()
+    }
+
+    /// Serve a completed proof with the given proof bytes and public values
+    fn serve_proof(server: &MockServer, proof: &str, public_values: &[u8]) {
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(200).json_body(json!({"id": "p1"}));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/proofs/p1");
+            then.status(200).json_body(json!({
+                "status": "completed",
+                "proof": proof,
+                "publicValues": format!("0x{}", hex::encode(public_values)),
+            }));
+        });
+    }
+
+    #[test]
+    fn test_remote_backend_proves() {
+        let input = test_input();
+        let public_values = expected_public_values(&input).unwrap();
+
+        let server = MockServer::start();
+        let submit = server.mock(|when, then| {
+            when.method(POST)
+                .path("/proofs")
+                .header("authorization", "Bearer secret")
+                .json_body_partial(format!(
+                    r#"{{"program":"sr25519-claim-program","vkey":"0x{}","proofSystem":"plonk"}}"#,
+                    "22".repeat(32)
+                ));
+            then.status(202).json_body(json!({"id": "p1"}));
+        });
+        let status = server.mock(|when, then| {
+            when.method(GET).path("/proofs/p1");
+            then.status(200).json_body(json!({
+                "status": "completed",
+                "proof": "0xaabb",
+                "publicValues": format!("0x{}", hex::encode(&public_values)),
+            }));
+        });
+
+        let output = remote(&server, Some("secret"))
+            .prove(&input, ProofSystem::Plonk)
+            .unwrap();
+        assert_eq!(output.proof, vec![0xaa, 0xbb]);
+        assert_eq!(output.public_values, public_values);
+        submit.assert();
+        status.assert();
+    }
+
+    #[test]
+    fn test_remote_backend_rejects_foreign_public_values() {
+        let server = MockServer::start();
+        serve_proof(&server, "0xaabb", &[0x12, 0x34]);
+
+        let err = remote(&server, None)
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert!(err.message.contains("different claim"));
+    }
+
+    #[test]
+    fn test_remote_backend_verifies_proofs() {
+        let input = test_input();
+        let server = MockServer::start();
+        serve_proof(&server, "0xaabb", &expected_public_values(&input).unwrap());
+
+        for proof_system in [
+            ProofSystem::Groth16,
+            ProofSystem::Plonk,
+            ProofSystem::Compressed,
+        ] {
+            let mut backend = remote(&server, None);
+            assert!(backend.prove(&input, proof_system).is_ok());
+
+            backend.verify_proof = true;
+            let err = backend.prove(&input, proof_system).unwrap_err();
+            assert_eq!(err.class, ErrorClass::Proof);
+            assert!(err.message.starts_with("Remote proof failed verification"));
+        }
+    }
+
+    #[test]
+    fn test_remote_backend_reports_failures() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(200).json_body(json!({"id": "p1"}));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/proofs/p1");
+            then.status(200)
+                .json_body(json!({"status": "failed", "error": "out of cycles"}));
+        });
+
+        let err = remote(&server, None)
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ProveError::proof("Remote prover failed: out of cycles")
+        );
+    }
+
+    #[test]
+    fn test_remote_backend_http_errors() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(503);
+        });
+        let err = remote(&server, None)
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Transient);
+        assert!(err.message.contains("Remote prover HTTP error 503"));
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(401).body("bad key");
+        });
+        let err = remote(&server, None)
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Proof);
+        assert!(err
+            .message
+            .contains("rejected the request (401 Unauthorized): bad key"));
+    }
+
+    #[test]
+    fn test_remote_backend_times_out() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(200).json_body(json!({"id": "p1"}));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/proofs/p1");
+            then.status(200).json_body(json!({"status": "running"}));
+        });
+
+        let mut backend = remote(&server, None);
+        backend.config.timeout_seconds = 0;
+        let err = backend
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Timeout);
+        assert!(err.message.contains("timed out"));
+    }
+
+    #[test]
+    fn test_remote_backend_unreachable_is_transient() {
+        let backend = RemoteBackend::new(
+            RemoteProverConfig {
+                url: "http://127.0.0.1:1".to_string(),
+                api_key: None,
+                poll_interval_ms: 10,
+                request_timeout_seconds: 5,
+                timeout_seconds: 5,
+            },
+            [0x22; 32],
+            true,
+        )
+        .unwrap();
+
+        let err = backend
+            .prove(&test_input(), ProofSystem::Groth16)
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Transient);
+    }
+
+    #[test]
+    fn test_check_program_vkey() {
+        let config = VerifyOnchainConfig {
+            rpc_url: "http://localhost:8545".to_string(),
+            verifier_address: [0x11; 20],
+            program_vkey: [0x22; 32],
+            timeout_seconds: 5,
+        };
+
+        assert!(check_program_vkey(None, &[0x33; 32]).is_ok());
+        assert!(check_program_vkey(Some(&config), &[0x22; 32]).is_ok());
+
+        let err = check_program_vkey(Some(&config), &[0x33; 32]).unwrap_err();
+        assert!(err.contains(&format!("0x{}", "22".repeat(32))));
+        assert!(err.contains(&format!("embedded program's vkey 0x{}", "33".repeat(32))));
+    }
+}
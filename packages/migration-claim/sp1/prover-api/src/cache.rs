@@ -60,7 +60,7 @@ pub fn start_cache_cleanup_task(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::now_ts;
+    use crate::types::{now_ts, ProofSystem};
 
     #[tokio::test]
     async fn test_cache_get() {
@@ -71,7 +71,11 @@ mod tests {
             let mut c = cache.lock().await;
             c.insert(
                 "key1".to_string(),
-                CachedProof::new("proof1".to_string(), "values1".to_string()),
+                CachedProof::new(
+                    "proof1".to_string(),
+                    "values1".to_string(),
+                    ProofSystem::Groth16,
+                ),
             );
         }
 
@@ -104,6 +108,7 @@ mod tests {
                 CachedProof {
                     zk_proof: "old_proof".to_string(),
                     public_values: "old_values".to_string(),
+                    proof_system: ProofSystem::Groth16,
                     created_at: now_ts() - 120, // 2 minutes ago
                 },
             );
@@ -125,13 +130,18 @@ mod tests {
             let mut c = cache.lock().await;
             c.insert(
                 "fresh".to_string(),
-                CachedProof::new("proof".to_string(), "values".to_string()),
+                CachedProof::new(
+                    "proof".to_string(),
+                    "values".to_string(),
+                    ProofSystem::Groth16,
+                ),
             );
             c.insert(
                 "expired1".to_string(),
                 CachedProof {
                     zk_proof: "old".to_string(),
                     public_values: "old".to_string(),
+                    proof_system: ProofSystem::Groth16,
                     created_at: now_ts() - 120,
                 },
             );
@@ -140,6 +150,7 @@ mod tests {
                 CachedProof {
                     zk_proof: "old".to_string(),
                     public_values: "old".to_string(),
+                    proof_system: ProofSystem::Groth16,
                     created_at: now_ts() - 200,
                 },
             );
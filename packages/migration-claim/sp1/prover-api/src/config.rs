@@ -1,7 +1,13 @@
 use std::env;
+use std::time::Duration;
 
-use crate::types::{AppConfig, ClaimContractConfig, VerifyOnchainConfig};
-use crate::validation::parse_hex_bytes;
+use crate::rate_limit::RateLimitStrategy;
+use crate::retry::{ErrorClass, RetryPolicy};
+use crate::types::{
+    AppConfig, ChallengeDomain, ClaimContractConfig, ProofSystem, ProverBackendConfig,
+    RemoteProverConfig, VerifyOnchainConfig,
+};
+use crate::validation::{parse_hex_bytes, PrefixPolicy};
 
 /// Load configuration from environment variables
 pub fn load_config() -> Result<AppConfig, String> {
@@ -86,6 +92,9 @@ pub fn load_config() -> Result<AppConfig, String> {
         Err(_) => None,
     };
 
+    let challenge_domain = parse_challenge_domain("CLAIM_CHAIN_ID", claim_contract.as_ref())?;
+    let prefix_policy = parse_prefix_policy("SS58_PREFIX_POLICY")?;
+
     // Parse numeric settings with defaults
     let cache_ttl_seconds = env::var("CACHE_TTL_SECONDS")
         .ok()
@@ -102,6 +111,8 @@ pub fn load_config() -> Result<AppConfig, String> {
         .and_then(|v| v.parse().ok())
         .unwrap_or(3);
 
+    let rate_limit_strategy = parse_rate_limit_strategy("RATE_LIMIT_STRATEGY")?;
+
     let queue_capacity = env::var("QUEUE_CAPACITY")
         .ok()
         .and_then(|v| v.parse().ok())
@@ -122,6 +133,11 @@ pub fn load_config() -> Result<AppConfig, String> {
         .and_then(|v| v.parse().ok())
         .unwrap_or(4096); // 4 KB
 
+    let max_batch_body_bytes = env::var("MAX_BATCH_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(262144); // 256 KB - room for a few hundred claims
+
     let jobs_ttl_seconds = env::var("JOBS_TTL_SECONDS")
         .ok()
         .and_then(|v| v.parse().ok())
@@ -138,10 +154,54 @@ pub fn load_config() -> Result<AppConfig, String> {
         .and_then(|v| v.parse().ok())
         .unwrap_or(10); // 10 requests per minute per IP
 
+    let ip_rate_limit_strategy = parse_rate_limit_strategy("IP_RATE_LIMIT_STRATEGY")?;
+
+    // Per-key quotas come from the API keys file; only the algorithm is global
+    let api_key_rate_limit_strategy = parse_rate_limit_strategy("API_KEY_RATE_LIMIT_STRATEGY")?;
+
+    // Claim deadline (unix timestamp) used to prioritize last-minute claims
+    let claim_deadline = match env::var("CLAIM_DEADLINE") {
+        Ok(value) if !value.trim().is_empty() => Some(
+            value
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("Invalid CLAIM_DEADLINE: {e}"))?,
+        ),
+        _ => None,
+    };
+
+    let deadline_priority_window_seconds = env::var("DEADLINE_PRIORITY_WINDOW_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(86400); // 1 day
+
+    let prover_backend = parse_prover_backend(&prover_mode, proof_timeout_seconds)?;
+    let proof_system = parse_proof_system("PROOF_SYSTEM")?;
+
+    // Retries per error class (RETRY_TRANSIENT_MAX_ATTEMPTS, RETRY_TRANSIENT_BACKOFF_SECONDS, ...)
+    let retry_policy = parse_retry_policy("RETRY");
+
     // Eligibility file path (default works for local dev, Dockerfile overrides for production)
     let eligibility_file =
         env::var("ELIGIBILITY_FILE").unwrap_or_else(|_| "../merkle-tree.json".to_string());
 
+    // API keys file (optional, enables authenticated batch submission)
+    let api_keys_file = env::var("API_KEYS_FILE")
+        .ok()
+        .filter(|path| !path.trim().is_empty());
+
+    // Anonymous access (enabled by default). Disabling it requires API keys.
+    let allow_anonymous = env::var("ALLOW_ANONYMOUS")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+
+    if !allow_anonymous && api_keys_file.is_none() {
+        return Err("ALLOW_ANONYMOUS=false requires API_KEYS_FILE".to_string());
+    }
+
+    // Startup self-test with the mock prover (enabled by default)
+    let self_test = env::var("SELF_TEST").map(|v| v != "false").unwrap_or(true);
+
     // Signature verification (enabled by default, can be disabled for testing)
     let verify_signatures = env::var("VERIFY_SIGNATURES")
         .map(|v| v != "false")
@@ -155,26 +215,159 @@ pub fn load_config() -> Result<AppConfig, String> {
 
     Ok(AppConfig {
         prover_mode,
+        prover_backend,
+        proof_system,
         verify_proof,
         verify_onchain: verify_onchain_config,
         claim_contract,
+        challenge_domain,
+        prefix_policy,
         cache_ttl_seconds,
         rate_limit_window_seconds,
         rate_limit_max_requests,
+        rate_limit_strategy,
         ip_rate_limit_window_seconds,
         ip_rate_limit_max_requests,
+        ip_rate_limit_strategy,
+        api_key_rate_limit_strategy,
         queue_capacity,
         worker_count,
         proof_timeout_seconds,
         rpc_timeout_seconds,
         max_body_bytes,
+        max_batch_body_bytes,
         jobs_ttl_seconds,
         eligibility_file,
+        api_keys_file,
+        allow_anonymous,
+        claim_deadline,
+        deadline_priority_window_seconds,
+        retry_policy,
+        self_test,
         verify_signatures,
         trust_proxy_headers,
     })
 }
 
+/// Parse a rate limit strategy from an environment variable (default: fixed window)
+fn parse_rate_limit_strategy(var: &str) -> Result<RateLimitStrategy, String> {
+    match env::var(var) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.parse().map_err(|e| format!("Invalid {var}: {e}"))
+        }
+        _ => Ok(RateLimitStrategy::default()),
+    }
+}
+
+/// Parse the SS58 prefix policy from an environment variable (default: any)
+fn parse_prefix_policy(var: &str) -> Result<PrefixPolicy, String> {
+    match env::var(var) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.parse().map_err(|e| format!("Invalid {var}: {e}"))
+        }
+        _ => Ok(PrefixPolicy::default()),
+    }
+}
+
+/// Parse the chain id that request challenges are bound to, together with the claim
+/// contract (disabled if unset)
+fn parse_challenge_domain(
+    var: &str,
+    claim_contract: Option<&ClaimContractConfig>,
+) -> Result<Option<ChallengeDomain>, String> {
+    let chain_id = match env::var(var) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid {var}: {e}"))?,
+        _ => return Ok(None),
+    };
+    let contract = claim_contract.ok_or(format!("{var} requires CLAIM_CONTRACT_ADDRESS"))?;
+    Ok(Some(ChallengeDomain {
+        contract_address: contract.contract_address,
+        chain_id,
+    }))
+}
+
+/// Parse a proof system from an environment variable (default: Groth16)
+fn parse_proof_system(var: &str) -> Result<ProofSystem, String> {
+    match env::var(var) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.parse().map_err(|e| format!("Invalid {var}: {e}"))
+        }
+        _ => Ok(ProofSystem::default()),
+    }
+}
+
+/// Select the proving backend for a `SP1_PROVER` mode
+///
+/// `remote` reads `REMOTE_PROVER_URL` (required), `REMOTE_PROVER_API_KEY`,
+/// `REMOTE_PROVER_POLL_INTERVAL_MS` and `REMOTE_PROVER_REQUEST_TIMEOUT_SECONDS`.
+/// Remote proofs are abandoned after the proof timeout.
+fn parse_prover_backend(
+    prover_mode: &str,
+    proof_timeout_seconds: u64,
+) -> Result<ProverBackendConfig, String> {
+    match prover_mode {
+        "mock" => Ok(ProverBackendConfig::Mock),
+        "local" => Ok(ProverBackendConfig::Local),
+        "remote" => {
+            let url = env::var("REMOTE_PROVER_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .ok_or("REMOTE_PROVER_URL is required when SP1_PROVER=remote")?;
+            let api_key = env::var("REMOTE_PROVER_API_KEY")
+                .ok()
+                .filter(|key| !key.trim().is_empty());
+            let poll_interval_ms = env::var("REMOTE_PROVER_POLL_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2000);
+            let request_timeout_seconds = env::var("REMOTE_PROVER_REQUEST_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30);
+
+            Ok(ProverBackendConfig::Remote(RemoteProverConfig {
+                url: url.trim().to_string(),
+                api_key,
+                poll_interval_ms,
+                request_timeout_seconds,
+                timeout_seconds: proof_timeout_seconds,
+            }))
+        }
+        other => Err(format!("Unsupported SP1_PROVER mode: {other}")),
+    }
+}
+
+/// Parse the retry policy from `<prefix>_<CLASS>_MAX_ATTEMPTS`,
+/// `<prefix>_<CLASS>_BACKOFF_SECONDS` and `<prefix>_MAX_BACKOFF_SECONDS`
+///
+/// Unset or invalid values keep the defaults; a max attempts of 0 is treated as 1.
+fn parse_retry_policy(prefix: &str) -> RetryPolicy {
+    let mut policy = RetryPolicy::default();
+    let read = |var: String| {
+        env::var(var)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    for class in ErrorClass::ALL {
+        let name = class.as_str().to_uppercase();
+        let rule = policy.rule_mut(class);
+        if let Some(attempts) = read(format!("{prefix}_{name}_MAX_ATTEMPTS")) {
+            rule.max_attempts = attempts.clamp(1, u32::MAX as u64) as u32;
+        }
+        if let Some(seconds) = read(format!("{prefix}_{name}_BACKOFF_SECONDS")) {
+            rule.backoff = Duration::from_secs(seconds);
+        }
+    }
+    if let Some(seconds) = read(format!("{prefix}_MAX_BACKOFF_SECONDS")) {
+        policy.max_backoff = Duration::from_secs(seconds);
+    }
+    policy
+}
+
 /// Validate CORS configuration
 pub fn validate_cors(prover_mode: &str) -> Result<Option<String>, String> {
     let cors_origins = env::var("CORS_ALLOWED_ORIGINS").ok();
@@ -239,10 +432,25 @@ mod tests {
         env::remove_var("VERIFY_ONCHAIN");
         env::remove_var("CORS_ALLOWED_ORIGINS");
         env::remove_var("CLAIM_CONTRACT_ADDRESS");
+        env::remove_var("CLAIM_CHAIN_ID");
+        env::remove_var("SS58_PREFIX_POLICY");
         env::remove_var("SP1_PROGRAM_VKEY");
         env::remove_var("CACHE_TTL_SECONDS");
         env::remove_var("RATE_LIMIT_WINDOW_SECONDS");
         env::remove_var("RATE_LIMIT_MAX_REQUESTS");
+        env::remove_var("RATE_LIMIT_STRATEGY");
+        env::remove_var("IP_RATE_LIMIT_STRATEGY");
+        env::remove_var("API_KEY_RATE_LIMIT_STRATEGY");
+        env::remove_var("API_KEYS_FILE");
+        env::remove_var("ALLOW_ANONYMOUS");
+        env::remove_var("CLAIM_DEADLINE");
+        env::remove_var("REMOTE_PROVER_URL");
+        env::remove_var("REMOTE_PROVER_API_KEY");
+        env::remove_var("SELF_TEST");
+        env::remove_var("PROOF_SYSTEM");
+        env::remove_var("RETRY_TRANSIENT_MAX_ATTEMPTS");
+        env::remove_var("RETRY_TRANSIENT_BACKOFF_SECONDS");
+        env::remove_var("RETRY_MAX_BACKOFF_SECONDS");
         env::remove_var("QUEUE_CAPACITY");
         env::remove_var("WORKER_COUNT");
         env::remove_var("PROOF_TIMEOUT_SECONDS");
@@ -276,6 +484,170 @@ mod tests {
         env::remove_var("CORS_ALLOWED_ORIGINS");
     }
 
+    #[test]
+    fn test_parse_rate_limit_strategy() {
+        // Each case uses its own variable name so tests can run in parallel
+        env::remove_var("TEST_STRATEGY_UNSET");
+        assert_eq!(
+            parse_rate_limit_strategy("TEST_STRATEGY_UNSET").unwrap(),
+            RateLimitStrategy::FixedWindow
+        );
+
+        env::set_var("TEST_STRATEGY_BUCKET", "token_bucket");
+        assert_eq!(
+            parse_rate_limit_strategy("TEST_STRATEGY_BUCKET").unwrap(),
+            RateLimitStrategy::TokenBucket
+        );
+
+        env::set_var("TEST_STRATEGY_INVALID", "leaky");
+        let err = parse_rate_limit_strategy("TEST_STRATEGY_INVALID").unwrap_err();
+        assert!(err.contains("TEST_STRATEGY_INVALID"));
+
+        env::remove_var("TEST_STRATEGY_BUCKET");
+        env::remove_var("TEST_STRATEGY_INVALID");
+    }
+
+    // These tests use the same env vars so must be run together to avoid race conditions
+    #[test]
+    fn test_parse_prover_backend() {
+        assert_eq!(
+            parse_prover_backend("mock", 600).unwrap(),
+            ProverBackendConfig::Mock
+        );
+        assert_eq!(
+            parse_prover_backend("local", 600).unwrap(),
+            ProverBackendConfig::Local
+        );
+        assert!(parse_prover_backend("gpu", 600)
+            .unwrap_err()
+            .contains("Unsupported"));
+
+        env::remove_var("REMOTE_PROVER_URL");
+        let err = parse_prover_backend("remote", 600).unwrap_err();
+        assert!(err.contains("REMOTE_PROVER_URL"));
+
+        env::set_var("REMOTE_PROVER_URL", " https://prover.example ");
+        env::set_var("REMOTE_PROVER_API_KEY", "secret");
+        let ProverBackendConfig::Remote(remote) = parse_prover_backend("remote", 900).unwrap()
+        else {
+            panic!("expected remote backend");
+        };
+        assert_eq!(remote.url, "https://prover.example");
+        assert_eq!(remote.api_key.as_deref(), Some("secret"));
+        assert_eq!(remote.poll_interval_ms, 2000);
+        assert_eq!(remote.timeout_seconds, 900);
+
+        env::remove_var("REMOTE_PROVER_URL");
+        env::remove_var("REMOTE_PROVER_API_KEY");
+    }
+
+    #[test]
+    fn test_parse_proof_system() {
+        // Uses its own variables so it can run in parallel with other tests
+        assert_eq!(
+            parse_proof_system("TEST_PROOF_SYSTEM_UNSET").unwrap(),
+            ProofSystem::Groth16
+        );
+
+        env::set_var("TEST_PROOF_SYSTEM_PLONK", " PLONK ");
+        assert_eq!(
+            parse_proof_system("TEST_PROOF_SYSTEM_PLONK").unwrap(),
+            ProofSystem::Plonk
+        );
+
+        env::set_var("TEST_PROOF_SYSTEM_INVALID", "stark");
+        let err = parse_proof_system("TEST_PROOF_SYSTEM_INVALID").unwrap_err();
+        assert!(err.contains("TEST_PROOF_SYSTEM_INVALID"));
+
+        env::remove_var("TEST_PROOF_SYSTEM_PLONK");
+        env::remove_var("TEST_PROOF_SYSTEM_INVALID");
+    }
+
+    #[test]
+    fn test_parse_prefix_policy() {
+        // Uses its own variables so it can run in parallel with other tests
+        assert_eq!(
+            parse_prefix_policy("TEST_PREFIX_POLICY_UNSET").unwrap(),
+            PrefixPolicy::Any
+        );
+
+        env::set_var("TEST_PREFIX_POLICY_LIST", "0, 2,5845");
+        assert_eq!(
+            parse_prefix_policy("TEST_PREFIX_POLICY_LIST").unwrap(),
+            PrefixPolicy::Allow(vec![0, 2, 5845])
+        );
+
+        env::set_var("TEST_PREFIX_POLICY_INVALID", "polkadot");
+        let err = parse_prefix_policy("TEST_PREFIX_POLICY_INVALID").unwrap_err();
+        assert!(err.contains("TEST_PREFIX_POLICY_INVALID"));
+
+        env::remove_var("TEST_PREFIX_POLICY_LIST");
+        env::remove_var("TEST_PREFIX_POLICY_INVALID");
+    }
+
+    #[test]
+    fn test_parse_challenge_domain() {
+        // Uses its own variables so it can run in parallel with other tests
+        let contract = ClaimContractConfig {
+            rpc_url: "http://localhost:8545".to_string(),
+            contract_address: [0x11; 20],
+        };
+        assert_eq!(
+            parse_challenge_domain("TEST_CHAIN_ID_UNSET", Some(&contract)).unwrap(),
+            None
+        );
+
+        env::set_var("TEST_CHAIN_ID_BASE", "8453");
+        assert_eq!(
+            parse_challenge_domain("TEST_CHAIN_ID_BASE", Some(&contract)).unwrap(),
+            Some(ChallengeDomain {
+                contract_address: [0x11; 20],
+                chain_id: 8453,
+            })
+        );
+        let err = parse_challenge_domain("TEST_CHAIN_ID_BASE", None).unwrap_err();
+        assert!(err.contains("CLAIM_CONTRACT_ADDRESS"));
+
+        env::set_var("TEST_CHAIN_ID_INVALID", "base");
+        let err = parse_challenge_domain("TEST_CHAIN_ID_INVALID", Some(&contract)).unwrap_err();
+        assert!(err.contains("TEST_CHAIN_ID_INVALID"));
+
+        env::remove_var("TEST_CHAIN_ID_BASE");
+        env::remove_var("TEST_CHAIN_ID_INVALID");
+    }
+
+    #[test]
+    fn test_parse_retry_policy() {
+        // Uses its own prefix so it can run in parallel with other tests
+        assert_eq!(
+            parse_retry_policy("TEST_RETRY_UNSET"),
+            RetryPolicy::default()
+        );
+
+        env::set_var("TEST_RETRY_PROOF_MAX_ATTEMPTS", "2");
+        env::set_var("TEST_RETRY_PROOF_BACKOFF_SECONDS", "30");
+        env::set_var("TEST_RETRY_TRANSIENT_MAX_ATTEMPTS", "0");
+        env::set_var("TEST_RETRY_TIMEOUT_BACKOFF_SECONDS", "soon");
+        env::set_var("TEST_RETRY_MAX_BACKOFF_SECONDS", "60");
+
+        let policy = parse_retry_policy("TEST_RETRY");
+        assert_eq!(policy.proof.max_attempts, 2);
+        assert_eq!(policy.proof.backoff, Duration::from_secs(30));
+        assert_eq!(policy.transient.max_attempts, 1);
+        assert_eq!(policy.timeout, RetryPolicy::default().timeout);
+        assert_eq!(policy.max_backoff, Duration::from_secs(60));
+
+        for var in [
+            "TEST_RETRY_PROOF_MAX_ATTEMPTS",
+            "TEST_RETRY_PROOF_BACKOFF_SECONDS",
+            "TEST_RETRY_TRANSIENT_MAX_ATTEMPTS",
+            "TEST_RETRY_TIMEOUT_BACKOFF_SECONDS",
+            "TEST_RETRY_MAX_BACKOFF_SECONDS",
+        ] {
+            env::remove_var(var);
+        }
+    }
+
     // These tests use the same env var so must be run together to avoid race conditions
     #[test]
     fn test_get_port_all_cases() {
@@ -2,24 +2,33 @@ use alloy_primitives::U256;
 use axum::{
     extract::{ConnectInfo, Path, State},
     http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
+use futures_util::stream::{self, Stream};
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::Duration;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::api_keys::{extract_api_key, rate_limit_key_api_key, ApiKeyConfig};
 use crate::cache::ProofCache;
 use crate::prover::check_already_claimed;
-use crate::queue::{EnqueueResult, JobQueue};
+use crate::queue::{estimate_eta_seconds, EnqueueResult, JobQueue};
 use crate::rate_limit::{RateLimitResult, RateLimiter};
-use crate::signature::verify_signature;
+use crate::signature::detect_wrapping;
 use crate::types::{
-    error_codes, AppState, HealthResponse, JobEntry, JobMessage, JobResponse, JobStatus,
+    error_codes, now_ts, AppState, BatchItemStatus, BatchProveRequest, BatchRef, BatchResponse,
+    CachedProof, HealthResponse, JobEntry, JobMessage, JobResponse, JobStatus, JobStatusEvent,
     ProveRequest, StatusResponse,
 };
 use crate::validation::{
-    cache_key, extract_client_ip, rate_limit_key_ip, rate_limit_key_pubkey, validate_request,
+    cache_key, extract_client_ip, normalize_address, rate_limit_key_ip, rate_limit_key_pubkey,
+    validate_request, NormalizedAddress,
 };
 
 /// Submit a new proof generation job
@@ -27,140 +36,63 @@ pub async fn submit_job(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
-    Json(request): Json<ProveRequest>,
+    Json(mut request): Json<ProveRequest>,
 ) -> Result<Json<JobResponse>, (StatusCode, Json<StatusResponse>)> {
-    // 0. Extract client IP for rate limiting
-    let client_ip = extract_client_ip(
-        &headers,
-        Some(&addr.to_string()),
-        state.config.trust_proxy_headers,
-    );
+    // Pin the proof system now, so the cache key and the job agree on it
+    request
+        .proof_system
+        .get_or_insert(state.config.proof_system);
 
-    // 1. Check IP-based rate limit FIRST (cheapest check, catches scanners/bots)
-    let ip_rate_limit_key = rate_limit_key_ip(&client_ip);
-    let ip_limiter = RateLimiter::new(
-        state.ip_rate_limits.clone(),
-        state.config.ip_rate_limit_window_seconds,
-        state.config.ip_rate_limit_max_requests,
-    );
+    // 0. Resolve the caller: API key holders get their own quota instead of the IP limit
+    let api_key = resolve_caller(&state, &headers)?;
 
-    match ip_limiter.check_and_update(&ip_rate_limit_key).await {
-        RateLimitResult::Limited { retry_after } => {
-            warn!(
-                "IP rate limited: {} (retry after {}s)",
-                client_ip, retry_after
-            );
-            return Err((
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(StatusResponse::failed_with_retry(
-                    error_codes::RATE_LIMITED,
-                    format!(
-                        "Too many requests from your IP. Please wait {} seconds.",
-                        retry_after
-                    ),
-                    retry_after,
-                )),
-            ));
-        }
-        RateLimitResult::Allowed => {}
-    }
-
-    // 2. Validate input format
-    let validated = validate_request(&request).map_err(|e| {
-        warn!("Invalid request: {}", e.message);
-        (
-            StatusCode::BAD_REQUEST,
-            Json(StatusResponse::failed(
-                error_codes::INVALID_INPUT,
-                e.message,
-            )),
-        )
-    })?;
-
-    // 3. Check eligibility by pubkey (handles different SS58 prefixes)
-    if !state.eligibility.is_eligible_by_pubkey(&validated.pubkey) {
-        warn!("Ineligible address: {}", request.ss58_address);
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(StatusResponse::failed(
-                error_codes::NOT_ELIGIBLE,
-                "This address is not eligible for migration claims".to_string(),
-            )),
-        ));
-    }
-
-    // 4. Verify amount matches the eligible balance (by pubkey)
-    let request_amount = U256::from_be_bytes(validated.amount);
-    if !state
-        .eligibility
-        .verify_amount_by_pubkey(&validated.pubkey, &request_amount)
-    {
-        warn!(
-            "Amount mismatch for {}: requested {} but eligible for different amount",
-            request.ss58_address, request_amount
+    // Jobs are scheduled fairly per client: the API key, or the client IP
+    let client = if let Some(ref api_key) = api_key {
+        // 1. Check the per-key quota
+        check_api_key_quota(&state, api_key).await?;
+        rate_limit_key_api_key(api_key)
+    } else {
+        // 1. Check IP-based rate limit FIRST (cheapest check, catches scanners/bots)
+        let client_ip = extract_client_ip(
+            &headers,
+            Some(&addr.to_string()),
+            state.config.trust_proxy_headers,
+        );
+        let ip_rate_limit_key = rate_limit_key_ip(&client_ip);
+        let ip_limiter = RateLimiter::with_strategy(
+            state.ip_rate_limits.clone(),
+            state.config.ip_rate_limit_window_seconds,
+            state.config.ip_rate_limit_max_requests,
+            state.config.ip_rate_limit_strategy,
         );
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(StatusResponse::failed(
-                error_codes::AMOUNT_MISMATCH,
-                "Requested amount does not match the eligible balance".to_string(),
-            )),
-        ));
-    }
-
-    // 5. Verify signature (if enabled)
-    if state.config.verify_signatures {
-        if let Err(e) = verify_signature(
-            &validated.pubkey,
-            &validated.signature,
-            &validated.challenge,
-        ) {
-            warn!("Invalid signature for {}: {}", request.ss58_address, e);
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                Json(StatusResponse::failed(
-                    error_codes::INVALID_SIGNATURE,
-                    "Signature verification failed. Please sign with the correct key.".to_string(),
-                )),
-            ));
-        }
-    }
-
-    // 6. Check per-pubkey rate limit (existing, for abuse prevention)
-    let rate_limit_key = rate_limit_key_pubkey(&validated.pubkey);
-    let rate_limiter = RateLimiter::new(
-        state.rate_limits.clone(),
-        state.config.rate_limit_window_seconds,
-        state.config.rate_limit_max_requests,
-    );
 
-    match rate_limiter.check_and_update(&rate_limit_key).await {
-        RateLimitResult::Limited { retry_after } => {
-            warn!(
-                "Rate limited: {} (retry after {}s)",
-                request.ss58_address, retry_after
-            );
-            return Err((
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(StatusResponse::failed_with_retry(
-                    error_codes::RATE_LIMITED,
-                    format!(
-                        "Too many requests. Please wait {} seconds before trying again.",
-                        retry_after
-                    ),
-                    retry_after,
-                )),
-            ));
+        match ip_limiter.check_and_update(&ip_rate_limit_key).await {
+            RateLimitResult::Limited { retry_after } => {
+                warn!(
+                    "IP rate limited: {} (retry after {}s)",
+                    client_ip, retry_after
+                );
+                return Err((
+                    StatusCode::TOO_MANY_REQUESTS,
+                    Json(StatusResponse::failed_with_retry(
+                        error_codes::RATE_LIMITED,
+                        format!(
+                            "Too many requests from your IP. Please wait {} seconds.",
+                            retry_after
+                        ),
+                        retry_after,
+                    )),
+                ));
+            }
+            RateLimitResult::Allowed => {}
         }
-        RateLimitResult::Allowed => {}
-    }
+        ip_rate_limit_key
+    };
 
-    // 7. Check cache for existing proof
+    // 2-8. Validate the claim and check the cache
     let cache_key = cache_key(&request);
-    let proof_cache = ProofCache::new(state.cache.clone(), state.config.cache_ttl_seconds);
-
-    if let Some(cached) = proof_cache.get(&cache_key).await {
-        info!("Cache hit for {}", request.ss58_address);
+    let owner = api_key.as_ref().map(|key| key.name.as_str());
+    if let Some(cached) = check_claim(&state, &request).await? {
         // Return a synthetic completed job with the cached proof
         let job_id = Uuid::new_v4().to_string();
         {
@@ -170,82 +102,43 @@ pub async fn submit_job(
                 JobEntry::new(JobStatus::Completed {
                     zk_proof: cached.zk_proof,
                     public_values: cached.public_values,
-                }),
+                    proof_system: cached.proof_system,
+                })
+                .owned_by(owner),
             );
         }
         return Ok(Json(JobResponse { job_id }));
     }
 
-    // 8. Check if user has already claimed on-chain
-    if let Some(ref claim_config) = state.config.claim_contract {
-        match check_already_claimed(
-            claim_config,
-            &request.ss58_address,
-            state.config.rpc_timeout_seconds,
-        )
-        .await
-        {
-            Ok(true) => {
-                info!(
-                    "Rejecting request: {} has already claimed",
-                    request.ss58_address
-                );
-                return Err((
-                    StatusCode::CONFLICT,
-                    Json(StatusResponse::failed(
-                        error_codes::ALREADY_CLAIMED,
-                        "This address has already claimed tokens".to_string(),
-                    )),
-                ));
-            }
-            Ok(false) => {
-                // User hasn't claimed, proceed
-            }
-            Err(e) => {
-                error!(
-                    "Failed to check claim status for {}: {}",
-                    request.ss58_address, e
-                );
-                return Err((
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    Json(StatusResponse::failed(
-                        error_codes::RPC_UNAVAILABLE,
-                        format!("Unable to verify claim status: {e}. Please try again."),
-                    )),
-                ));
-            }
-        }
-    }
-
     // 9. Create job and try to enqueue
     let job_id = Uuid::new_v4().to_string();
 
     // Try to enqueue if we have a queue
-    if let (Some(sender), Some(queue_size)) =
-        (state.job_sender.as_ref(), state.queue_size.as_ref())
-    {
-        let queue = JobQueue::from_sender(
-            sender.clone(),
-            state.config.queue_capacity,
-            queue_size.clone(),
-        );
+    if let Some(queue) = state.job_queue.as_ref() {
+        // Create the pending entry first so a fast worker always finds it
+        {
+            let mut jobs = state.jobs.lock().await;
+            jobs.insert(
+                job_id.clone(),
+                JobEntry::new(JobStatus::Pending).owned_by(owner),
+            );
+        }
 
         match queue
             .try_enqueue(JobMessage {
                 job_id: job_id.clone(),
                 request: request.clone(),
+                priority: api_key.as_ref().map_or(0, |key| key.priority),
+                client,
+                attempt: 1,
             })
             .await
         {
             EnqueueResult::Queued => {
-                // Create pending job entry
-                {
-                    let mut jobs = state.jobs.lock().await;
-                    jobs.insert(job_id.clone(), JobEntry::new(JobStatus::Pending));
-                }
                 info!("Job {} queued for {}", job_id, request.ss58_address);
             }
             EnqueueResult::QueueFull => {
+                state.jobs.lock().await.remove(&job_id);
                 warn!("Queue full, rejecting job for {}", request.ss58_address);
                 return Err((
                     StatusCode::SERVICE_UNAVAILABLE,
@@ -256,51 +149,47 @@ pub async fn submit_job(
                 ));
             }
         }
-    } else if state.job_sender.is_some() {
-        error!("Job sender configured but queue size counter is missing");
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(StatusResponse::failed(
-                error_codes::INTERNAL_ERROR,
-                "Server misconfiguration".to_string(),
-            )),
-        ));
     } else {
         // Legacy mode: spawn task directly (for backward compatibility during transition)
         {
             let mut jobs = state.jobs.lock().await;
-            jobs.insert(job_id.clone(), JobEntry::new(JobStatus::Pending));
+            jobs.insert(
+                job_id.clone(),
+                JobEntry::new(JobStatus::Pending).owned_by(owner),
+            );
         }
 
         let jobs = state.jobs.clone();
         let cache = state.cache.clone();
-        let verify_proof = state.config.verify_proof;
         let verify_onchain = state.config.verify_onchain.clone();
         let prover_mode = state.config.prover_mode.clone();
         let job_id_clone = job_id.clone();
         let cache_key_clone = cache_key.clone();
         let config = state.config.clone();
         let ss58_address = request.ss58_address.clone();
+        let proof_system = request.proof_system();
         let metrics = state.metrics.clone();
+        let backend = state.backend.clone();
 
         tokio::spawn(async move {
             use crate::prover::generate_proof;
-            use crate::types::{now_ts, CachedProof};
 
-            // Update to running
+            // Update to running, unless the job was cancelled first
             {
                 let mut jobs = jobs.lock().await;
-                if let Some(entry) = jobs.get_mut(&job_id_clone) {
-                    entry.status = JobStatus::Running;
-                    entry.updated_at = now_ts();
+                match jobs.get_mut(&job_id_clone) {
+                    Some(entry) if matches!(entry.status, JobStatus::Pending) => {
+                        entry.set_status(JobStatus::Running);
+                    }
+                    _ => return,
                 }
             }
 
             // Generate proof with timeout
             let timeout = std::time::Duration::from_secs(config.proof_timeout_seconds);
-            let prover_mode_clone = prover_mode.clone();
+            let started = std::time::Instant::now();
             let handle = tokio::task::spawn_blocking(move || {
-                generate_proof(request, verify_proof, verify_onchain, &prover_mode_clone)
+                generate_proof(&*backend, request, verify_onchain, None)
             });
             tokio::pin!(handle);
             let result = tokio::select! {
@@ -313,17 +202,19 @@ pub async fn submit_job(
             let final_status = match result {
                 Some(Ok(Ok((zk_proof, public_values)))) => {
                     metrics.record_completion();
+                    metrics.record_proof_duration(&prover_mode, started.elapsed());
                     // Store in cache
                     {
                         let mut c = cache.lock().await;
                         c.insert(
                             cache_key_clone.clone(),
-                            CachedProof::new(zk_proof.clone(), public_values.clone()),
+                            CachedProof::new(zk_proof.clone(), public_values.clone(), proof_system),
                         );
                     }
                     JobStatus::Completed {
                         zk_proof,
                         public_values,
+                        proof_system,
                     }
                 }
                 Some(Ok(Err(err))) => {
@@ -355,8 +246,7 @@ pub async fn submit_job(
             {
                 let mut jobs = jobs.lock().await;
                 if let Some(entry) = jobs.get_mut(&job_id_clone) {
-                    entry.status = final_status;
-                    entry.updated_at = now_ts();
+                    entry.set_status(final_status);
                 }
             }
 
@@ -368,16 +258,20 @@ pub async fn submit_job(
                             let mut c = cache.lock().await;
                             c.insert(
                                 cache_key_clone,
-                                CachedProof::new(zk_proof.clone(), public_values.clone()),
+                                CachedProof::new(
+                                    zk_proof.clone(),
+                                    public_values.clone(),
+                                    proof_system,
+                                ),
                             );
                         }
                         let mut jobs = jobs.lock().await;
                         if let Some(entry) = jobs.get_mut(&job_id_clone) {
-                            entry.status = JobStatus::Completed {
+                            entry.set_status(JobStatus::Completed {
                                 zk_proof,
                                 public_values,
-                            };
-                            entry.updated_at = now_ts();
+                                proof_system,
+                            });
                         }
                     }
                     Ok(Err(err)) => {
@@ -387,10 +281,9 @@ pub async fn submit_job(
                         );
                         let mut jobs = jobs.lock().await;
                         if let Some(entry) = jobs.get_mut(&job_id_clone) {
-                            entry.status = JobStatus::Failed {
+                            entry.set_status(JobStatus::Failed {
                                 error: format!("{}: {}", error_codes::PROOF_FAILED, err),
-                            };
-                            entry.updated_at = now_ts();
+                            });
                         }
                     }
                     Err(join_err) => {
@@ -400,10 +293,9 @@ pub async fn submit_job(
                         );
                         let mut jobs = jobs.lock().await;
                         if let Some(entry) = jobs.get_mut(&job_id_clone) {
-                            entry.status = JobStatus::Failed {
+                            entry.set_status(JobStatus::Failed {
                                 error: format!("{}: task panicked", error_codes::INTERNAL_ERROR),
-                            };
-                            entry.updated_at = now_ts();
+                            });
                         }
                     }
                 }
@@ -417,14 +309,491 @@ pub async fn submit_job(
     Ok(Json(JobResponse { job_id }))
 }
 
+/// Submit a batch of claims on behalf of an API key holder
+///
+/// Every claim goes through the same checks as `POST /`. Rejected claims are reported
+/// per item without affecting the rest; claims that need a proof are enqueued as a
+/// group, so either all of them get a queue slot or all are reported as `queue_full`.
+pub async fn submit_batch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(batch): Json<BatchProveRequest>,
+) -> Result<Json<BatchResponse>, (StatusCode, Json<StatusResponse>)> {
+    // 1. Authenticate (batches are never anonymous)
+    let api_key = resolve_caller(&state, &headers)?.ok_or_else(unauthorized)?;
+
+    // 2. Check batch size against the key's limit
+    if batch.claims.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(StatusResponse::failed(
+                error_codes::INVALID_INPUT,
+                "Batch must contain at least one claim".to_string(),
+            )),
+        ));
+    }
+    if batch.claims.len() > api_key.max_batch_size {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(StatusResponse::failed(
+                error_codes::INVALID_INPUT,
+                format!(
+                    "Batch contains {} claims but the limit for this API key is {}",
+                    batch.claims.len(),
+                    api_key.max_batch_size
+                ),
+            )),
+        ));
+    }
+
+    // 3. Check the per-key quota (authenticated callers skip the IP limit)
+    check_api_key_quota(&state, &api_key).await?;
+
+    let Some(queue) = state.job_queue.as_ref() else {
+        error!("Batch submission requires the job queue");
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(StatusResponse::failed(
+                error_codes::INTERNAL_ERROR,
+                "Server misconfiguration".to_string(),
+            )),
+        ));
+    };
+
+    // 4. Check each claim, collecting the ones that need a proof
+    let batch_id = Uuid::new_v4().to_string();
+    let mut items = Vec::with_capacity(batch.claims.len());
+    let mut to_enqueue = Vec::new();
+    let mut first_index_by_key: HashMap<String, usize> = HashMap::new();
+
+    for (index, mut request) in batch.claims.into_iter().enumerate() {
+        request
+            .proof_system
+            .get_or_insert(state.config.proof_system);
+        let batch_ref = BatchRef {
+            batch_id: batch_id.clone(),
+            index,
+            ss58_address: request.ss58_address.clone(),
+        };
+
+        if let Some(first) = first_index_by_key.get(&cache_key(&request)) {
+            items.push(BatchItemStatus {
+                index,
+                ss58_address: request.ss58_address,
+                job_id: None,
+                status: StatusResponse::failed(
+                    error_codes::INVALID_INPUT,
+                    format!("Duplicate of claim {} in this batch", first),
+                ),
+            });
+            continue;
+        }
+        first_index_by_key.insert(cache_key(&request), index);
+
+        match check_claim(&state, &request).await {
+            Ok(Some(cached)) => {
+                let job_id = Uuid::new_v4().to_string();
+                let status = StatusResponse::completed(
+                    cached.zk_proof.clone(),
+                    cached.public_values.clone(),
+                    cached.proof_system,
+                );
+                state.jobs.lock().await.insert(
+                    job_id.clone(),
+                    JobEntry::for_batch(
+                        JobStatus::Completed {
+                            zk_proof: cached.zk_proof,
+                            public_values: cached.public_values,
+                            proof_system: cached.proof_system,
+                        },
+                        &api_key.name,
+                        batch_ref,
+                    ),
+                );
+                items.push(BatchItemStatus {
+                    index,
+                    ss58_address: request.ss58_address,
+                    job_id: Some(job_id),
+                    status,
+                });
+            }
+            Ok(None) => {
+                let job_id = Uuid::new_v4().to_string();
+                items.push(BatchItemStatus {
+                    index,
+                    ss58_address: request.ss58_address.clone(),
+                    job_id: Some(job_id.clone()),
+                    status: StatusResponse::pending(),
+                });
+                to_enqueue.push((
+                    batch_ref,
+                    JobMessage {
+                        job_id,
+                        request,
+                        priority: api_key.priority,
+                        client: rate_limit_key_api_key(&api_key),
+                        attempt: 1,
+                    },
+                ));
+            }
+            Err((_, Json(status))) => {
+                items.push(BatchItemStatus {
+                    index,
+                    ss58_address: request.ss58_address,
+                    job_id: None,
+                    status,
+                });
+            }
+        }
+    }
+
+    // 5. Enqueue the claims that need a proof as one group. Job entries are created
+    // first so a fast worker always finds its entry.
+    if !to_enqueue.is_empty() {
+        {
+            let mut jobs = state.jobs.lock().await;
+            for (batch_ref, message) in &to_enqueue {
+                jobs.insert(
+                    message.job_id.clone(),
+                    JobEntry::for_batch(JobStatus::Pending, &api_key.name, batch_ref.clone()),
+                );
+            }
+        }
+
+        let (refs, messages): (Vec<_>, Vec<_>) = to_enqueue.into_iter().unzip();
+        let job_ids: Vec<String> = messages.iter().map(|m| m.job_id.clone()).collect();
+
+        if let EnqueueResult::QueueFull = queue.try_enqueue_batch(messages).await {
+            warn!(
+                "Queue full, rejecting {} claims from batch {}",
+                job_ids.len(),
+                batch_id
+            );
+            let mut jobs = state.jobs.lock().await;
+            for (batch_ref, job_id) in refs.iter().zip(&job_ids) {
+                jobs.remove(job_id);
+                let item = &mut items[batch_ref.index];
+                item.job_id = None;
+                item.status = StatusResponse::failed(
+                    error_codes::QUEUE_FULL,
+                    "Server is at capacity. Please try again later.".to_string(),
+                );
+            }
+        }
+    }
+
+    let queued = items
+        .iter()
+        .filter(|item| item.status.status == "pending")
+        .count();
+    info!(
+        "Batch {} from {}: {} claims, {} queued",
+        batch_id,
+        api_key.name,
+        items.len(),
+        queued
+    );
+
+    Ok(Json(BatchResponse { batch_id, items }))
+}
+
+/// Get the status of every queued or completed claim in a batch
+///
+/// Only claims that were accepted into the batch have jobs; rejected claims are
+/// reported once, in the `POST /batch` response.
+pub async fn batch_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(batch_id): Path<String>,
+) -> Result<Json<BatchResponse>, (StatusCode, Json<StatusResponse>)> {
+    let api_key = resolve_caller(&state, &headers)?.ok_or_else(unauthorized)?;
+
+    let mut members: Vec<(BatchRef, String)> = {
+        let jobs = state.jobs.lock().await;
+        jobs.iter()
+            .filter(|(_, entry)| entry.api_key.as_deref() == Some(api_key.name.as_str()))
+            .filter_map(|(job_id, entry)| {
+                entry
+                    .batch
+                    .as_ref()
+                    .filter(|batch| batch.batch_id == batch_id)
+                    .map(|batch| (batch.clone(), job_id.clone()))
+            })
+            .collect()
+    };
+
+    // Batches owned by other keys look the same as unknown ones
+    if members.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(StatusResponse::failed(
+                error_codes::NOT_FOUND,
+                "Batch not found".to_string(),
+            )),
+        ));
+    }
+    members.sort_by_key(|(batch, _)| batch.index);
+
+    let mut items = Vec::with_capacity(members.len());
+    for (batch, job_id) in members {
+        // A job can expire between collecting the members and reading its progress
+        let Some(event) = job_progress(&state, &job_id, Some(&api_key.name)).await else {
+            continue;
+        };
+        items.push(BatchItemStatus {
+            index: batch.index,
+            ss58_address: batch.ss58_address,
+            job_id: Some(job_id),
+            status: event.status,
+        });
+    }
+
+    Ok(Json(BatchResponse { batch_id, items }))
+}
+
+/// Resolve the API key presented in the request headers
+///
+/// Returns `None` for anonymous callers when anonymous access is allowed. A key that
+/// is presented but unknown is always rejected rather than treated as anonymous.
+#[allow(clippy::result_large_err)]
+fn resolve_caller(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<Option<ApiKeyConfig>, (StatusCode, Json<StatusResponse>)> {
+    match extract_api_key(headers) {
+        Some(key) => state
+            .api_keys
+            .authenticate(&key)
+            .cloned()
+            .map(Some)
+            .ok_or_else(unauthorized),
+        None if state.config.allow_anonymous => Ok(None),
+        None => Err(unauthorized()),
+    }
+}
+
+/// Check and update the quota of an API key
+async fn check_api_key_quota(
+    state: &AppState,
+    api_key: &ApiKeyConfig,
+) -> Result<(), (StatusCode, Json<StatusResponse>)> {
+    let key_limiter = RateLimiter::with_strategy(
+        state.api_key_rate_limits.clone(),
+        api_key.rate_limit_window_seconds,
+        api_key.rate_limit_max_requests,
+        state.config.api_key_rate_limit_strategy,
+    );
+
+    match key_limiter
+        .check_and_update(&rate_limit_key_api_key(api_key))
+        .await
+    {
+        RateLimitResult::Limited { retry_after } => {
+            warn!(
+                "API key rate limited: {} (retry after {}s)",
+                api_key.name, retry_after
+            );
+            Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(StatusResponse::failed_with_retry(
+                    error_codes::RATE_LIMITED,
+                    format!(
+                        "Quota exceeded for this API key. Please wait {} seconds.",
+                        retry_after
+                    ),
+                    retry_after,
+                )),
+            ))
+        }
+        RateLimitResult::Allowed => Ok(()),
+    }
+}
+
+/// Error response for a missing or unknown API key
+fn unauthorized() -> (StatusCode, Json<StatusResponse>) {
+    warn!("Rejected request with missing or unknown API key");
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(StatusResponse::failed(
+            error_codes::UNAUTHORIZED,
+            "A valid API key is required".to_string(),
+        )),
+    )
+}
+
+/// Run the per-claim checks shared by single and batch submission
+///
+/// Covers input validation, eligibility, amount, signature, the per-pubkey rate
+/// limit, the proof cache and the on-chain claim status. Returns the cached proof
+/// on a cache hit, or `None` if the claim needs a new proof.
+async fn check_claim(
+    state: &AppState,
+    request: &ProveRequest,
+) -> Result<Option<CachedProof>, (StatusCode, Json<StatusResponse>)> {
+    // 2. Validate input format
+    let validated = validate_request(
+        request,
+        state.config.challenge_domain.as_ref(),
+        &state.config.prefix_policy,
+    )
+    .map_err(|e| {
+        warn!("Invalid request: {}", e.message);
+        (
+            StatusCode::BAD_REQUEST,
+            Json(StatusResponse::failed(&e.code, e.message)),
+        )
+    })?;
+
+    // 3. Check eligibility by pubkey (handles different SS58 prefixes)
+    if !state.eligibility.is_eligible_by_pubkey(&validated.pubkey) {
+        warn!("Ineligible address: {}", request.ss58_address);
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(StatusResponse::failed(
+                error_codes::NOT_ELIGIBLE,
+                "This address is not eligible for migration claims".to_string(),
+            )),
+        ));
+    }
+
+    // 4. Verify amount matches the eligible balance (by pubkey)
+    let request_amount = U256::from_be_bytes(validated.amount);
+    if !state
+        .eligibility
+        .verify_amount_by_pubkey(&validated.pubkey, &request_amount)
+    {
+        warn!(
+            "Amount mismatch for {}: requested {} but eligible for different amount",
+            request.ss58_address, request_amount
+        );
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(StatusResponse::failed(
+                error_codes::AMOUNT_MISMATCH,
+                "Requested amount does not match the eligible balance".to_string(),
+            )),
+        ));
+    }
+
+    // 5. Verify signature (if enabled), over each framing the request allows
+    if state.config.verify_signatures {
+        let mode = request.signature_wrapping;
+        if let Err(e) = detect_wrapping(
+            mode,
+            validated.scheme,
+            &validated.pubkey,
+            &validated.signature,
+            &validated.challenge,
+        ) {
+            warn!("Invalid signature for {}: {}", request.ss58_address, e);
+            let tried: Vec<&str> = mode.candidates().iter().map(|w| w.as_str()).collect();
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(StatusResponse::failed(
+                    error_codes::INVALID_SIGNATURE,
+                    format!(
+                        "Signature verification failed (tried: {}). Please sign with the correct key.",
+                        tried.join(", ")
+                    ),
+                )),
+            ));
+        }
+    }
+
+    // 6. Check per-pubkey rate limit (existing, for abuse prevention)
+    let rate_limit_key = rate_limit_key_pubkey(&validated.pubkey);
+    let rate_limiter = RateLimiter::with_strategy(
+        state.rate_limits.clone(),
+        state.config.rate_limit_window_seconds,
+        state.config.rate_limit_max_requests,
+        state.config.rate_limit_strategy,
+    );
+
+    match rate_limiter.check_and_update(&rate_limit_key).await {
+        RateLimitResult::Limited { retry_after } => {
+            warn!(
+                "Rate limited: {} (retry after {}s)",
+                request.ss58_address, retry_after
+            );
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(StatusResponse::failed_with_retry(
+                    error_codes::RATE_LIMITED,
+                    format!(
+                        "Too many requests. Please wait {} seconds before trying again.",
+                        retry_after
+                    ),
+                    retry_after,
+                )),
+            ));
+        }
+        RateLimitResult::Allowed => {}
+    }
+
+    // 7. Check cache for existing proof
+    let cache_key = cache_key(request);
+    let proof_cache = ProofCache::new(state.cache.clone(), state.config.cache_ttl_seconds);
+
+    if let Some(cached) = proof_cache.get(&cache_key).await {
+        info!("Cache hit for {}", request.ss58_address);
+        return Ok(Some(cached));
+    }
+
+    // 8. Check if user has already claimed on-chain
+    if let Some(ref claim_config) = state.config.claim_contract {
+        match check_already_claimed(
+            claim_config,
+            &request.ss58_address,
+            state.config.rpc_timeout_seconds,
+        )
+        .await
+        {
+            Ok(true) => {
+                info!(
+                    "Rejecting request: {} has already claimed",
+                    request.ss58_address
+                );
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(StatusResponse::failed(
+                        error_codes::ALREADY_CLAIMED,
+                        "This address has already claimed tokens".to_string(),
+                    )),
+                ));
+            }
+            Ok(false) => {
+                // User hasn't claimed, proceed
+            }
+            Err(e) => {
+                error!(
+                    "Failed to check claim status for {}: {}",
+                    request.ss58_address, e
+                );
+                return Err((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(StatusResponse::failed(
+                        error_codes::RPC_UNAVAILABLE,
+                        format!("Unable to verify claim status: {e}. Please try again."),
+                    )),
+                ));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 /// Get job status
+///
+/// Jobs submitted with an API key are only visible to that key.
 pub async fn job_status(
     State(state): State<AppState>,
     Path(job_id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Json<StatusResponse>, (StatusCode, Json<StatusResponse>)> {
-    let jobs = state.jobs.lock().await;
-    match jobs.get(&job_id) {
-        Some(job) => Ok(Json(status_from_job(&job.status))),
+    let viewer = resolve_caller(&state, &headers)?.map(|key| key.name);
+    match job_progress(&state, &job_id, viewer.as_deref()).await {
+        Some(event) => Ok(Json(event.status)),
         None => Err((
             StatusCode::NOT_FOUND,
             Json(StatusResponse::failed(
@@ -435,26 +804,224 @@ pub async fn job_status(
     }
 }
 
+/// Cancel a pending job
+///
+/// Removes the job from the queue, freeing its slot, or stops it from being retried
+/// if it is waiting out a retry backoff. Running and finished jobs can't be
+/// cancelled. Jobs submitted with an API key can only be cancelled by that key.
+pub async fn cancel_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<StatusResponse>, (StatusCode, Json<StatusResponse>)> {
+    let viewer = resolve_caller(&state, &headers)?.map(|key| key.name);
+
+    let response = {
+        let mut jobs = state.jobs.lock().await;
+        let entry = jobs
+            .get_mut(&job_id)
+            .filter(|job| job.api_key.is_none() || job.api_key == viewer)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(StatusResponse::failed(
+                        error_codes::NOT_FOUND,
+                        "Job not found".to_string(),
+                    )),
+                )
+            })?;
+
+        if !matches!(entry.status, JobStatus::Pending) {
+            let status = status_from_job(&entry.status).status;
+            return Err((
+                StatusCode::CONFLICT,
+                Json(StatusResponse::failed(
+                    error_codes::NOT_CANCELLABLE,
+                    format!("Job is {status} and can no longer be cancelled"),
+                )),
+            ));
+        }
+
+        // Workers check the status before starting, so this wins over a concurrent dequeue
+        entry.set_status(JobStatus::Cancelled);
+        status_from_job(&entry.status).with_attempts(entry.attempts.clone())
+    };
+
+    if let Some(queue) = state.job_queue.as_ref() {
+        queue.cancel(&job_id);
+    }
+    info!("Job {} cancelled", job_id);
+
+    Ok(Json(response))
+}
+
+/// How often a status stream re-checks its job
+const STREAM_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stream job status updates as server-sent events
+///
+/// Emits a `status` event (the `GET /status/:job_id` payload plus `etaSeconds`)
+/// whenever the status or queue position changes, and closes the stream after the
+/// job completes, fails or is cancelled.
+pub async fn job_status_stream(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, Json<StatusResponse>)>
+{
+    let viewer = resolve_caller(&state, &headers)?.map(|key| key.name);
+    if job_progress(&state, &job_id, viewer.as_deref())
+        .await
+        .is_none()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(StatusResponse::failed(
+                error_codes::NOT_FOUND,
+                "Job not found".to_string(),
+            )),
+        ));
+    }
+
+    let interval = tokio::time::interval(STREAM_POLL_INTERVAL);
+    // (state, job_id, viewer, poll interval, last emitted (status, position), finished)
+    let initial = (
+        state,
+        job_id,
+        viewer,
+        interval,
+        None::<(String, Option<usize>)>,
+        false,
+    );
+
+    let events = stream::unfold(
+        initial,
+        |(state, job_id, viewer, mut interval, mut last, finished)| async move {
+            if finished {
+                return None;
+            }
+            loop {
+                interval.tick().await;
+
+                let Some(event) = job_progress(&state, &job_id, viewer.as_deref()).await else {
+                    // Job expired from the jobs map while we were watching it
+                    let gone =
+                        StatusResponse::failed(error_codes::NOT_FOUND, "Job not found".to_string());
+                    let sse = Event::default().event("status").json_data(gone);
+                    return Some((sse, (state, job_id, viewer, interval, last, true)));
+                };
+
+                let key = (event.status.status.clone(), event.status.queue_position);
+                if last.as_ref() == Some(&key) {
+                    continue;
+                }
+                let terminal = matches!(key.0.as_str(), "completed" | "failed" | "cancelled");
+                last = Some(key);
+
+                let sse = Event::default().event("status").json_data(event);
+                return Some((sse, (state, job_id, viewer, interval, last, terminal)));
+            }
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Build the current status of a job with queue progress
+///
+/// Returns `None` if the job doesn't exist or was submitted with an API key other
+/// than `viewer`.
+async fn job_progress(
+    state: &AppState,
+    job_id: &str,
+    viewer: Option<&str>,
+) -> Option<JobStatusEvent> {
+    let (status, started_at, attempts) = {
+        let jobs = state.jobs.lock().await;
+        let job = jobs.get(job_id)?;
+        if job.api_key.is_some() && job.api_key.as_deref() != viewer {
+            return None;
+        }
+        (job.status.clone(), job.started_at, job.attempts.clone())
+    };
+
+    let now = now_ts();
+    let (queue_position, running_for) = match status {
+        JobStatus::Pending => (
+            state
+                .job_queue
+                .as_ref()
+                .and_then(|queue| queue.position(job_id)),
+            None,
+        ),
+        JobStatus::Running => (
+            None,
+            started_at.map(|started| Duration::from_secs(now.saturating_sub(started))),
+        ),
+        _ => (None, None),
+    };
+
+    let eta_seconds = match status {
+        JobStatus::Pending | JobStatus::Running => estimate_eta_seconds(
+            queue_position,
+            running_for,
+            state.config.worker_count,
+            state
+                .metrics
+                .average_proof_duration(&state.config.prover_mode),
+        ),
+        _ => None,
+    };
+
+    let response = status_from_job(&status).with_attempts(attempts);
+    let response = match status {
+        JobStatus::Pending | JobStatus::Running => {
+            response.with_progress(queue_position, started_at, eta_seconds.map(|eta| now + eta))
+        }
+        _ => response,
+    };
+
+    Some(JobStatusEvent {
+        status: response,
+        eta_seconds,
+    })
+}
+
+/// Canonical Tangle SS58 form of an address from any network
+pub async fn normalize(
+    Path(address): Path<String>,
+) -> Result<Json<NormalizedAddress>, (StatusCode, Json<StatusResponse>)> {
+    normalize_address(&address).map(Json).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(StatusResponse::failed(&e.code, e.message)),
+        )
+    })
+}
+
 /// Health check endpoint
 pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
     let jobs = state.jobs.lock().await;
     let cache = state.cache.lock().await;
 
-    let queue_size = state
-        .queue_size
+    let queue_size = state.job_queue.as_ref().map(JobQueue::depth).unwrap_or(0);
+    let queue_depth = state
+        .job_queue
         .as_ref()
-        .map(|counter| counter.load(std::sync::atomic::Ordering::SeqCst))
-        .unwrap_or(0);
+        .map(JobQueue::depth_by_class)
+        .unwrap_or_default();
 
     let response = HealthResponse {
         status: "ok".to_string(),
         prover_mode: state.config.prover_mode.clone(),
         verify_proof: state.config.verify_proof,
         verify_onchain: state.config.verify_onchain.is_some(),
+        program_vkey: format!("0x{}", hex::encode(state.program_vkey)),
         jobs: jobs.len(),
         cache_size: cache.len(),
         queue_size,
         queue_capacity: state.config.queue_capacity,
+        queue_depth,
         proof_metrics: state.metrics.snapshot(),
     };
     (StatusCode::OK, Json(response))
@@ -468,7 +1035,8 @@ fn status_from_job(status: &JobStatus) -> StatusResponse {
         JobStatus::Completed {
             zk_proof,
             public_values,
-        } => StatusResponse::completed(zk_proof.clone(), public_values.clone()),
+            proof_system,
+        } => StatusResponse::completed(zk_proof.clone(), public_values.clone(), *proof_system),
         JobStatus::Failed { error } => {
             // Parse the error code from the error message if present
             let (code, message) = if let Some(idx) = error.find(':') {
@@ -480,12 +1048,14 @@ fn status_from_job(status: &JobStatus) -> StatusResponse {
             };
             StatusResponse::failed(code, message)
         }
+        JobStatus::Cancelled => StatusResponse::cancelled(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::ProofSystem;
 
     #[test]
     fn test_status_from_job_pending() {
@@ -506,11 +1076,13 @@ mod tests {
         let status = JobStatus::Completed {
             zk_proof: "0x123".to_string(),
             public_values: "0x456".to_string(),
+            proof_system: ProofSystem::Groth16,
         };
         let resp = status_from_job(&status);
         assert_eq!(resp.status, "completed");
         assert_eq!(resp.zk_proof, Some("0x123".to_string()));
         assert_eq!(resp.public_values, Some("0x456".to_string()));
+        assert_eq!(resp.proof_system, Some(ProofSystem::Groth16));
     }
 
     #[test]
@@ -533,4 +1105,12 @@ mod tests {
         assert_eq!(resp.status, "failed");
         assert_eq!(resp.code, Some(error_codes::PROOF_FAILED.to_string()));
     }
+
+    #[test]
+    fn test_status_from_job_cancelled() {
+        let resp = status_from_job(&JobStatus::Cancelled);
+        assert_eq!(resp.status, "cancelled");
+        assert!(resp.code.is_none());
+        assert!(resp.error.is_none());
+    }
 }
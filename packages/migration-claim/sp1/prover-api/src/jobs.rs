@@ -16,14 +16,14 @@ impl JobManager {
         Self { jobs, ttl_seconds }
     }
 
-    /// Clean up old completed/failed jobs
+    /// Clean up old completed/failed/cancelled jobs
     pub async fn cleanup(&self) -> usize {
         let mut jobs = self.jobs.lock().await;
         let before = jobs.len();
         let now = now_ts();
 
         jobs.retain(|_, entry| match entry.status {
-            JobStatus::Completed { .. } | JobStatus::Failed { .. } => {
+            JobStatus::Completed { .. } | JobStatus::Failed { .. } | JobStatus::Cancelled => {
                 now - entry.updated_at < self.ttl_seconds
             }
             JobStatus::Pending | JobStatus::Running => true,
@@ -57,6 +57,7 @@ pub fn start_jobs_cleanup_task(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::ProofSystem;
 
     #[tokio::test]
     async fn test_cleanup_keeps_pending_jobs() {
@@ -70,6 +71,11 @@ mod tests {
                 JobEntry {
                     status: JobStatus::Pending,
                     updated_at: now_ts() - 1000, // Old job
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
         }
@@ -93,6 +99,11 @@ mod tests {
                 JobEntry {
                     status: JobStatus::Running,
                     updated_at: now_ts() - 1000, // Old job
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
         }
@@ -117,8 +128,14 @@ mod tests {
                     status: JobStatus::Completed {
                         zk_proof: "0x123".to_string(),
                         public_values: "0x456".to_string(),
+                        proof_system: ProofSystem::Groth16,
                     },
                     updated_at: now_ts() - 120, // 2 minutes ago
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
             // Insert a fresh completed job
@@ -128,8 +145,14 @@ mod tests {
                     status: JobStatus::Completed {
                         zk_proof: "0x789".to_string(),
                         public_values: "0xabc".to_string(),
+                        proof_system: ProofSystem::Groth16,
                     },
                     updated_at: now_ts() - 30, // 30 seconds ago
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
         }
@@ -160,6 +183,11 @@ mod tests {
                         error: "some error".to_string(),
                     },
                     updated_at: now_ts() - 120, // 2 minutes ago
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
         }
@@ -171,6 +199,47 @@ mod tests {
         assert_eq!(jobs.lock().await.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_cleanup_removes_old_cancelled_jobs() {
+        let jobs = Arc::new(Mutex::new(HashMap::new()));
+
+        {
+            let mut j = jobs.lock().await;
+            j.insert(
+                "old_cancelled".to_string(),
+                JobEntry {
+                    status: JobStatus::Cancelled,
+                    updated_at: now_ts() - 120, // 2 minutes ago
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
+                },
+            );
+            j.insert(
+                "fresh_cancelled".to_string(),
+                JobEntry {
+                    status: JobStatus::Cancelled,
+                    updated_at: now_ts() - 30, // 30 seconds ago
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
+                },
+            );
+        }
+
+        let manager = JobManager::new(jobs.clone(), 60); // 1 minute TTL
+        let removed = manager.cleanup().await;
+
+        assert_eq!(removed, 1);
+        let j = jobs.lock().await;
+        assert!(j.contains_key("fresh_cancelled"));
+        assert!(!j.contains_key("old_cancelled"));
+    }
+
     #[tokio::test]
     async fn test_mixed_cleanup() {
         let jobs = Arc::new(Mutex::new(HashMap::new()));
@@ -184,6 +253,11 @@ mod tests {
                 JobEntry {
                     status: JobStatus::Pending,
                     updated_at: now - 200,
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
             j.insert(
@@ -191,6 +265,11 @@ mod tests {
                 JobEntry {
                     status: JobStatus::Running,
                     updated_at: now - 200,
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
             j.insert(
@@ -199,8 +278,14 @@ mod tests {
                     status: JobStatus::Completed {
                         zk_proof: "0x".to_string(),
                         public_values: "0x".to_string(),
+                        proof_system: ProofSystem::Groth16,
                     },
                     updated_at: now - 200,
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
             j.insert(
@@ -210,6 +295,11 @@ mod tests {
                         error: "err".to_string(),
                     },
                     updated_at: now - 200,
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
             // Fresh jobs
@@ -219,8 +309,14 @@ mod tests {
                     status: JobStatus::Completed {
                         zk_proof: "0x".to_string(),
                         public_values: "0x".to_string(),
+                        proof_system: ProofSystem::Groth16,
                     },
                     updated_at: now - 30,
+                    started_at: None,
+                    api_key: None,
+                    batch: None,
+                    attempts: Vec::new(),
+                    unverified_proof: None,
                 },
             );
         }
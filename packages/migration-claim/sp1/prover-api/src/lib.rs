@@ -3,6 +3,8 @@
 //! This crate provides a REST API for generating ZK proofs using the SP1 SDK.
 //! The modules are separated to allow testing of non-SP1-dependent code independently.
 
+pub mod api_keys;
+pub mod backend;
 pub mod cache;
 pub mod config;
 pub mod eligibility;
@@ -11,13 +13,14 @@ pub mod jobs;
 pub mod prover;
 pub mod queue;
 pub mod rate_limit;
+pub mod retry;
 pub mod signature;
 pub mod types;
 pub mod validation;
 
 // Re-export commonly used types
 pub use types::{
-    error_codes, AppConfig, AppState, CachedProof, ClaimContractConfig, HealthResponse, JobEntry,
-    JobMessage, JobResponse, JobStatus, ProveRequest, RateLimitEntry, StatusResponse,
-    VerifyOnchainConfig,
+    error_codes, AppConfig, AppState, BatchItemStatus, BatchProveRequest, BatchResponse,
+    CachedProof, ClaimContractConfig, HealthResponse, JobEntry, JobMessage, JobResponse,
+    JobStatus, JobStatusEvent, ProveRequest, RateLimitEntry, StatusResponse, VerifyOnchainConfig,
 };
@@ -1,3 +1,5 @@
+mod api_keys;
+mod backend;
 mod cache;
 mod config;
 mod eligibility;
@@ -6,6 +8,7 @@ mod jobs;
 mod prover;
 mod queue;
 mod rate_limit;
+mod retry;
 mod signature;
 mod types;
 mod validation;
@@ -21,12 +24,16 @@ use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{error, info};
 
+use api_keys::ApiKeyStore;
 use cache::start_cache_cleanup_task;
 use config::{get_port, load_config, validate_cors};
 use eligibility::EligibilityData;
-use handlers::{health, job_status, submit_job};
+use handlers::{
+    batch_status, cancel_job, health, job_status, job_status_stream, normalize, submit_batch,
+    submit_job,
+};
 use jobs::start_jobs_cleanup_task;
-use queue::{JobQueue, WorkerPool};
+use queue::{DeadlinePolicy, JobQueue, WorkerPool};
 use rate_limit::start_rate_limit_cleanup_task;
 use types::{AppState, CachedProof, JobEntry, ProofMetrics, RateLimitEntry};
 
@@ -62,6 +69,7 @@ async fn main() {
     // Log configuration
     info!("Configuration loaded:");
     info!("  SP1_PROVER={}", config.prover_mode);
+    info!("  PROOF_SYSTEM={}", config.proof_system);
     info!("  VERIFY_PROOF={}", config.verify_proof);
     info!("  VERIFY_ONCHAIN={}", config.verify_onchain.is_some());
     info!(
@@ -72,24 +80,60 @@ async fn main() {
             .map(|c| format!("0x{}", hex::encode(c.contract_address)))
             .unwrap_or_else(|| "disabled".to_string())
     );
+    info!(
+        "  CLAIM_CHAIN_ID={}",
+        config
+            .challenge_domain
+            .map(|domain| domain.chain_id.to_string())
+            .unwrap_or_else(|| "disabled".to_string())
+    );
+    info!("  SS58_PREFIX_POLICY={}", config.prefix_policy);
     info!("  CACHE_TTL_SECONDS={}", config.cache_ttl_seconds);
     info!(
-        "  RATE_LIMIT={}/{}s",
-        config.rate_limit_max_requests, config.rate_limit_window_seconds
+        "  RATE_LIMIT={}/{}s ({})",
+        config.rate_limit_max_requests,
+        config.rate_limit_window_seconds,
+        config.rate_limit_strategy
     );
     info!(
         "  QUEUE_CAPACITY={} WORKERS={}",
         config.queue_capacity, config.worker_count
     );
     info!("  PROOF_TIMEOUT_SECONDS={}", config.proof_timeout_seconds);
+    info!("  SELF_TEST={}", config.self_test);
+    for class in retry::ErrorClass::ALL {
+        let rule = config.retry_policy.rule(class);
+        info!(
+            "  RETRY {}: {} attempts, {}s backoff",
+            class,
+            rule.max_attempts,
+            rule.backoff.as_secs()
+        );
+    }
+    info!(
+        "  CLAIM_DEADLINE={} (priority window {}s)",
+        config
+            .claim_deadline
+            .map(|deadline| deadline.to_string())
+            .unwrap_or_else(|| "none".to_string()),
+        config.deadline_priority_window_seconds
+    );
     info!("  MAX_BODY_BYTES={}", config.max_body_bytes);
     info!("  JOBS_TTL_SECONDS={}", config.jobs_ttl_seconds);
     info!(
-        "  IP_RATE_LIMIT={}/{}s",
-        config.ip_rate_limit_max_requests, config.ip_rate_limit_window_seconds
+        "  IP_RATE_LIMIT={}/{}s ({})",
+        config.ip_rate_limit_max_requests,
+        config.ip_rate_limit_window_seconds,
+        config.ip_rate_limit_strategy
     );
     info!("  ELIGIBILITY_FILE={}", config.eligibility_file);
+    info!(
+        "  API_KEYS_FILE={} ({})",
+        config.api_keys_file.as_deref().unwrap_or("none"),
+        config.api_key_rate_limit_strategy
+    );
     info!("  VERIFY_SIGNATURES={}", config.verify_signatures);
+    info!("  ALLOW_ANONYMOUS={}", config.allow_anonymous);
     info!(
         "  CORS_ALLOWED_ORIGINS={}",
         cors_origins.as_deref().unwrap_or("*")
@@ -108,6 +152,39 @@ async fn main() {
         }
     };
 
+    // Load API keys
+    let api_keys = match config.api_keys_file.as_deref() {
+        Some(path) => match ApiKeyStore::load_from_file(path) {
+            Ok(store) => {
+                info!("Loaded {} API keys", store.len());
+                for key in store.keys() {
+                    info!(
+                        "  {} (tier {}): {}/{}s, priority {}, max batch {}",
+                        key.name,
+                        key.tier.as_deref().unwrap_or("none"),
+                        key.rate_limit_max_requests,
+                        key.rate_limit_window_seconds,
+                        key.priority,
+                        key.max_batch_size
+                    );
+                }
+                Arc::new(store)
+            }
+            Err(e) => {
+                error!("Failed to load API keys: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Arc::new(ApiKeyStore::empty()),
+    };
+    if api_keys.is_empty() {
+        if !config.allow_anonymous {
+            error!("ALLOW_ANONYMOUS=false but no API keys are configured");
+            std::process::exit(1);
+        }
+        info!("No API keys configured, batch submission is disabled");
+    }
+
     // Initialize shared state
     let jobs: Arc<Mutex<HashMap<String, JobEntry>>> = Arc::new(Mutex::new(HashMap::new()));
     let cache: Arc<Mutex<HashMap<String, CachedProof>>> = Arc::new(Mutex::new(HashMap::new()));
@@ -115,6 +192,8 @@ async fn main() {
         Arc::new(Mutex::new(HashMap::new()));
     let ip_rate_limits: Arc<Mutex<HashMap<String, RateLimitEntry>>> =
         Arc::new(Mutex::new(HashMap::new()));
+    let api_key_rate_limits: Arc<Mutex<HashMap<String, RateLimitEntry>>> =
+        Arc::new(Mutex::new(HashMap::new()));
     let metrics = Arc::new(ProofMetrics::new());
     let config = Arc::new(config);
 
@@ -136,16 +215,91 @@ async fn main() {
     start_jobs_cleanup_task(jobs.clone(), config.jobs_ttl_seconds, 60);
 
     // Create job queue and worker pool
-    let (job_queue, receiver) = JobQueue::new(config.queue_capacity);
-    let worker_pool = WorkerPool::new(config.worker_count);
+    let deadline = config.claim_deadline.map(|deadline| DeadlinePolicy {
+        deadline,
+        window_seconds: config.deadline_priority_window_seconds,
+    });
+    let job_queue = JobQueue::new(config.queue_capacity, deadline);
+
+    // Check the on-chain vkey against the embedded program, so a stale SP1_PROGRAM_VKEY
+    // or ELF fails at startup instead of failing every on-chain verification
+    let program_vkey = match tokio::task::spawn_blocking(backend::program_vkey).await {
+        Ok(Ok(vkey)) => vkey,
+        Ok(Err(e)) => {
+            error!("Failed to compute the program vkey: {}", e);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("Program vkey computation panicked: {}", e);
+            std::process::exit(1);
+        }
+    };
+    info!("Program vkey: 0x{}", hex::encode(program_vkey));
+    if let Err(e) = backend::check_program_vkey(config.verify_onchain.as_ref(), &program_vkey) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+
+    // Prove a canned claim in mock mode, so a bad deployment fails before serving
+    if config.self_test {
+        match tokio::task::spawn_blocking(backend::self_test).await {
+            Ok(Ok(elapsed)) => info!("Self-test proof verified in {:?}", elapsed),
+            Ok(Err(e)) => {
+                error!("Self-test failed: {}", e);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                error!("Self-test panicked: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Prepare a prover per worker up front, so a broken backend fails at startup
+    let backend_config = config.prover_backend.clone();
+    let (verify_proof, worker_count) = (config.verify_proof, config.worker_count);
+    let setup_metrics = metrics.clone();
+    let started = std::time::Instant::now();
+    let backends = match tokio::task::spawn_blocking(move || {
+        backend::prepare_backends(
+            &backend_config,
+            program_vkey,
+            verify_proof,
+            worker_count,
+            &setup_metrics,
+        )
+    })
+    .await
+    {
+        Ok(Ok(backends)) => backends,
+        Ok(Err(e)) => {
+            error!("Failed to prepare {} prover: {}", config.prover_mode, e);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("Prover setup panicked: {}", e);
+            std::process::exit(1);
+        }
+    };
+    info!(
+        "Prepared {} {} prover(s) in {:?}",
+        backends.len(),
+        config.prover_mode,
+        started.elapsed()
+    );
+    // Jobs spawned outside the queue reuse a worker's prover rather than setting one up
+    let Some(backend) = backends.first().cloned() else {
+        error!("WORKER_COUNT must be at least 1");
+        std::process::exit(1);
+    };
+    let worker_pool = WorkerPool::new(backends);
 
     // Start workers
     worker_pool.start(
-        receiver,
+        job_queue.clone(),
         jobs.clone(),
         cache.clone(),
         config.clone(),
-        job_queue.size_counter(),
         metrics.clone(),
     );
 
@@ -154,11 +308,14 @@ async fn main() {
         cache,
         rate_limits,
         ip_rate_limits,
+        api_key_rate_limits,
+        api_keys,
         eligibility,
         config: config.clone(),
-        job_sender: Some(job_queue.sender.clone()),
-        queue_size: Some(job_queue.size_counter()),
+        job_queue: Some(job_queue),
+        backend,
         metrics,
+        program_vkey,
     };
 
     // Configure CORS
@@ -182,7 +339,14 @@ async fn main() {
     // Build router
     let app = Router::new()
         .route("/", post(submit_job))
-        .route("/status/:job_id", get(job_status))
+        .route("/status/:job_id", get(job_status).delete(cancel_job))
+        .route("/status/:job_id/stream", get(job_status_stream))
+        .route(
+            "/batch",
+            post(submit_batch).layer(DefaultBodyLimit::max(config.max_batch_body_bytes)),
+        )
+        .route("/batch/:batch_id", get(batch_status))
+        .route("/normalize/:address", get(normalize))
         .route("/health", get(health))
         .layer(DefaultBodyLimit::max(config.max_body_bytes))
         .layer(cors)
@@ -1,15 +1,17 @@
 use alloy_primitives::{Bytes, FixedBytes};
 use alloy_sol_types::{sol, SolCall};
-use sp1_sdk::blocking::{ProveRequest as _, Prover, ProverClient, SP1Stdin};
-use sp1_sdk::{include_elf, ProvingKey};
-use sr25519_claim_lib::{ss58_decode, ProgramInput, PublicValues};
+use sp1_sdk::include_elf;
+use sr25519_claim_lib::{encode_zk_proof, ss58_decode, ProgramInput, PublicValues};
 use std::time::Duration;
 use tracing::info;
 
+use crate::backend::{ProofOutput, ProverBackend};
+use crate::retry::{ErrorClass, ProveError};
+use crate::signature::detect_wrapping;
 use crate::types::{ClaimContractConfig, ProveRequest, VerifyOnchainConfig};
-use crate::validation::parse_hex_bytes;
+use crate::validation::{parse_hex_bytes, parse_signature};
 
-const ELF: sp1_sdk::Elf = include_elf!("sr25519-claim-program");
+pub(crate) const ELF: sp1_sdk::Elf = include_elf!("sr25519-claim-program");
 
 sol! {
     function verifyProof(bytes32 programVKey, bytes publicValues, bytes proofBytes) external view;
@@ -19,81 +21,53 @@ sol! {
 /// Generate a ZK proof for the given request
 ///
 /// # Arguments
-/// * `request` - The proof request containing signature and claim details
-/// * `verify_proof` - Whether to verify the proof after generation
+/// * `backend` - The worker's prepared prover
+/// * `request` - The proof request containing signature, claim details and proof system
 /// * `verify_onchain` - Optional on-chain verification config
-/// * `prover_mode` - The prover mode: "mock" or "local"; "network" fails closed in this build
+/// * `proof` - Proof of an earlier attempt, to verify instead of proving again
+///
+/// If the on-chain verification fails, the error carries the proof.
 pub fn generate_proof(
+    backend: &dyn ProverBackend,
     request: ProveRequest,
-    verify_proof: bool,
     verify_onchain: Option<VerifyOnchainConfig>,
-    prover_mode: &str,
-) -> Result<(String, String), String> {
-    let signature = parse_hex_bytes::<64>(&request.signature).map_err(err_to_string)?;
+    proof: Option<ProofOutput>,
+) -> Result<(String, String), ProveError> {
+    let scheme = request.signature_scheme;
+    let signature = parse_signature(&request.signature, scheme)?;
     let evm_address = parse_hex_bytes::<20>(&request.evm_address).map_err(err_to_string)?;
     let challenge = parse_hex_bytes::<32>(&request.challenge).map_err(err_to_string)?;
     let amount = crate::validation::parse_amount(&request.amount).map_err(err_to_string)?;
+    let proof_system = request.proof_system();
     let ss58_address = request.ss58_address;
 
+    // Resolve the framing the program should verify against. Signatures that verify
+    // under none of the candidates are left for the program to reject.
+    let mode = request.signature_wrapping;
+    let account_id = ss58_decode(&ss58_address).map_err(err_to_string)?;
+    let wrapping = detect_wrapping(mode, scheme, &account_id, &signature, &challenge)
+        .unwrap_or(mode.candidates()[0]);
+
     let input = ProgramInput {
         substrate_address: ss58_address.clone(),
+        scheme,
         signature,
+        wrapping,
         evm_address,
         amount,
         challenge,
     };
 
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&input);
-
-    let (proof, _vk) = match prover_mode {
-        "mock" => {
-            info!("Using SP1 mock prover (test mode)");
-            std::env::set_var("SP1_PROVER", "mock");
-            let client = ProverClient::from_env();
-            let pk = client.setup(ELF).map_err(err_to_string)?;
-            let proof = client
-                .prove(&pk, stdin)
-                .groth16()
-                .run()
-                .map_err(err_to_string)?;
-            if verify_proof {
-                client
-                    .verify(&proof, pk.verifying_key(), None)
-                    .map_err(err_to_string)?;
-            }
-            (proof, pk)
-        }
-        "local" => {
-            info!("Using SP1 local prover");
-            std::env::set_var("SP1_PROVER", "cpu");
-            let client = ProverClient::from_env();
-            let pk = client.setup(ELF).map_err(err_to_string)?;
-            let proof = client
-                .prove(&pk, stdin)
-                .groth16()
-                .run()
-                .map_err(err_to_string)?;
-            if verify_proof {
-                client
-                    .verify(&proof, pk.verifying_key(), None)
-                    .map_err(err_to_string)?;
-            }
-            (proof, pk)
-        }
-        "network" => {
-            return Err(
-                "SP1 network proving is disabled in this build because the upstream SP1 network \
-                 feature currently pulls a vulnerable legacy rustls-webpki dependency. Use \
-                 SP1_PROVER=local or SP1_PROVER=mock."
-                    .to_string(),
-            );
+    let proof = match proof {
+        Some(proof) => {
+            info!("Reusing the proof of a previous attempt");
+            proof
         }
-        other => return Err(format!("Unsupported SP1_PROVER mode: {other}")),
+        None => backend.prove(&input, proof_system)?,
     };
 
     // Log the committed public values for debugging
-    let committed_public_values = proof.public_values.to_vec();
+    let committed_public_values = proof.public_values.clone();
     info!(
         "Committed public values (hex): 0x{}",
         hex::encode(&committed_public_values)
@@ -112,23 +86,32 @@ pub fn generate_proof(
         );
         info!("Decoded amount: 0x{}", hex::encode(&decoded.amount));
         info!("Decoded challenge: 0x{}", hex::encode(&decoded.challenge));
+        info!("Decoded scheme: {}", decoded.scheme.as_str());
     }
 
-    let proof_bytes = proof.bytes();
-
-    if let Some(config) = verify_onchain {
-        let pubkey = ss58_decode(&ss58_address).map_err(err_to_string)?;
+    // The verifier gateway only accepts Groth16 and PLONK proofs
+    if let Some(config) = verify_onchain.filter(|_| proof_system.is_onchain()) {
         let public_values = PublicValues {
-            pubkey,
+            pubkey: account_id,
             evm_address,
             amount,
             challenge,
+            scheme,
         };
-        verify_onchain_proof(&config, public_values, proof_bytes.clone())
-            .map_err(|err| format!("On-chain verify failed: {err}"))?;
+        verify_onchain_proof(&config, public_values, proof.proof.clone()).map_err(|err| {
+            err.context("On-chain verify failed")
+                .with_proof(proof.clone())
+        })?;
     }
 
-    let proof_hex = format!("0x{}", hex::encode(proof_bytes));
+    let proof_bytes = proof.proof;
+
+    let zk_proof = if proof_system.is_onchain() {
+        encode_zk_proof(scheme, &proof_bytes)
+    } else {
+        proof_bytes
+    };
+    let proof_hex = format!("0x{}", hex::encode(zk_proof));
     let public_values_hex = format!("0x{}", hex::encode(&committed_public_values));
     Ok((proof_hex, public_values_hex))
 }
@@ -138,7 +121,7 @@ fn verify_onchain_proof(
     config: &VerifyOnchainConfig,
     public_values: PublicValues,
     proof: Vec<u8>,
-) -> Result<(), String> {
+) -> Result<(), ProveError> {
     let call = verifyProofCall {
         programVKey: FixedBytes::<32>::from_slice(&config.program_vkey),
         publicValues: Bytes::from(public_values.abi_encode()),
@@ -160,26 +143,29 @@ fn verify_onchain_proof(
     let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(config.timeout_seconds))
         .build()
-        .map_err(err_to_string)?;
+        .map_err(|err| ProveError::new(ErrorClass::Internal, err.to_string()))?;
 
+    // Anything before the verifier answers is the RPC's fault and worth retrying
     let response = client
         .post(&config.rpc_url)
         .json(&payload)
         .send()
-        .map_err(err_to_string)?;
+        .map_err(|err| ProveError::transient(err.to_string()))?;
 
     let status = response.status();
-    let body: serde_json::Value = response.json().map_err(err_to_string)?;
     if !status.is_success() {
-        return Err(format!("RPC HTTP error {status}"));
+        return Err(ProveError::transient(format!("RPC HTTP error {status}")));
     }
+    let body: serde_json::Value = response
+        .json()
+        .map_err(|err| ProveError::transient(err.to_string()))?;
 
     if let Some(error) = body.get("error") {
-        return Err(format!("eth_call reverted: {error}"));
+        return Err(ProveError::proof(format!("eth_call reverted: {error}")));
     }
 
     if body.get("result").is_none() {
-        return Err("Missing eth_call result".to_string());
+        return Err(ProveError::proof("Missing eth_call result"));
     }
 
     Ok(())
@@ -249,7 +235,7 @@ pub async fn check_already_claimed(
     Ok(claimed_amount)
 }
 
-fn err_to_string(err: impl std::fmt::Display) -> String {
+pub(crate) fn err_to_string(err: impl std::fmt::Display) -> String {
     err.to_string()
 }
 
@@ -258,6 +244,7 @@ mod tests {
     use super::*;
     use httpmock::{Method::POST, MockServer};
     use serde_json::json;
+    use sr25519_claim_lib::SignatureScheme;
 
     // Note: These tests require the SP1 SDK which is not available in unit tests
     // Integration tests should be used for actual proof generation
@@ -288,6 +275,7 @@ mod tests {
             evm_address: [0x44; 20],
             amount: [0x55; 32],
             challenge: [0x66; 32],
+            scheme: SignatureScheme::Sr25519,
         };
 
         let result = verify_onchain_proof(&config, public_values, vec![0xaa, 0xbb]);
@@ -316,14 +304,45 @@ mod tests {
             evm_address: [0x44; 20],
             amount: [0x55; 32],
             challenge: [0x66; 32],
+            scheme: SignatureScheme::Sr25519,
         };
 
         let err = verify_onchain_proof(&config, public_values, vec![0xaa, 0xbb])
             .expect_err("expected eth_call error");
-        assert!(err.contains("eth_call reverted"));
+        assert_eq!(err.class, ErrorClass::Proof);
+        assert!(err.message.contains("eth_call reverted"));
         mock.assert();
     }
 
+    #[test]
+    fn test_verify_onchain_proof_rpc_error_is_transient() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST);
+            then.status(502).body("<html>Bad Gateway</html>");
+        });
+
+        let config = VerifyOnchainConfig {
+            rpc_url: server.url("/"),
+            verifier_address: [0x11; 20],
+            program_vkey: [0x22; 32],
+            timeout_seconds: 5,
+        };
+
+        let public_values = PublicValues {
+            pubkey: [0x33; 32],
+            evm_address: [0x44; 20],
+            amount: [0x55; 32],
+            challenge: [0x66; 32],
+            scheme: SignatureScheme::Sr25519,
+        };
+
+        let err = verify_onchain_proof(&config, public_values, vec![0xaa, 0xbb])
+            .expect_err("expected RPC error");
+        assert_eq!(err.class, ErrorClass::Transient);
+        assert!(err.message.contains("RPC HTTP error 502"));
+    }
+
     #[tokio::test]
     async fn test_check_already_claimed_true_false() {
         let ss58_address = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
@@ -1,21 +1,258 @@
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
 use tracing::{error, info, warn};
 
+use crate::backend::ProverBackend;
 use crate::prover::generate_proof;
+use crate::retry::{ErrorClass, RetryPolicy};
 use crate::types::{
-    error_codes, AppConfig, CachedProof, JobEntry, JobMessage, JobStatus, ProofMetrics,
+    error_codes, now_ts, AppConfig, AttemptRecord, CachedProof, JobEntry, JobMessage, JobStatus,
+    ProofMetrics,
 };
 use crate::validation::cache_key;
 
+/// How long a failed claim counts as a retry when it is submitted again
+const RETRY_WINDOW_SECONDS: u64 = 3600;
+
+/// Scheduling class of a queued job, from most to least urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PriorityClass {
+    /// Automatic retry, or resubmission of a claim whose previous proof failed
+    Retry,
+    /// Submitted close to the migration contract's `claimDeadline`
+    Deadline,
+    /// Submitted with an API key that has a non-zero priority
+    Keyed,
+    /// Everything else
+    Standard,
+}
+
+impl PriorityClass {
+    pub const ALL: [PriorityClass; 4] = [
+        PriorityClass::Retry,
+        PriorityClass::Deadline,
+        PriorityClass::Keyed,
+        PriorityClass::Standard,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriorityClass::Retry => "retry",
+            PriorityClass::Deadline => "deadline",
+            PriorityClass::Keyed => "keyed",
+            PriorityClass::Standard => "standard",
+        }
+    }
+}
+
+/// Claim deadline settings used to classify jobs
+#[derive(Debug, Clone, Copy)]
+pub struct DeadlinePolicy {
+    /// Unix timestamp of the migration contract's `claimDeadline`
+    pub deadline: u64,
+    /// Jobs submitted within this many seconds of the deadline get the deadline class
+    pub window_seconds: u64,
+}
+
+impl DeadlinePolicy {
+    fn is_near(&self, now: u64) -> bool {
+        now.saturating_add(self.window_seconds) >= self.deadline
+    }
+}
+
+/// Jobs of one priority lane, served round-robin across clients
+#[derive(Debug, Default)]
+struct Lane {
+    /// Clients with waiting jobs, in the order they will next be served
+    clients: VecDeque<String>,
+    /// Waiting jobs per client (oldest first)
+    jobs: HashMap<String, VecDeque<JobMessage>>,
+}
+
+impl Lane {
+    fn push(&mut self, message: JobMessage) {
+        let queue = self.jobs.entry(message.client.clone()).or_default();
+        if queue.is_empty() {
+            self.clients.push_back(message.client.clone());
+        }
+        queue.push_back(message);
+    }
+
+    fn pop(&mut self) -> Option<JobMessage> {
+        let client = self.clients.pop_front()?;
+        let queue = self.jobs.get_mut(&client)?;
+        let message = queue.pop_front();
+        if queue.is_empty() {
+            self.jobs.remove(&client);
+        } else {
+            self.clients.push_back(client);
+        }
+        message
+    }
+
+    fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.jobs.values().map(VecDeque::len).sum()
+    }
+
+    /// Remove a waiting job, returning whether it was in this lane
+    fn remove(&mut self, job_id: &str) -> bool {
+        let found = self.jobs.iter().find_map(|(client, queue)| {
+            queue
+                .iter()
+                .position(|message| message.job_id == job_id)
+                .map(|idx| (client.clone(), idx))
+        });
+        let Some((client, idx)) = found else {
+            return false;
+        };
+
+        if let Some(queue) = self.jobs.get_mut(&client) {
+            queue.remove(idx);
+            if queue.is_empty() {
+                self.jobs.remove(&client);
+                self.clients.retain(|c| c != &client);
+            }
+        }
+        true
+    }
+
+    /// Job IDs in the order they would be served if nothing else arrived
+    fn dispatch_order(&self) -> Vec<&str> {
+        let mut order = Vec::with_capacity(self.len());
+        for round in 0.. {
+            let before = order.len();
+            for client in &self.clients {
+                if let Some(message) = self.jobs.get(client).and_then(|q| q.get(round)) {
+                    order.push(message.job_id.as_str());
+                }
+            }
+            if order.len() == before {
+                break;
+            }
+        }
+        order
+    }
+}
+
+/// Lanes are ordered by class, then by API key priority (highest first)
+type LaneKey = (PriorityClass, Reverse<u8>);
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    lanes: BTreeMap<LaneKey, Lane>,
+    len: usize,
+    /// Claims whose last proof failed, by cache key, with the failure time
+    recent_failures: HashMap<String, u64>,
+}
+
+impl SchedulerState {
+    fn classify(
+        &mut self,
+        message: &JobMessage,
+        deadline: Option<DeadlinePolicy>,
+        now: u64,
+    ) -> PriorityClass {
+        let failed_at = self.recent_failures.remove(&cache_key(&message.request));
+        if message.attempt > 1
+            || failed_at.is_some_and(|at| now.saturating_sub(at) <= RETRY_WINDOW_SECONDS)
+        {
+            PriorityClass::Retry
+        } else if deadline.is_some_and(|policy| policy.is_near(now)) {
+            PriorityClass::Deadline
+        } else if message.priority > 0 {
+            PriorityClass::Keyed
+        } else {
+            PriorityClass::Standard
+        }
+    }
+
+    fn push(&mut self, class: PriorityClass, message: JobMessage) {
+        self.lanes
+            .entry((class, Reverse(message.priority)))
+            .or_default()
+            .push(message);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<JobMessage> {
+        let (&key, lane) = self.lanes.iter_mut().next()?;
+        let message = lane.pop();
+        if lane.is_empty() {
+            self.lanes.remove(&key);
+        }
+        if message.is_some() {
+            self.len -= 1;
+        }
+        message
+    }
+
+    fn remove(&mut self, job_id: &str) -> bool {
+        let Some(key) = self
+            .lanes
+            .iter_mut()
+            .find_map(|(key, lane)| lane.remove(job_id).then_some(*key))
+        else {
+            return false;
+        };
+        if self.lanes.get(&key).is_some_and(Lane::is_empty) {
+            self.lanes.remove(&key);
+        }
+        self.len -= 1;
+        true
+    }
+
+    fn position(&self, job_id: &str) -> Option<usize> {
+        self.lanes
+            .values()
+            .flat_map(Lane::dispatch_order)
+            .position(|id| id == job_id)
+            .map(|idx| idx + 1)
+    }
+}
+
 /// Job queue for managing proof generation work
+///
+/// Jobs are served by lane (priority class, then API key priority) and round-robin
+/// across clients within a lane, so one client filling the queue can't starve the
+/// others. Cloning yields another handle to the same queue.
+#[derive(Clone)]
 pub struct JobQueue {
-    pub sender: mpsc::Sender<JobMessage>,
-    pub queue_size: Arc<AtomicUsize>,
-    pub capacity: usize,
+    inner: Arc<QueueInner>,
+}
+
+struct QueueInner {
+    state: std::sync::Mutex<SchedulerState>,
+    /// Signalled whenever a job is added
+    available: Notify,
+    capacity: usize,
+    deadline: Option<DeadlinePolicy>,
+}
+
+/// Estimate seconds until a job completes
+///
+/// Pending jobs wait for `ceil(position / workers)` proof rounds; running jobs need
+/// the remainder of one average proof. Returns `None` until a proof duration has been
+/// observed.
+pub fn estimate_eta_seconds(
+    position: Option<usize>,
+    running_for: Option<Duration>,
+    worker_count: usize,
+    average_proof_duration: Option<Duration>,
+) -> Option<u64> {
+    let average = average_proof_duration?;
+    if let Some(elapsed) = running_for {
+        return Some(average.saturating_sub(elapsed).as_secs());
+    }
+    let rounds = position?.div_ceil(worker_count.max(1)) as u32;
+    Some((average * rounds).as_secs())
 }
 
 /// Result of trying to enqueue a job
@@ -29,128 +266,239 @@ pub enum EnqueueResult {
 
 impl JobQueue {
     /// Create a new job queue with the given capacity
-    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<JobMessage>) {
-        let (sender, receiver) = mpsc::channel(capacity);
-        let queue = Self {
-            sender,
-            queue_size: Arc::new(AtomicUsize::new(0)),
-            capacity,
-        };
-        (queue, receiver)
+    ///
+    /// With a deadline policy, claims submitted close to the claim deadline are
+    /// scheduled ahead of regular ones.
+    pub fn new(capacity: usize, deadline: Option<DeadlinePolicy>) -> Self {
+        Self {
+            inner: Arc::new(QueueInner {
+                state: std::sync::Mutex::new(SchedulerState::default()),
+                available: Notify::new(),
+                capacity,
+                deadline,
+            }),
+        }
     }
 
     /// Try to enqueue a job
     pub async fn try_enqueue(&self, message: JobMessage) -> EnqueueResult {
-        // Check if queue is full before trying to send
-        let current_size = self.queue_size.load(Ordering::SeqCst);
-        if current_size >= self.capacity {
+        self.try_enqueue_batch(vec![message]).await
+    }
+
+    /// Try to enqueue a group of jobs, all or nothing
+    ///
+    /// Capacity for the whole group is checked under one lock, so a batch is never
+    /// split by a concurrent submission filling the queue.
+    pub async fn try_enqueue_batch(&self, messages: Vec<JobMessage>) -> EnqueueResult {
+        let mut state = self.lock();
+        if state.len + messages.len() > self.inner.capacity {
             return EnqueueResult::QueueFull;
         }
 
-        match self.sender.try_send(message) {
-            Ok(()) => {
-                self.queue_size.fetch_add(1, Ordering::SeqCst);
-                EnqueueResult::Queued
-            }
-            Err(mpsc::error::TrySendError::Full(_)) => EnqueueResult::QueueFull,
-            Err(mpsc::error::TrySendError::Closed(_)) => {
-                error!("Job queue channel closed");
-                EnqueueResult::QueueFull
+        let now = now_ts();
+        for message in messages {
+            let class = state.classify(&message, self.inner.deadline, now);
+            state.push(class, message);
+            self.inner.available.notify_one();
+        }
+        EnqueueResult::Queued
+    }
+
+    /// Wait for the next job to run
+    pub async fn recv(&self) -> JobMessage {
+        loop {
+            if let Some(message) = self.lock().pop() {
+                return message;
             }
+            // A notification sent between the pop and this await is stored, so no
+            // wakeup is lost
+            self.inner.available.notified().await;
         }
     }
 
-    /// Get a handle to the queue size counter
-    pub fn size_counter(&self) -> Arc<AtomicUsize> {
-        self.queue_size.clone()
+    /// Remove a waiting job, freeing its slot
+    ///
+    /// Returns `false` if the job isn't in the queue (already picked up, or waiting
+    /// out a retry backoff).
+    pub fn cancel(&self, job_id: &str) -> bool {
+        self.lock().remove(job_id)
     }
 
-    /// Create a queue wrapper from an existing sender and shared size counter
-    pub fn from_sender(
-        sender: mpsc::Sender<JobMessage>,
-        capacity: usize,
-        queue_size: Arc<AtomicUsize>,
-    ) -> Self {
-        Self {
-            sender,
-            queue_size,
-            capacity,
+    /// Remember that a claim's proof failed, so resubmitting it counts as a retry
+    pub fn record_failure(&self, cache_key: String) {
+        let now = now_ts();
+        let mut state = self.lock();
+        state
+            .recent_failures
+            .retain(|_, failed_at| now.saturating_sub(*failed_at) <= RETRY_WINDOW_SECONDS);
+        state.recent_failures.insert(cache_key, now);
+    }
+
+    /// Number of jobs waiting for a worker
+    pub fn depth(&self) -> usize {
+        self.lock().len
+    }
+
+    /// 1-based position of a job in dispatch order (1 = next to be picked up)
+    pub fn position(&self, job_id: &str) -> Option<usize> {
+        self.lock().position(job_id)
+    }
+
+    /// Number of waiting jobs per priority class
+    pub fn depth_by_class(&self) -> BTreeMap<String, usize> {
+        let state = self.lock();
+        let mut depth: BTreeMap<String, usize> = PriorityClass::ALL
+            .iter()
+            .map(|class| (class.as_str().to_string(), 0))
+            .collect();
+        for ((class, _), lane) in &state.lanes {
+            *depth.entry(class.as_str().to_string()).or_default() += lane.len();
         }
+        depth
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SchedulerState> {
+        // Scheduler state is only mutated in short non-panicking sections
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
 /// Worker pool for processing jobs
+///
+/// Each worker owns a prepared prover backend and runs one job at a time.
 pub struct WorkerPool {
-    worker_count: usize,
+    backends: Vec<Arc<dyn ProverBackend>>,
 }
 
 impl WorkerPool {
-    pub fn new(worker_count: usize) -> Self {
-        Self { worker_count }
+    /// Create a pool with one worker per prepared backend
+    pub fn new(backends: Vec<Arc<dyn ProverBackend>>) -> Self {
+        Self { backends }
     }
 
-    /// Start workers that process jobs from the receiver
+    /// Start workers that process jobs from the queue
     pub fn start(
         &self,
-        mut receiver: mpsc::Receiver<JobMessage>,
+        queue: JobQueue,
         jobs: Arc<Mutex<HashMap<String, JobEntry>>>,
         cache: Arc<Mutex<HashMap<String, CachedProof>>>,
         config: Arc<AppConfig>,
-        queue_size: Arc<AtomicUsize>,
         metrics: Arc<ProofMetrics>,
     ) {
-        let worker_count = self.worker_count;
         let proof_timeout = Duration::from_secs(config.proof_timeout_seconds);
+        info!(
+            "Starting {} workers for job processing",
+            self.backends.len()
+        );
+
+        for backend in &self.backends {
+            let backend = backend.clone();
+            let queue = queue.clone();
+            let jobs = jobs.clone();
+            let cache = cache.clone();
+            let config = config.clone();
+            let metrics = metrics.clone();
+
+            tokio::spawn(async move {
+                loop {
+                    // A worker only takes a job once it is free, so the scheduler picks
+                    // the most urgent job at that moment
+                    let message = queue.recv().await;
 
-        // Spawn a task that distributes work to workers
-        let jobs_clone = jobs.clone();
-        let cache_clone = cache.clone();
-        let config_clone = config.clone();
-        let metrics_clone = metrics.clone();
-
-        tokio::spawn(async move {
-            info!("Starting {} workers for job processing", worker_count);
-
-            // Use a semaphore to limit concurrent proof generation
-            // IMPORTANT: Permits are held until the underlying task completes,
-            // even after timeout. This prevents unbounded concurrency.
-            let semaphore = Arc::new(tokio::sync::Semaphore::new(worker_count));
-
-            while let Some(message) = receiver.recv().await {
-                queue_size.fetch_sub(1, Ordering::SeqCst);
-
-                // Acquire an owned permit that can be moved into the spawned task
-                // The permit will be held until process_job completes (including
-                // waiting for timed-out blocking tasks to finish)
-                let permit = match semaphore.clone().acquire_owned().await {
-                    Ok(p) => p,
-                    Err(_) => {
-                        error!("Semaphore closed");
+                    let retry = process_job(
+                        message.clone(),
+                        jobs.clone(),
+                        cache.clone(),
+                        config.clone(),
+                        proof_timeout,
+                        metrics.clone(),
+                        backend.clone(),
+                    )
+                    .await;
+
+                    if let Some(delay) = retry {
+                        info!(
+                            "Job {} will be retried in {:?} (attempt {})",
+                            message.job_id,
+                            delay,
+                            message.attempt + 1
+                        );
+                        tokio::spawn(requeue_after(queue.clone(), jobs.clone(), message, delay));
                         continue;
                     }
-                };
 
-                let jobs = jobs_clone.clone();
-                let cache = cache_clone.clone();
-                let config = config_clone.clone();
-                let metrics = metrics_clone.clone();
-                let timeout = proof_timeout;
+                    // Resubmissions of a failed claim are scheduled as retries
+                    let failed = matches!(
+                        jobs.lock()
+                            .await
+                            .get(&message.job_id)
+                            .map(|entry| &entry.status),
+                        Some(JobStatus::Failed { .. })
+                    );
+                    if failed {
+                        queue.record_failure(cache_key(&message.request));
+                    }
+                }
+            });
+        }
+    }
+}
 
-                tokio::spawn(async move {
-                    process_job(message, jobs, cache, config, timeout, metrics, permit).await;
-                });
-            }
+/// Put a job back in the queue once its retry backoff has passed
+///
+/// Jobs cancelled during the backoff are dropped. If the queue is full by then, the
+/// job fails with the error of its last attempt.
+async fn requeue_after(
+    queue: JobQueue,
+    jobs: Arc<Mutex<HashMap<String, JobEntry>>>,
+    message: JobMessage,
+    delay: Duration,
+) {
+    tokio::time::sleep(delay).await;
 
-            warn!("Job receiver closed, workers stopping");
-        });
+    let pending = matches!(
+        jobs.lock()
+            .await
+            .get(&message.job_id)
+            .map(|entry| &entry.status),
+        Some(JobStatus::Pending)
+    );
+    if !pending {
+        info!(
+            "Job {} is no longer pending, dropping its retry",
+            message.job_id
+        );
+        return;
+    }
+
+    let job_id = message.job_id.clone();
+    let key = cache_key(&message.request);
+    let retry = JobMessage {
+        attempt: message.attempt + 1,
+        ..message
+    };
+    if let EnqueueResult::QueueFull = queue.try_enqueue(retry).await {
+        warn!("Queue full, giving up on retrying job {}", job_id);
+        let mut jobs = jobs.lock().await;
+        if let Some(entry) = jobs.get_mut(&job_id) {
+            let error = entry
+                .attempts
+                .last()
+                .map(|attempt| attempt.error.clone())
+                .unwrap_or_default();
+            entry.set_status(JobStatus::Failed { error });
+        }
+        queue.record_failure(key);
     }
 }
 
 /// Process a single job
 ///
-/// The `_permit` parameter holds the semaphore permit for the duration of this function.
-/// This is critical: the permit is NOT released until the underlying blocking task
-/// completes, even if a timeout occurs. This prevents unbounded concurrency.
+/// This does not return until the underlying blocking task completes, even if a
+/// timeout occurs, so a worker never has more than one proof in flight. This prevents
+/// unbounded concurrency.
+///
+/// Returns the backoff before the next attempt if the job failed and will be retried.
 async fn process_job(
     message: JobMessage,
     jobs: Arc<Mutex<HashMap<String, JobEntry>>>,
@@ -158,23 +506,38 @@ async fn process_job(
     config: Arc<AppConfig>,
     timeout: Duration,
     metrics: Arc<ProofMetrics>,
-    _permit: OwnedSemaphorePermit, // Held until function returns (after task completes)
-) {
+    backend: Arc<dyn ProverBackend>,
+) -> Option<Duration> {
     let job_id = message.job_id.clone();
     let cache_key = cache_key(&message.request);
+    let proof_system = message.request.proof_system();
+    let retry_policy = &config.retry_policy;
+
+    // Update job to running, unless it was cancelled after being dequeued
+    if !begin_job(&jobs, &job_id).await {
+        info!("Job {} was cancelled before it started", job_id);
+        return None;
+    }
+    info!(
+        "Job {} started (priority {}, attempt {})",
+        job_id, message.priority, message.attempt
+    );
 
-    // Update job to running
-    update_job(&jobs, &job_id, JobStatus::Running).await;
+    // A retry after a failed on-chain verification only repeats the verification
+    let unverified_proof = jobs
+        .lock()
+        .await
+        .get_mut(&job_id)
+        .and_then(|entry| entry.unverified_proof.take());
 
     // Generate proof with timeout
-    let verify_proof = config.verify_proof;
     let verify_onchain = config.verify_onchain.clone();
-    let prover_mode = config.prover_mode.clone();
     let request = message.request.clone();
 
     // Spawn the blocking task - we keep the handle to track completion after timeout
+    let started = Instant::now();
     let handle = tokio::task::spawn_blocking(move || {
-        generate_proof(request, verify_proof, verify_onchain, &prover_mode)
+        generate_proof(&*backend, request, verify_onchain, unverified_proof)
     });
 
     // Pin the handle so we can use it with select! and still access it after timeout
@@ -192,13 +555,14 @@ async fn process_job(
         Some(Ok(Ok((zk_proof, public_values)))) => {
             // Record successful completion
             metrics.record_completion();
+            metrics.record_proof_duration(&config.prover_mode, started.elapsed());
 
             // Store in cache
             {
                 let mut c = cache.lock().await;
                 c.insert(
                     cache_key,
-                    CachedProof::new(zk_proof.clone(), public_values.clone()),
+                    CachedProof::new(zk_proof.clone(), public_values.clone(), proof_system),
                 );
             }
 
@@ -209,56 +573,46 @@ async fn process_job(
                 JobStatus::Completed {
                     zk_proof,
                     public_values,
+                    proof_system,
                 },
             )
             .await;
         }
         Some(Ok(Err(err))) => {
             error!("Proof generation failed for job {}: {}", job_id, err);
-            update_job(
-                &jobs,
-                &job_id,
-                JobStatus::Failed {
-                    error: format!("{}: {}", error_codes::PROOF_FAILED, err),
-                },
-            )
-            .await;
+            let error = format!("{}: {}", error_codes::PROOF_FAILED, err);
+            let retry = fail_job(&jobs, &message, err.class, error, retry_policy).await;
+            if let (Some(_), Some(proof)) = (retry, err.proof) {
+                if let Some(entry) = jobs.lock().await.get_mut(&job_id) {
+                    entry.unverified_proof = Some(proof);
+                }
+            }
+            return retry;
         }
         Some(Err(join_err)) => {
             error!("Job {} panicked: {}", job_id, join_err);
-            update_job(
-                &jobs,
-                &job_id,
-                JobStatus::Failed {
-                    error: format!("{}: task panicked", error_codes::INTERNAL_ERROR),
-                },
-            )
-            .await;
+            let error = format!("{}: task panicked", error_codes::INTERNAL_ERROR);
+            return fail_job(&jobs, &message, ErrorClass::Internal, error, retry_policy).await;
         }
         None => {
             // Timeout fired - mark job as failed immediately for user feedback
             metrics.record_timeout();
 
             error!(
-                "Job {} timed out after {:?} - waiting for task to complete before releasing permit",
+                "Job {} timed out after {:?} - waiting for task to complete before taking another job",
                 job_id,
                 timeout,
             );
 
-            update_job(
-                &jobs,
-                &job_id,
-                JobStatus::Failed {
-                    error: format!(
-                        "{}: proof generation exceeded {} seconds",
-                        error_codes::TIMEOUT,
-                        timeout.as_secs()
-                    ),
-                },
-            )
-            .await;
+            let error = format!(
+                "{}: proof generation exceeded {} seconds",
+                error_codes::TIMEOUT,
+                timeout.as_secs()
+            );
+            let mut retry =
+                fail_job(&jobs, &message, ErrorClass::Timeout, error, retry_policy).await;
 
-            // CRITICAL: Wait for the blocking task to actually finish before releasing permit.
+            // CRITICAL: Wait for the blocking task to actually finish before returning.
             // This ensures true concurrency limits are enforced. The job is already marked
             // as failed for the user, but we prevent resource exhaustion by not starting
             // new jobs until this one completes.
@@ -267,7 +621,7 @@ async fn process_job(
             // This may take a long time (SP1 proofs can take 10+ minutes), but
             // it's necessary to prevent unbounded cost and resource usage.
             warn!(
-                "Job {} timed out, holding permit while waiting for blocking task to complete (timed_out_still_running={})",
+                "Job {} timed out, holding worker while waiting for blocking task to complete (timed_out_still_running={})",
                 job_id,
                 metrics.timed_out_still_running.load(Ordering::Relaxed)
             );
@@ -275,12 +629,13 @@ async fn process_job(
             // Wait for the actual task to complete (handle is still valid after select!)
             match handle.await {
                 Ok(Ok((zk_proof, public_values))) => {
+                    retry = None;
                     metrics.record_completion();
                     {
                         let mut c = cache.lock().await;
                         c.insert(
                             cache_key,
-                            CachedProof::new(zk_proof.clone(), public_values.clone()),
+                            CachedProof::new(zk_proof.clone(), public_values.clone(), proof_system),
                         );
                     }
                     update_job(
@@ -289,12 +644,17 @@ async fn process_job(
                         JobStatus::Completed {
                             zk_proof,
                             public_values,
+                            proof_system,
                         },
                     )
                     .await;
                 }
-                Ok(Err(err)) => {
-                    error!("Proof generation failed for job {} after timeout: {}", job_id, err);
+                // A job already rescheduled keeps its timeout as the attempt's error
+                Ok(Err(err)) if retry.is_none() => {
+                    error!(
+                        "Proof generation failed for job {} after timeout: {}",
+                        job_id, err
+                    );
                     update_job(
                         &jobs,
                         &job_id,
@@ -304,11 +664,14 @@ async fn process_job(
                     )
                     .await;
                 }
-                Err(join_err) => {
+                Ok(Err(err)) => {
                     error!(
-                        "Job {} panicked after timeout: {}",
-                        job_id, join_err
+                        "Proof generation failed for job {} after timeout: {}",
+                        job_id, err
                     );
+                }
+                Err(join_err) if retry.is_none() => {
+                    error!("Job {} panicked after timeout: {}", job_id, join_err);
                     update_job(
                         &jobs,
                         &job_id,
@@ -318,6 +681,9 @@ async fn process_job(
                     )
                     .await;
                 }
+                Err(join_err) => {
+                    error!("Job {} panicked after timeout: {}", job_id, join_err);
+                }
             }
 
             // Task completed - update metrics
@@ -327,24 +693,67 @@ async fn process_job(
                 job_id,
                 metrics.timed_out_still_running.load(Ordering::Relaxed)
             );
+            return retry;
         }
     }
-    // Permit is dropped here, releasing the semaphore slot
+    // The worker is free to take the next job
+    None
+}
+
+/// Move a pending job to running, unless it was cancelled in the meantime
+async fn begin_job(jobs: &Arc<Mutex<HashMap<String, JobEntry>>>, job_id: &str) -> bool {
+    let mut jobs = jobs.lock().await;
+    match jobs.get_mut(job_id) {
+        Some(entry) if matches!(entry.status, JobStatus::Pending) => {
+            entry.set_status(JobStatus::Running);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Record a failed attempt and decide whether the job is retried
+///
+/// Retryable jobs go straight back to pending, so status readers never see a failure
+/// that is about to be retried. Returns the backoff before the next attempt.
+async fn fail_job(
+    jobs: &Arc<Mutex<HashMap<String, JobEntry>>>,
+    message: &JobMessage,
+    error_class: ErrorClass,
+    error: String,
+    policy: &RetryPolicy,
+) -> Option<Duration> {
+    let retry = policy.backoff(error_class, message.attempt);
+
+    let mut jobs = jobs.lock().await;
+    let entry = jobs.get_mut(&message.job_id)?;
+    entry.attempts.push(AttemptRecord {
+        attempt: message.attempt,
+        started_at: entry.started_at,
+        failed_at: now_ts(),
+        error_class,
+        error: error.clone(),
+    });
+    match retry {
+        Some(_) => entry.set_status(JobStatus::Pending),
+        None => entry.set_status(JobStatus::Failed { error }),
+    }
+    retry
 }
 
 /// Update a job's status
 async fn update_job(jobs: &Arc<Mutex<HashMap<String, JobEntry>>>, job_id: &str, status: JobStatus) {
     let mut jobs = jobs.lock().await;
     if let Some(entry) = jobs.get_mut(job_id) {
-        entry.status = status;
-        entry.updated_at = crate::types::now_ts();
+        entry.set_status(status);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::ProveRequest;
+    use crate::types::{ProveRequest, WrappingMode};
+    use sr25519_claim_lib::SignatureScheme;
 
     fn test_request() -> ProveRequest {
         ProveRequest {
@@ -353,88 +762,585 @@ mod tests {
             evm_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f4a3b2".to_string(),
             challenge: format!("0x{}", "12".repeat(32)),
             amount: "1000000000000000000".to_string(),
+            signature_scheme: SignatureScheme::Sr25519,
+            signature_wrapping: WrappingMode::Auto,
+            proof_system: None,
+        }
+    }
+
+    fn message(job_id: &str, client: &str, priority: u8) -> JobMessage {
+        JobMessage {
+            job_id: job_id.to_string(),
+            request: test_request(),
+            priority,
+            client: client.to_string(),
+            attempt: 1,
+        }
+    }
+
+    /// Drain the queue, returning job IDs in dispatch order
+    async fn drain(queue: &JobQueue) -> Vec<String> {
+        let mut order = Vec::new();
+        while queue.depth() > 0 {
+            order.push(queue.recv().await.job_id);
         }
+        order
     }
 
     #[tokio::test]
     async fn test_queue_enqueue() {
-        let (queue, _receiver) = JobQueue::new(10);
+        let queue = JobQueue::new(10, None);
 
-        let result = queue
-            .try_enqueue(JobMessage {
-                job_id: "job1".to_string(),
-                request: test_request(),
-            })
-            .await;
+        let result = queue.try_enqueue(message("job1", "ip:a", 0)).await;
 
         assert!(matches!(result, EnqueueResult::Queued));
-        assert_eq!(queue.queue_size.load(Ordering::SeqCst), 1);
+        assert_eq!(queue.depth(), 1);
     }
 
     #[tokio::test]
     async fn test_queue_full() {
-        let (queue, _receiver) = JobQueue::new(2);
+        let queue = JobQueue::new(2, None);
 
         // Fill the queue
-        queue
-            .try_enqueue(JobMessage {
-                job_id: "job1".to_string(),
-                request: test_request(),
-            })
-            .await;
-        queue
-            .try_enqueue(JobMessage {
-                job_id: "job2".to_string(),
-                request: test_request(),
-            })
-            .await;
+        queue.try_enqueue(message("job1", "ip:a", 0)).await;
+        queue.try_enqueue(message("job2", "ip:a", 0)).await;
 
         // Third should fail
+        let result = queue.try_enqueue(message("job3", "ip:b", 0)).await;
+
+        assert!(matches!(result, EnqueueResult::QueueFull));
+        assert_eq!(queue.depth(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_queue_dequeue_updates_size() {
+        let queue = JobQueue::new(10, None);
+
+        queue.try_enqueue(message("job1", "ip:a", 0)).await;
+        assert_eq!(queue.depth(), 1);
+
+        let msg = queue.recv().await;
+        assert_eq!(msg.job_id, "job1");
+        assert_eq!(queue.depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_recv_waits_for_jobs() {
+        let queue = JobQueue::new(10, None);
+
+        let receiver = queue.clone();
+        let handle = tokio::spawn(async move { receiver.recv().await.job_id });
+        tokio::task::yield_now().await;
+
+        queue.try_enqueue(message("late", "ip:a", 0)).await;
+        assert_eq!(handle.await.unwrap(), "late");
+    }
+
+    #[tokio::test]
+    async fn test_queue_enqueue_batch_all_or_nothing() {
+        let queue = JobQueue::new(3, None);
+
         let result = queue
-            .try_enqueue(JobMessage {
-                job_id: "job3".to_string(),
-                request: test_request(),
-            })
+            .try_enqueue_batch(vec![
+                message("a", "apikey:x", 0),
+                message("b", "apikey:x", 0),
+            ])
             .await;
+        assert!(matches!(result, EnqueueResult::Queued));
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(queue.position("b"), Some(2));
 
+        // Only one slot left, so a batch of two is rejected as a whole
+        let result = queue
+            .try_enqueue_batch(vec![
+                message("c", "apikey:x", 0),
+                message("d", "apikey:x", 0),
+            ])
+            .await;
         assert!(matches!(result, EnqueueResult::QueueFull));
-        assert_eq!(queue.queue_size.load(Ordering::SeqCst), 2);
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(queue.position("c"), None);
     }
 
     #[tokio::test]
-    async fn test_queue_dequeue_updates_size() {
-        let (queue, mut receiver) = JobQueue::new(10);
+    async fn test_queue_tracks_positions() {
+        let queue = JobQueue::new(10, None);
+
+        for id in ["job1", "job2", "job3"] {
+            queue.try_enqueue(message(id, "ip:a", 0)).await;
+        }
+
+        assert_eq!(queue.position("job1"), Some(1));
+        assert_eq!(queue.position("job3"), Some(3));
+
+        // Worker picks up the head of the queue
+        assert_eq!(queue.recv().await.job_id, "job1");
+        assert_eq!(queue.position("job1"), None);
+        assert_eq!(queue.position("job2"), Some(1));
+        assert_eq!(queue.position("job3"), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_queue_full_does_not_track_position() {
+        let queue = JobQueue::new(1, None);
+
+        for id in ["job1", "job2"] {
+            queue.try_enqueue(message(id, "ip:a", 0)).await;
+        }
+
+        assert_eq!(queue.position("job1"), Some(1));
+        assert_eq!(queue.position("job2"), None);
+    }
+
+    #[tokio::test]
+    async fn test_queue_fair_across_clients() {
+        let queue = JobQueue::new(10, None);
+
+        // A noisy client fills the queue before anyone else arrives
+        for i in 1..=4 {
+            queue
+                .try_enqueue(message(&format!("noisy{i}"), "ip:a", 0))
+                .await;
+        }
+        queue.try_enqueue(message("quiet1", "ip:b", 0)).await;
+        queue.try_enqueue(message("quiet2", "ip:c", 0)).await;
+
+        // Positions match the round-robin dispatch order
+        assert_eq!(queue.position("quiet1"), Some(2));
+        assert_eq!(queue.position("noisy4"), Some(6));
+
+        assert_eq!(
+            drain(&queue).await,
+            ["noisy1", "quiet1", "quiet2", "noisy2", "noisy3", "noisy4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_serves_higher_priority_first() {
+        let queue = JobQueue::new(10, None);
+
+        queue.try_enqueue(message("anon", "ip:a", 0)).await;
+        queue.try_enqueue(message("basic", "apikey:basic", 1)).await;
+        queue
+            .try_enqueue(message("partner", "apikey:partner", 10))
+            .await;
+
+        assert_eq!(queue.position("partner"), Some(1));
+        assert_eq!(drain(&queue).await, ["partner", "basic", "anon"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_retry_class() {
+        let queue = JobQueue::new(10, None);
 
+        queue
+            .try_enqueue(message("keyed", "apikey:partner", 10))
+            .await;
+        queue.record_failure(cache_key(&test_request()));
+
+        // The failed claim comes back ahead of everything else
+        queue.try_enqueue(message("retry", "ip:a", 0)).await;
+        assert_eq!(queue.depth_by_class()["retry"], 1);
+        assert_eq!(queue.position("retry"), Some(1));
+
+        // The failure mark is consumed by the retry
+        queue.try_enqueue(message("again", "ip:a", 0)).await;
+        assert_eq!(queue.depth_by_class()["standard"], 1);
+        assert_eq!(drain(&queue).await, ["retry", "keyed", "again"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_automatic_retry_class() {
+        let queue = JobQueue::new(10, None);
+
+        queue.try_enqueue(message("first", "ip:a", 0)).await;
         queue
             .try_enqueue(JobMessage {
-                job_id: "job1".to_string(),
-                request: test_request(),
+                attempt: 2,
+                ..message("retried", "ip:b", 0)
             })
             .await;
 
-        assert_eq!(queue.queue_size.load(Ordering::SeqCst), 1);
+        assert_eq!(queue.depth_by_class()["retry"], 1);
+        assert_eq!(drain(&queue).await, ["retried", "first"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_cancel_frees_slot() {
+        let queue = JobQueue::new(2, None);
+
+        queue.try_enqueue(message("job1", "ip:a", 0)).await;
+        queue.try_enqueue(message("job2", "ip:b", 0)).await;
+        assert!(matches!(
+            queue.try_enqueue(message("job3", "ip:c", 0)).await,
+            EnqueueResult::QueueFull
+        ));
+
+        assert!(queue.cancel("job1"));
+        assert!(!queue.cancel("job1"));
+        assert_eq!(queue.depth(), 1);
+        assert_eq!(queue.position("job2"), Some(1));
+
+        assert!(matches!(
+            queue.try_enqueue(message("job3", "ip:c", 0)).await,
+            EnqueueResult::Queued
+        ));
+        assert_eq!(drain(&queue).await, ["job2", "job3"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_cancel_keeps_client_rotation() {
+        let queue = JobQueue::new(10, None);
+
+        queue.try_enqueue(message("a1", "ip:a", 0)).await;
+        queue.try_enqueue(message("a2", "ip:a", 0)).await;
+        queue.try_enqueue(message("b1", "ip:b", 0)).await;
+
+        // Cancelling a client's only job removes it from the rotation
+        assert!(queue.cancel("b1"));
+        assert!(queue.cancel("a1"));
+        assert_eq!(drain(&queue).await, ["a2"]);
+        assert!(queue.inner.state.lock().unwrap().lanes.is_empty());
+    }
+
+    fn test_jobs(job_id: &str) -> Arc<Mutex<HashMap<String, JobEntry>>> {
+        let mut jobs = HashMap::new();
+        let mut entry = JobEntry::new(JobStatus::Pending);
+        entry.set_status(JobStatus::Running);
+        jobs.insert(job_id.to_string(), entry);
+        Arc::new(Mutex::new(jobs))
+    }
+
+    #[tokio::test]
+    async fn test_fail_job_schedules_retry() {
+        let jobs = test_jobs("job1");
+        let policy = RetryPolicy::default();
+        let transient = "proof_failed: On-chain verify failed: RPC HTTP error 503";
+
+        let retry = fail_job(
+            &jobs,
+            &message("job1", "ip:a", 0),
+            ErrorClass::Transient,
+            transient.into(),
+            &policy,
+        )
+        .await;
+        assert_eq!(retry, policy.backoff(ErrorClass::Transient, 1));
+
+        let jobs = jobs.lock().await;
+        let entry = &jobs["job1"];
+        assert!(matches!(entry.status, JobStatus::Pending));
+        assert!(entry.started_at.is_none());
+        assert_eq!(entry.attempts.len(), 1);
+        assert_eq!(entry.attempts[0].error_class, ErrorClass::Transient);
+        assert!(entry.attempts[0].started_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_fail_job_gives_up_after_max_attempts() {
+        let jobs = test_jobs("job1");
+        let policy = RetryPolicy::default();
+        let last = JobMessage {
+            attempt: policy.transient.max_attempts,
+            ..message("job1", "ip:a", 0)
+        };
+
+        let error = "proof_failed: error sending request for url".to_string();
+        assert_eq!(
+            fail_job(&jobs, &last, ErrorClass::Transient, error.clone(), &policy).await,
+            None
+        );
+
+        let jobs = jobs.lock().await;
+        let entry = &jobs["job1"];
+        assert!(matches!(&entry.status, JobStatus::Failed { error: e } if *e == error));
+        assert_eq!(entry.attempts[0].attempt, policy.transient.max_attempts);
+    }
+
+    #[tokio::test]
+    async fn test_fail_job_does_not_retry_proof_errors() {
+        let jobs = test_jobs("job1");
+        let error = "proof_failed: invalid signature".to_string();
+        let retry = fail_job(
+            &jobs,
+            &message("job1", "ip:a", 0),
+            ErrorClass::Proof,
+            error,
+            &RetryPolicy::default(),
+        )
+        .await;
+
+        assert_eq!(retry, None);
+        assert!(matches!(
+            jobs.lock().await["job1"].status,
+            JobStatus::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requeue_after_backoff() {
+        let queue = JobQueue::new(10, None);
+        let jobs = Arc::new(Mutex::new(HashMap::from([
+            ("job1".to_string(), JobEntry::new(JobStatus::Pending)),
+            ("job2".to_string(), JobEntry::new(JobStatus::Cancelled)),
+        ])));
+
+        requeue_after(
+            queue.clone(),
+            jobs.clone(),
+            message("job1", "ip:a", 0),
+            Duration::ZERO,
+        )
+        .await;
+        requeue_after(
+            queue.clone(),
+            jobs.clone(),
+            message("job2", "ip:b", 0),
+            Duration::ZERO,
+        )
+        .await;
+
+        // Only the pending job comes back, as its second attempt
+        assert_eq!(queue.depth(), 1);
+        let retried = queue.recv().await;
+        assert_eq!(retried.job_id, "job1");
+        assert_eq!(retried.attempt, 2);
+    }
+
+    #[tokio::test]
+    async fn test_process_job_fails_on_invalid_remote_proof() {
+        use crate::backend::RemoteBackend;
+        use crate::types::RemoteProverConfig;
+        use crate::validation::{parse_amount, parse_hex_bytes};
+        use httpmock::{Method::GET, Method::POST, MockServer};
+        use sr25519_claim_lib::{ss58_decode, PublicValues};
+
+        // The remote commits the right public values but returns a bogus proof
+        let request = test_request();
+        let public_values = PublicValues {
+            pubkey: ss58_decode(&request.ss58_address).unwrap(),
+            evm_address: parse_hex_bytes::<20>(&request.evm_address).unwrap(),
+            amount: parse_amount(&request.amount).unwrap(),
+            challenge: parse_hex_bytes::<32>(&request.challenge).unwrap(),
+            scheme: request.signature_scheme,
+        };
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/proofs");
+            then.status(200).json_body(serde_json::json!({"id": "p1"}));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/proofs/p1");
+            then.status(200).json_body(serde_json::json!({
+                "status": "completed",
+                "proof": format!("0x{}", "ab".repeat(260)),
+                "publicValues": format!("0x{}", hex::encode(public_values.abi_encode())),
+            }));
+        });
+        // A blocking HTTP client can't be built (or dropped) on the async runtime
+        let config = RemoteProverConfig {
+            url: server.base_url(),
+            api_key: None,
+            poll_interval_ms: 10,
+            request_timeout_seconds: 5,
+            timeout_seconds: 5,
+        };
+        let backend =
+            tokio::task::spawn_blocking(move || RemoteBackend::new(config, [0x22; 32], true))
+                .await
+                .unwrap()
+                .unwrap();
+
+        let jobs = Arc::new(Mutex::new(HashMap::from([(
+            "job1".to_string(),
+            JobEntry::new(JobStatus::Pending),
+        )])));
+        let cache = Arc::new(Mutex::new(HashMap::new()));
+        let retry = process_job(
+            message("job1", "ip:a", 0),
+            jobs.clone(),
+            cache.clone(),
+            Arc::new(AppConfig::default()),
+            Duration::from_secs(30),
+            Arc::new(ProofMetrics::new()),
+            Arc::new(backend),
+        )
+        .await;
+
+        assert_eq!(retry, None);
+        let jobs = jobs.lock().await;
+        let JobStatus::Failed { error } = &jobs["job1"].status else {
+            panic!("job did not fail: {:?}", jobs["job1"].status);
+        };
+        assert!(error.contains("Remote proof failed verification"));
+        assert!(cache.lock().await.is_empty());
+    }
+
+    /// Backend that counts its proofs
+    #[derive(Default)]
+    struct CountingBackend {
+        proofs: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ProverBackend for CountingBackend {
+        fn prove(
+            &self,
+            _input: &sr25519_claim_lib::ProgramInput,
+            _proof_system: crate::types::ProofSystem,
+        ) -> Result<crate::backend::ProofOutput, crate::retry::ProveError> {
+            self.proofs.fetch_add(1, Ordering::SeqCst);
+            Ok(crate::backend::ProofOutput {
+                proof: vec![0xaa, 0xbb],
+                public_values: vec![0x01],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_job_retries_only_onchain_verification() {
+        use crate::types::VerifyOnchainConfig;
+        use httpmock::{Method::POST, MockServer};
+
+        let server = MockServer::start_async().await;
+        let mut rpc = server.mock(|when, then| {
+            when.method(POST);
+            then.status(503);
+        });
+        let config = Arc::new(AppConfig {
+            verify_onchain: Some(VerifyOnchainConfig {
+                rpc_url: server.url("/"),
+                verifier_address: [0x11; 20],
+                program_vkey: [0x22; 32],
+                timeout_seconds: 5,
+            }),
+            ..AppConfig::default()
+        });
+        let backend = Arc::new(CountingBackend::default());
+        let jobs = Arc::new(Mutex::new(HashMap::from([(
+            "job1".to_string(),
+            JobEntry::new(JobStatus::Pending),
+        )])));
+        let cache = Arc::new(Mutex::new(HashMap::new()));
+        let run = |attempt| {
+            process_job(
+                JobMessage {
+                    attempt,
+                    ..message("job1", "ip:a", 0)
+                },
+                jobs.clone(),
+                cache.clone(),
+                config.clone(),
+                Duration::from_secs(30),
+                Arc::new(ProofMetrics::new()),
+                backend.clone(),
+            )
+        };
+
+        // The RPC is down: the proof is kept for the retry
+        assert!(run(1).await.is_some());
+        {
+            let jobs = jobs.lock().await;
+            assert!(matches!(jobs["job1"].status, JobStatus::Pending));
+            assert!(jobs["job1"].unverified_proof.is_some());
+        }
+
+        // The retry verifies the kept proof without proving again
+        rpc.delete();
+        server.mock(|when, then| {
+            when.method(POST);
+            then.status(200)
+                .json_body(serde_json::json!({"result": "0x"}));
+        });
+        assert_eq!(run(2).await, None);
+
+        assert_eq!(backend.proofs.load(Ordering::SeqCst), 1);
+        let jobs = jobs.lock().await;
+        assert!(matches!(jobs["job1"].status, JobStatus::Completed { .. }));
+        assert!(jobs["job1"].unverified_proof.is_none());
+        assert_eq!(cache.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_begin_job_skips_cancelled() {
+        let jobs = Arc::new(Mutex::new(HashMap::from([
+            ("job1".to_string(), JobEntry::new(JobStatus::Pending)),
+            ("job2".to_string(), JobEntry::new(JobStatus::Cancelled)),
+        ])));
+
+        assert!(begin_job(&jobs, "job1").await);
+        assert!(!begin_job(&jobs, "job2").await);
+        assert!(!begin_job(&jobs, "missing").await);
+        assert!(matches!(
+            jobs.lock().await["job1"].status,
+            JobStatus::Running
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_queue_deadline_class() {
+        let now = now_ts();
+        let near = JobQueue::new(
+            10,
+            Some(DeadlinePolicy {
+                deadline: now + 60,
+                window_seconds: 3600,
+            }),
+        );
+        near.try_enqueue(message("job1", "ip:a", 0)).await;
+        assert_eq!(near.depth_by_class()["deadline"], 1);
+
+        let far = JobQueue::new(
+            10,
+            Some(DeadlinePolicy {
+                deadline: now + 7 * 86400,
+                window_seconds: 3600,
+            }),
+        );
+        far.try_enqueue(message("job1", "ip:a", 0)).await;
+        assert_eq!(far.depth_by_class()["standard"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_queue_depth_by_class_lists_all_classes() {
+        let queue = JobQueue::new(10, None);
+        queue.try_enqueue(message("job1", "apikey:x", 5)).await;
+
+        let depth = queue.depth_by_class();
+        assert_eq!(depth.len(), PriorityClass::ALL.len());
+        assert_eq!(depth["keyed"], 1);
+        assert_eq!(depth["standard"], 0);
+    }
+
+    #[test]
+    fn test_estimate_eta_seconds() {
+        let avg = Some(Duration::from_secs(100));
+
+        // No history yet
+        assert_eq!(estimate_eta_seconds(Some(1), None, 4, None), None);
 
-        // Receive the message and decrement size (simulating worker behavior)
-        let _msg = receiver.recv().await;
-        queue.queue_size.fetch_sub(1, Ordering::SeqCst);
+        // Pending: positions 1-4 fit in the first round of 4 workers
+        assert_eq!(estimate_eta_seconds(Some(1), None, 4, avg), Some(100));
+        assert_eq!(estimate_eta_seconds(Some(4), None, 4, avg), Some(100));
+        assert_eq!(estimate_eta_seconds(Some(5), None, 4, avg), Some(200));
 
-        assert_eq!(queue.queue_size.load(Ordering::SeqCst), 0);
+        // Running: remainder of an average proof, never negative
+        let running = Some(Duration::from_secs(30));
+        assert_eq!(estimate_eta_seconds(None, running, 4, avg), Some(70));
+        let overdue = Some(Duration::from_secs(300));
+        assert_eq!(estimate_eta_seconds(None, overdue, 4, avg), Some(0));
     }
 
     #[tokio::test]
     async fn test_multiple_enqueues() {
-        let (queue, _receiver) = JobQueue::new(100);
+        let queue = JobQueue::new(100, None);
 
         for i in 0..50 {
             let result = queue
-                .try_enqueue(JobMessage {
-                    job_id: format!("job{}", i),
-                    request: test_request(),
-                })
+                .try_enqueue(message(&format!("job{}", i), "ip:a", 0))
                 .await;
             assert!(matches!(result, EnqueueResult::Queued));
         }
 
-        assert_eq!(queue.queue_size.load(Ordering::SeqCst), 50);
+        assert_eq!(queue.depth(), 50);
     }
 }
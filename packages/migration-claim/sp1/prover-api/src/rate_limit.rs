@@ -1,15 +1,63 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::info;
 
 use crate::types::{now_ts, RateLimitEntry};
 
+/// Algorithm used to enforce `max_requests` per `window_seconds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitStrategy {
+    /// Counter that resets once the window has elapsed since its first request.
+    /// Cheap, but allows up to 2x the limit across a window boundary.
+    #[default]
+    FixedWindow,
+    /// Log of request timestamps within the trailing window. Never allows more
+    /// than the limit in any window-sized interval.
+    SlidingWindow,
+    /// Bucket of `max_requests` tokens refilled evenly over the window. Allows a
+    /// burst up to the bucket size, then a steady rate.
+    TokenBucket,
+}
+
+impl RateLimitStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FixedWindow => "fixed_window",
+            Self::SlidingWindow => "sliding_window",
+            Self::TokenBucket => "token_bucket",
+        }
+    }
+}
+
+impl FromStr for RateLimitStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "fixed_window" | "fixed" => Ok(Self::FixedWindow),
+            "sliding_window" | "sliding" => Ok(Self::SlidingWindow),
+            "token_bucket" | "bucket" => Ok(Self::TokenBucket),
+            other => Err(format!(
+                "unknown rate limit strategy `{other}` (expected fixed_window, sliding_window or token_bucket)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RateLimitStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Rate limiter for controlling request frequency
 pub struct RateLimiter {
     limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
     window_seconds: u64,
     max_requests: u32,
+    strategy: RateLimitStrategy,
 }
 
 /// Result of rate limit check
@@ -26,11 +74,26 @@ impl RateLimiter {
         limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
         window_seconds: u64,
         max_requests: u32,
+    ) -> Self {
+        Self::with_strategy(
+            limits,
+            window_seconds,
+            max_requests,
+            RateLimitStrategy::FixedWindow,
+        )
+    }
+
+    pub fn with_strategy(
+        limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
+        window_seconds: u64,
+        max_requests: u32,
+        strategy: RateLimitStrategy,
     ) -> Self {
         Self {
             limits,
             window_seconds,
             max_requests,
+            strategy,
         }
     }
 
@@ -39,6 +102,19 @@ impl RateLimiter {
         let mut limits = self.limits.lock().await;
         let now = now_ts();
 
+        match self.strategy {
+            RateLimitStrategy::FixedWindow => self.check_fixed_window(&mut limits, key, now),
+            RateLimitStrategy::SlidingWindow => self.check_sliding_window(&mut limits, key, now),
+            RateLimitStrategy::TokenBucket => self.check_token_bucket(&mut limits, key, now),
+        }
+    }
+
+    fn check_fixed_window(
+        &self,
+        limits: &mut HashMap<String, RateLimitEntry>,
+        key: &str,
+        now: u64,
+    ) -> RateLimitResult {
         match limits.get_mut(key) {
             Some(entry) => {
                 let elapsed = now - entry.last_request_at;
@@ -66,6 +142,74 @@ impl RateLimiter {
         }
     }
 
+    fn check_sliding_window(
+        &self,
+        limits: &mut HashMap<String, RateLimitEntry>,
+        key: &str,
+        now: u64,
+    ) -> RateLimitResult {
+        let entry = limits
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitEntry {
+                request_count: 0,
+                ..RateLimitEntry::new()
+            });
+
+        // Forget requests that have left the trailing window
+        while let Some(&oldest) = entry.request_log.front() {
+            if now.saturating_sub(oldest) >= self.window_seconds {
+                entry.request_log.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if entry.request_log.len() >= self.max_requests as usize {
+            // Retry once the oldest logged request leaves the window
+            let oldest = entry.request_log.front().copied().unwrap_or(now);
+            let retry_after = (oldest + self.window_seconds).saturating_sub(now).max(1);
+            return RateLimitResult::Limited { retry_after };
+        }
+
+        entry.request_log.push_back(now);
+        entry.request_count = entry.request_log.len() as u32;
+        entry.last_request_at = now;
+        RateLimitResult::Allowed
+    }
+
+    fn check_token_bucket(
+        &self,
+        limits: &mut HashMap<String, RateLimitEntry>,
+        key: &str,
+        now: u64,
+    ) -> RateLimitResult {
+        let capacity = self.max_requests as f64;
+        // Tokens regained per second so an empty bucket refills over one window
+        let refill_rate = capacity / self.window_seconds.max(1) as f64;
+
+        let entry = limits
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitEntry {
+                tokens: capacity,
+                ..RateLimitEntry::new()
+            });
+
+        // `last_request_at` doubles as the last refill time
+        let elapsed = now.saturating_sub(entry.last_request_at) as f64;
+        entry.tokens = (entry.tokens + elapsed * refill_rate).min(capacity);
+        entry.last_request_at = now;
+
+        if entry.tokens >= 1.0 {
+            entry.tokens -= 1.0;
+            RateLimitResult::Allowed
+        } else {
+            let retry_after = ((1.0 - entry.tokens) / refill_rate).ceil() as u64;
+            RateLimitResult::Limited {
+                retry_after: retry_after.max(1),
+            }
+        }
+    }
+
     /// Clean up expired entries
     pub async fn cleanup(&self) -> usize {
         let mut limits = self.limits.lock().await;
@@ -184,6 +328,7 @@ mod tests {
                 RateLimitEntry {
                     last_request_at: now_ts() - 120, // 2 minutes ago
                     request_count: 10,
+                    ..Default::default()
                 },
             );
         }
@@ -211,6 +356,7 @@ mod tests {
                 RateLimitEntry {
                     last_request_at: now_ts(),
                     request_count: 1,
+                    ..Default::default()
                 },
             );
             l.insert(
@@ -218,6 +364,7 @@ mod tests {
                 RateLimitEntry {
                     last_request_at: now_ts() - 300, // 5 minutes ago
                     request_count: 1,
+                    ..Default::default()
                 },
             );
         }
@@ -243,6 +390,7 @@ mod tests {
                 RateLimitEntry {
                     last_request_at: start_time,
                     request_count: 3,
+                    ..Default::default()
                 },
             );
         }
@@ -260,4 +408,153 @@ mod tests {
             _ => panic!("Expected rate limited"),
         }
     }
+
+    async fn count_allowed(limiter: &RateLimiter, key: &str, attempts: usize) -> usize {
+        let mut allowed = 0;
+        for _ in 0..attempts {
+            if matches!(
+                limiter.check_and_update(key).await,
+                RateLimitResult::Allowed
+            ) {
+                allowed += 1;
+            }
+        }
+        allowed
+    }
+
+    #[test]
+    fn test_strategy_parsing() {
+        assert_eq!(
+            "fixed_window".parse::<RateLimitStrategy>().unwrap(),
+            RateLimitStrategy::FixedWindow
+        );
+        assert_eq!(
+            "Sliding_Window".parse::<RateLimitStrategy>().unwrap(),
+            RateLimitStrategy::SlidingWindow
+        );
+        assert_eq!(
+            " token_bucket ".parse::<RateLimitStrategy>().unwrap(),
+            RateLimitStrategy::TokenBucket
+        );
+        assert!("leaky_bucket".parse::<RateLimitStrategy>().is_err());
+        assert_eq!(RateLimitStrategy::default(), RateLimitStrategy::FixedWindow);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_window_allows_double_burst_at_edge() {
+        let limits = Arc::new(Mutex::new(HashMap::new()));
+
+        // A full window that started just over 60s ago: the last 3 requests
+        // landed right before the boundary
+        {
+            let mut l = limits.lock().await;
+            l.insert(
+                "user1".to_string(),
+                RateLimitEntry {
+                    last_request_at: now_ts() - 60,
+                    request_count: 3,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let limiter = RateLimiter::new(limits, 60, 3);
+
+        // The window resets, so another full burst gets through immediately
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_blocks_edge_burst() {
+        let limits = Arc::new(Mutex::new(HashMap::new()));
+        let now = now_ts();
+
+        // Same situation as above, but the requests are logged individually:
+        // one at the start of the old window, two just before the boundary
+        {
+            let mut l = limits.lock().await;
+            l.insert(
+                "user1".to_string(),
+                RateLimitEntry {
+                    last_request_at: now - 1,
+                    request_count: 3,
+                    request_log: [now - 60, now - 1, now - 1].into_iter().collect(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let limiter = RateLimiter::with_strategy(limits, 60, 3, RateLimitStrategy::SlidingWindow);
+
+        // Only the request that has left the trailing window frees a slot
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 1);
+
+        match limiter.check_and_update("user1").await {
+            RateLimitResult::Limited { retry_after } => {
+                // The two recent requests expire in ~59 seconds
+                assert!((58..=60).contains(&retry_after));
+            }
+            _ => panic!("Expected rate limited"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_burst_from_empty() {
+        let limits = Arc::new(Mutex::new(HashMap::new()));
+        let limiter =
+            RateLimiter::with_strategy(limits.clone(), 60, 3, RateLimitStrategy::SlidingWindow);
+
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 3);
+        assert_eq!(limits.lock().await["user1"].request_log.len(), 3);
+
+        // Other keys are unaffected
+        assert_eq!(count_allowed(&limiter, "user2", 1).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_burst_then_limited() {
+        let limits = Arc::new(Mutex::new(HashMap::new()));
+        let limiter = RateLimiter::with_strategy(limits, 60, 3, RateLimitStrategy::TokenBucket);
+
+        // A fresh bucket allows a burst up to its capacity
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 3);
+
+        match limiter.check_and_update("user1").await {
+            RateLimitResult::Limited { retry_after } => {
+                // One token refills every 60 / 3 = 20 seconds
+                assert!((1..=20).contains(&retry_after));
+            }
+            _ => panic!("Expected rate limited"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_refills_gradually() {
+        let limits = Arc::new(Mutex::new(HashMap::new()));
+
+        // Empty bucket last touched 40 seconds ago: 2 tokens regained
+        {
+            let mut l = limits.lock().await;
+            l.insert(
+                "user1".to_string(),
+                RateLimitEntry {
+                    last_request_at: now_ts() - 40,
+                    tokens: 0.0,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let limiter =
+            RateLimiter::with_strategy(limits.clone(), 60, 3, RateLimitStrategy::TokenBucket);
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 2);
+
+        // Long idle periods never overfill the bucket
+        {
+            let mut l = limits.lock().await;
+            let entry = l.get_mut("user1").unwrap();
+            entry.last_request_at = now_ts() - 3600;
+        }
+        assert_eq!(count_allowed(&limiter, "user1", 10).await, 3);
+    }
 }
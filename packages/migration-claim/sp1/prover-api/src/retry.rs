@@ -0,0 +1,247 @@
+//! Retry policy for failed proof jobs
+//!
+//! Failures are grouped into error classes, each with its own attempt limit and
+//! exponential backoff. Transient failures (RPC hiccups during on-chain verification)
+//! are retried by default; deterministic proof failures are not. Backends and the
+//! on-chain check report a [`ProveError`] carrying its class, so nothing is inferred
+//! from error text.
+
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+use crate::backend::ProofOutput;
+
+/// Kind of failure, used to pick a retry rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Network or RPC failure (connection errors, RPC timeouts, HTTP 5xx), including
+    /// from a remote prover
+    Transient,
+    /// Proof generation exceeded the timeout
+    Timeout,
+    /// Worker task panicked
+    Internal,
+    /// Proof generation or verification failed deterministically
+    Proof,
+}
+
+impl ErrorClass {
+    pub const ALL: [ErrorClass; 4] = [
+        ErrorClass::Transient,
+        ErrorClass::Timeout,
+        ErrorClass::Internal,
+        ErrorClass::Proof,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorClass::Transient => "transient",
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Internal => "internal",
+            ErrorClass::Proof => "proof",
+        }
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed proof attempt, classified where the failure happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProveError {
+    pub class: ErrorClass,
+    pub message: String,
+    /// The proof, if it was generated before a later check failed, so a retry only
+    /// needs to repeat that check
+    pub proof: Option<ProofOutput>,
+}
+
+impl ProveError {
+    pub fn new(class: ErrorClass, message: impl Into<String>) -> Self {
+        Self {
+            class,
+            message: message.into(),
+            proof: None,
+        }
+    }
+
+    pub fn transient(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Transient, message)
+    }
+
+    pub fn proof(message: impl Into<String>) -> Self {
+        Self::new(ErrorClass::Proof, message)
+    }
+
+    /// Prefix the message, keeping the class
+    pub fn context(self, context: &str) -> Self {
+        Self {
+            message: format!("{context}: {}", self.message),
+            ..self
+        }
+    }
+
+    /// Attach the proof that was generated before the failure
+    pub fn with_proof(self, proof: ProofOutput) -> Self {
+        Self {
+            proof: Some(proof),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ProveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ProveError {}
+
+/// Errors without a class come from validating or proving the claim itself
+impl From<String> for ProveError {
+    fn from(message: String) -> Self {
+        Self::proof(message)
+    }
+}
+
+impl From<&str> for ProveError {
+    fn from(message: &str) -> Self {
+        Self::proof(message)
+    }
+}
+
+/// Attempt limit and backoff for one error class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryRule {
+    /// Total attempts including the first (1 disables retries)
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub backoff: Duration,
+}
+
+impl RetryRule {
+    pub const NONE: RetryRule = RetryRule {
+        max_attempts: 1,
+        backoff: Duration::ZERO,
+    };
+}
+
+/// Retry rules per error class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub transient: RetryRule,
+    pub timeout: RetryRule,
+    pub internal: RetryRule,
+    pub proof: RetryRule,
+    /// Upper bound for the exponential backoff
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            transient: RetryRule {
+                max_attempts: 3,
+                backoff: Duration::from_secs(10),
+            },
+            // A timed-out proof held a worker for the full timeout already
+            timeout: RetryRule::NONE,
+            internal: RetryRule {
+                max_attempts: 2,
+                backoff: Duration::from_secs(10),
+            },
+            proof: RetryRule::NONE,
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn rule(&self, class: ErrorClass) -> RetryRule {
+        match class {
+            ErrorClass::Transient => self.transient,
+            ErrorClass::Timeout => self.timeout,
+            ErrorClass::Internal => self.internal,
+            ErrorClass::Proof => self.proof,
+        }
+    }
+
+    pub fn rule_mut(&mut self, class: ErrorClass) -> &mut RetryRule {
+        match class {
+            ErrorClass::Transient => &mut self.transient,
+            ErrorClass::Timeout => &mut self.timeout,
+            ErrorClass::Internal => &mut self.internal,
+            ErrorClass::Proof => &mut self.proof,
+        }
+    }
+
+    /// Delay before retrying after `attempt` (1-based) failed, or `None` to give up
+    pub fn backoff(&self, class: ErrorClass, attempt: u32) -> Option<Duration> {
+        let rule = self.rule(class);
+        if attempt >= rule.max_attempts {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        Some(rule.backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prove_error_context_keeps_class() {
+        let err = ProveError::transient("RPC HTTP error 502").context("On-chain verify failed");
+        assert_eq!(err.class, ErrorClass::Transient);
+        assert_eq!(
+            err.to_string(),
+            "On-chain verify failed: RPC HTTP error 502"
+        );
+
+        let err: ProveError = "invalid signature".into();
+        assert_eq!(err.class, ErrorClass::Proof);
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            transient: RetryRule {
+                max_attempts: 5,
+                backoff: Duration::from_secs(10),
+            },
+            max_backoff: Duration::from_secs(30),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            policy.backoff(ErrorClass::Transient, 1),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            policy.backoff(ErrorClass::Transient, 2),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            policy.backoff(ErrorClass::Transient, 3),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(policy.backoff(ErrorClass::Transient, 5), None);
+    }
+
+    #[test]
+    fn test_default_policy_skips_deterministic_failures() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(ErrorClass::Proof, 1), None);
+        assert_eq!(policy.backoff(ErrorClass::Timeout, 1), None);
+        assert!(policy.backoff(ErrorClass::Transient, 1).is_some());
+        assert!(policy.backoff(ErrorClass::Internal, 1).is_some());
+        assert_eq!(policy.backoff(ErrorClass::Internal, 2), None);
+    }
+}
@@ -1,86 +1,80 @@
-//! SR25519 signature verification
+//! Substrate signature verification
 //!
-//! Verifies signatures using the same logic as the SP1 program to ensure
-//! early rejection of invalid signatures before expensive proof generation.
+//! Verifies SR25519, Ed25519 and ECDSA signatures with the verifiers the SP1 program
+//! uses (from `sr25519_claim_lib`) to ensure early rejection of invalid signatures
+//! before expensive proof generation.
 
-use schnorrkel::{signing_context, PublicKey, Signature};
+use sr25519_claim_lib::{
+    verify_ecdsa, verify_ed25519, verify_sr25519, MessageWrapping, SignatureScheme,
+};
 
-/// The signing context used by Substrate for SR25519 signatures
-/// This must match what the polkadot.js extension uses
-const SUBSTRATE_CONTEXT: &[u8] = b"substrate";
+use crate::types::WrappingMode;
 
-/// Substrate wallet extensions wrap messages with <Bytes>...</Bytes> when signing
-/// with signRaw({ type: 'bytes' }). We must wrap the challenge the same way.
-const WRAP_PREFIX: &[u8] = b"<Bytes>";
-const WRAP_POSTFIX: &[u8] = b"</Bytes>";
+/// Find the framing a signature was made over
+///
+/// Tries each framing allowed by `mode` in turn (wrapped first) and returns the one
+/// that verifies, so it can be passed to the SP1 program. The error lists every
+/// framing that was tried.
+pub fn detect_wrapping(
+    mode: WrappingMode,
+    scheme: SignatureScheme,
+    account_id: &[u8; 32],
+    signature: &[u8],
+    challenge: &[u8; 32],
+) -> Result<MessageWrapping, String> {
+    let mut errors = Vec::new();
+    for &wrapping in mode.candidates() {
+        match verify_signature(scheme, wrapping, account_id, signature, challenge) {
+            Ok(()) => return Ok(wrapping),
+            Err(e) => errors.push(format!("{}: {}", wrapping.as_str(), e)),
+        }
+    }
+    Err(format!("tried {}", errors.join("; ")))
+}
 
-/// Verify an SR25519 signature over a challenge
+/// Verify a Substrate signature over a challenge
 ///
 /// The signature verification matches exactly what the SP1 program does:
-/// 1. Create signing context with "substrate"
-/// 2. Wrap challenge with <Bytes>...</Bytes>
-/// 3. Verify signature against wrapped challenge
+/// 1. Frame the challenge: `<Bytes>challenge</Bytes>` when wrapped, as is when raw
+/// 2. Verify the signature against the framed challenge with the account's scheme:
+///    - SR25519 with the "substrate" signing context
+///    - Ed25519 over the message
+///    - ECDSA over `blake2_256(message)`, recovering a key that must hash to the
+///      account id
 ///
 /// # Arguments
-/// * `pubkey` - 32-byte SR25519 public key (derived from SS58 address)
-/// * `signature` - 64-byte SR25519 signature
+/// * `scheme` - Signature scheme of the account
+/// * `wrapping` - How the challenge was framed before signing
+/// * `account_id` - 32-byte account id (derived from SS58 address)
+/// * `signature` - 64-byte signature, or 65 bytes (`r || s || v`) for ECDSA
 /// * `challenge` - 32-byte challenge (the keccak256 hash that was signed)
 ///
 /// # Returns
 /// * `Ok(())` - Signature is valid
 /// * `Err(String)` - Signature verification failed with reason
 pub fn verify_signature(
-    pubkey: &[u8; 32],
-    signature: &[u8; 64],
+    scheme: SignatureScheme,
+    wrapping: MessageWrapping,
+    account_id: &[u8; 32],
+    signature: &[u8],
     challenge: &[u8; 32],
 ) -> Result<(), String> {
-    // Parse the SR25519 public key
-    let public_key =
-        PublicKey::from_bytes(pubkey).map_err(|e| format!("Invalid public key: {}", e))?;
-
-    // Parse the signature
-    let sig =
-        Signature::from_bytes(signature).map_err(|e| format!("Invalid signature format: {}", e))?;
-
-    // Create the signing context (must match what was used to sign)
-    let ctx = signing_context(SUBSTRATE_CONTEXT);
-
-    // Wrap the challenge with <Bytes>...</Bytes> as Substrate wallet extensions do
-    // when using signRaw with type: 'bytes'
-    //
     // IMPORTANT: This must match exactly what the SP1 program does.
     // The SP1 program wraps the raw challenge bytes, not the hex string.
-    // See: sp1/program/src/main.rs lines 56-63
-    let mut wrapped_challenge =
-        Vec::with_capacity(WRAP_PREFIX.len() + challenge.len() + WRAP_POSTFIX.len());
-    wrapped_challenge.extend_from_slice(WRAP_PREFIX);
-    wrapped_challenge.extend_from_slice(challenge);
-    wrapped_challenge.extend_from_slice(WRAP_POSTFIX);
-
-    // Verify the signature over the wrapped challenge
-    public_key
-        .verify(ctx.bytes(&wrapped_challenge), &sig)
-        .map_err(|_| "Signature verification failed".to_string())
+    let message = wrapping.message(challenge);
+
+    let verified = match scheme {
+        SignatureScheme::Sr25519 => verify_sr25519(account_id, signature, &message),
+        SignatureScheme::Ed25519 => verify_ed25519(account_id, signature, &message),
+        SignatureScheme::Ecdsa => verify_ecdsa(account_id, signature, &message),
+    };
+    verified.map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_wrapping_format() {
-        // The wrapping should match SP1 program exactly
-        let challenge: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
-        let mut wrapped = Vec::new();
-        wrapped.extend_from_slice(WRAP_PREFIX);
-        wrapped.extend_from_slice(&challenge);
-        wrapped.extend_from_slice(WRAP_POSTFIX);
-
-        // Should be: <Bytes> + raw bytes + </Bytes>
-        assert_eq!(wrapped.len(), 7 + 4 + 8); // "<Bytes>" + 4 bytes + "</Bytes>"
-        assert!(wrapped.starts_with(b"<Bytes>"));
-        assert!(wrapped.ends_with(b"</Bytes>"));
-    }
+    use sr25519_claim_lib::blake2_256;
 
     #[test]
     fn test_invalid_pubkey() {
@@ -88,8 +82,152 @@ mod tests {
         let signature = [0u8; 64];
         let challenge = [0u8; 32];
 
-        let result = verify_signature(&invalid_pubkey, &signature, &challenge);
+        let result = verify_signature(
+            SignatureScheme::Sr25519,
+            MessageWrapping::Wrapped,
+            &invalid_pubkey,
+            &signature,
+            &challenge,
+        );
         // Should fail with invalid public key or signature verification failed
         assert!(result.is_err());
     }
+
+    fn wrapped(challenge: &[u8; 32]) -> Vec<u8> {
+        MessageWrapping::Wrapped.message(challenge)
+    }
+
+    #[test]
+    fn test_ed25519_signature() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[0x07; 32]);
+        let account_id = key.verifying_key().to_bytes();
+        let challenge = [0x42; 32];
+        let signature = key.sign(&wrapped(&challenge)).to_bytes();
+
+        assert!(verify_signature(
+            SignatureScheme::Ed25519,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature,
+            &challenge
+        )
+        .is_ok());
+        assert!(verify_signature(
+            SignatureScheme::Ed25519,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature,
+            &[0x43; 32]
+        )
+        .is_err());
+        // The same bytes are not a valid SR25519 signature for this account
+        assert!(verify_signature(
+            SignatureScheme::Sr25519,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature,
+            &challenge
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_detect_wrapping() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[0x07; 32]);
+        let account_id = key.verifying_key().to_bytes();
+        let challenge = [0x42; 32];
+        let raw = key.sign(&challenge).to_bytes();
+        let detect = |mode, signature: &[u8]| {
+            detect_wrapping(
+                mode,
+                SignatureScheme::Ed25519,
+                &account_id,
+                signature,
+                &challenge,
+            )
+        };
+
+        assert_eq!(
+            detect(WrappingMode::Auto, &raw).unwrap(),
+            MessageWrapping::Raw
+        );
+        assert_eq!(
+            detect(
+                WrappingMode::Auto,
+                &key.sign(&wrapped(&challenge)).to_bytes()
+            )
+            .unwrap(),
+            MessageWrapping::Wrapped
+        );
+
+        // An explicit mode only tries that framing
+        let err = detect(WrappingMode::Wrapped, &raw).unwrap_err();
+        assert!(err.contains("wrapped") && !err.contains("raw"), "{err}");
+
+        // The error lists every framing that was tried
+        let err = detect(WrappingMode::Auto, &[0u8; 64]).unwrap_err();
+        assert!(err.contains("wrapped: ") && err.contains("raw: "), "{err}");
+    }
+
+    #[test]
+    fn test_ecdsa_signature() {
+        use k256::ecdsa::SigningKey;
+
+        // Alice's ECDSA dev key (5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X)
+        let secret =
+            hex::decode("cb6df9de1efca7a3998a8ead4e02159d5fa99c3e0d4fd6432667390bb4726854")
+                .unwrap();
+        let key = SigningKey::from_slice(&secret).unwrap();
+        let account_id =
+            sr25519_claim_lib::ss58_decode("5C7C2Z5sWbytvHpuLTvzKunnnRwQxft1jiqrLD5rhucQ5S9X")
+                .unwrap();
+        let challenge = [0x42; 32];
+        let (sig, recovery_id) = key
+            .sign_prehash_recoverable(&blake2_256(&wrapped(&challenge)))
+            .unwrap();
+        let mut signature = sig.to_bytes().to_vec();
+        signature.push(recovery_id.to_byte());
+
+        assert!(verify_signature(
+            SignatureScheme::Ecdsa,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature,
+            &challenge
+        )
+        .is_ok());
+
+        // Ethereum-style recovery ids are accepted too
+        signature[64] += 27;
+        assert!(verify_signature(
+            SignatureScheme::Ecdsa,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature,
+            &challenge
+        )
+        .is_ok());
+
+        // A valid signature from another account is rejected
+        assert!(verify_signature(
+            SignatureScheme::Ecdsa,
+            MessageWrapping::Wrapped,
+            &[0x11; 32],
+            &signature,
+            &challenge
+        )
+        .is_err());
+        assert!(verify_signature(
+            SignatureScheme::Ecdsa,
+            MessageWrapping::Wrapped,
+            &account_id,
+            &signature[..64],
+            &challenge
+        )
+        .is_err());
+    }
 }
@@ -1,9 +1,16 @@
+use crate::api_keys::ApiKeyStore;
+use crate::backend::{ProofOutput, ProverBackend};
 use crate::eligibility::EligibilityData;
+use crate::queue::JobQueue;
+use crate::rate_limit::RateLimitStrategy;
+use crate::retry::{ErrorClass, RetryPolicy};
+use crate::validation::PrefixPolicy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sr25519_claim_lib::{MessageWrapping, SignatureScheme};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
 /// Request payload for proof generation
@@ -16,6 +23,124 @@ pub struct ProveRequest {
     pub evm_address: String,
     pub challenge: String,
     pub amount: String,
+    /// Signature scheme of the account (`sr25519`, `ed25519` or `ecdsa`)
+    #[serde(rename = "signatureScheme", default)]
+    pub signature_scheme: SignatureScheme,
+    /// Whether the challenge was signed `wrapped` in `<Bytes>...</Bytes>`, `raw`, or
+    /// `auto` to detect it
+    #[serde(rename = "signatureWrapping", default)]
+    pub signature_wrapping: WrappingMode,
+    /// Proof system to generate, defaulting to the server's `PROOF_SYSTEM`
+    #[serde(rename = "proofSystem", default)]
+    pub proof_system: Option<ProofSystem>,
+}
+
+impl ProveRequest {
+    /// Proof system this request resolves to (`Groth16` if unset)
+    pub fn proof_system(&self) -> ProofSystem {
+        self.proof_system.unwrap_or_default()
+    }
+}
+
+/// How a request's challenge was framed before signing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WrappingMode {
+    /// Try `<Bytes>`-wrapped, then raw
+    #[default]
+    Auto,
+    /// Only `<Bytes>challenge</Bytes>` (polkadot.js `signRaw`)
+    Wrapped,
+    /// Only the bare challenge (subkey, CLI signers)
+    Raw,
+}
+
+impl WrappingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Wrapped => "wrapped",
+            Self::Raw => "raw",
+        }
+    }
+
+    /// Framings to try, in order
+    pub fn candidates(&self) -> &'static [MessageWrapping] {
+        match self {
+            Self::Auto => &MessageWrapping::ALL,
+            Self::Wrapped => &[MessageWrapping::Wrapped],
+            Self::Raw => &[MessageWrapping::Raw],
+        }
+    }
+}
+
+/// SP1 proof system a claim is proved with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofSystem {
+    /// Groth16 SNARK, verified on-chain for roughly 100k gas
+    #[default]
+    Groth16,
+    /// PLONK SNARK, verified on-chain for roughly 300k gas without a trusted setup
+    Plonk,
+    /// Constant-size STARK for off-chain verification
+    Compressed,
+    /// Uncompressed STARK (one proof per shard) for off-chain verification
+    Core,
+}
+
+impl ProofSystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Groth16 => "groth16",
+            Self::Plonk => "plonk",
+            Self::Compressed => "compressed",
+            Self::Core => "core",
+        }
+    }
+
+    /// Whether the SP1 verifier gateway can verify this proof system on-chain
+    pub fn is_onchain(&self) -> bool {
+        matches!(self, Self::Groth16 | Self::Plonk)
+    }
+
+    /// Selector routing a proof to its verifier in the SP1 verifier gateway
+    ///
+    /// On-chain `zkProof`s are the signature scheme byte followed by the SP1 proof,
+    /// which starts with the first 4 bytes of the verifier's key hash. Mock proofs are
+    /// empty and off-chain proofs have no selector.
+    pub fn verifier_selector(&self, zk_proof: &str) -> Option<String> {
+        let proof = zk_proof.strip_prefix("0x").unwrap_or(zk_proof);
+        (self.is_onchain() && proof.len() >= 10).then(|| format!("0x{}", &proof[2..10]))
+    }
+}
+
+impl std::str::FromStr for ProofSystem {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "groth16" => Ok(Self::Groth16),
+            "plonk" => Ok(Self::Plonk),
+            "compressed" => Ok(Self::Compressed),
+            "core" => Ok(Self::Core),
+            other => Err(format!(
+                "unknown proof system '{other}' (expected groth16, plonk, compressed or core)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ProofSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Request payload for batch proof generation
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchProveRequest {
+    pub claims: Vec<ProveRequest>,
 }
 
 /// Response with job ID after submission
@@ -35,10 +160,31 @@ pub struct StatusResponse {
     pub zk_proof: Option<String>,
     #[serde(rename = "publicValues", skip_serializing_if = "Option::is_none")]
     pub public_values: Option<String>,
+    /// Proof system of `zkProof`
+    #[serde(rename = "proofSystem", skip_serializing_if = "Option::is_none")]
+    pub proof_system: Option<ProofSystem>,
+    /// SP1 verifier gateway selector (first 4 bytes of `zkProof`) for on-chain proofs
+    #[serde(rename = "verifierSelector", skip_serializing_if = "Option::is_none")]
+    pub verifier_selector: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(rename = "retryAfter", skip_serializing_if = "Option::is_none")]
     pub retry_after: Option<u64>,
+    /// 1-based position in the job queue while pending
+    #[serde(rename = "queuePosition", skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// Unix timestamp when a worker started the job
+    #[serde(rename = "startedAt", skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    /// Estimated unix timestamp of completion, from the rolling average proof time
+    #[serde(
+        rename = "estimatedCompletion",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub estimated_completion: Option<u64>,
+    /// Failed attempts so far, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<AttemptRecord>,
 }
 
 impl StatusResponse {
@@ -48,8 +194,14 @@ impl StatusResponse {
             code: None,
             zk_proof: None,
             public_values: None,
+            proof_system: None,
+            verifier_selector: None,
             error: None,
             retry_after: None,
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
         }
     }
 
@@ -59,19 +211,31 @@ impl StatusResponse {
             code: None,
             zk_proof: None,
             public_values: None,
+            proof_system: None,
+            verifier_selector: None,
             error: None,
             retry_after: None,
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
         }
     }
 
-    pub fn completed(zk_proof: String, public_values: String) -> Self {
+    pub fn completed(zk_proof: String, public_values: String, proof_system: ProofSystem) -> Self {
         Self {
             status: "completed".to_string(),
             code: None,
+            verifier_selector: proof_system.verifier_selector(&zk_proof),
             zk_proof: Some(zk_proof),
             public_values: Some(public_values),
+            proof_system: Some(proof_system),
             error: None,
             retry_after: None,
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
         }
     }
 
@@ -81,8 +245,14 @@ impl StatusResponse {
             code: Some(code.to_string()),
             zk_proof: None,
             public_values: None,
+            proof_system: None,
+            verifier_selector: None,
             error: Some(error),
             retry_after: None,
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
         }
     }
 
@@ -92,10 +262,101 @@ impl StatusResponse {
             code: Some(code.to_string()),
             zk_proof: None,
             public_values: None,
+            proof_system: None,
+            verifier_selector: None,
             error: Some(error),
             retry_after: Some(retry_after),
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
         }
     }
+
+    pub fn cancelled() -> Self {
+        Self {
+            status: "cancelled".to_string(),
+            code: None,
+            zk_proof: None,
+            public_values: None,
+            proof_system: None,
+            verifier_selector: None,
+            error: None,
+            retry_after: None,
+            queue_position: None,
+            started_at: None,
+            estimated_completion: None,
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Attach queue progress details to a pending or running status
+    pub fn with_progress(
+        mut self,
+        queue_position: Option<usize>,
+        started_at: Option<u64>,
+        estimated_completion: Option<u64>,
+    ) -> Self {
+        self.queue_position = queue_position;
+        self.started_at = started_at;
+        self.estimated_completion = estimated_completion;
+        self
+    }
+
+    /// Attach the job's attempt history
+    pub fn with_attempts(mut self, attempts: Vec<AttemptRecord>) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
+
+/// A failed attempt at proving a job
+#[derive(Debug, Clone, Serialize)]
+pub struct AttemptRecord {
+    /// 1-based attempt number
+    pub attempt: u32,
+    #[serde(rename = "startedAt", skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    #[serde(rename = "failedAt")]
+    pub failed_at: u64,
+    #[serde(rename = "errorClass")]
+    pub error_class: ErrorClass,
+    pub error: String,
+}
+
+/// Status of a single claim in a batch submission
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemStatus {
+    /// Position of the claim in the submitted `claims` array
+    pub index: usize,
+    #[serde(rename = "ss58Address")]
+    pub ss58_address: String,
+    /// Job tracking this claim (absent if the claim was rejected)
+    #[serde(rename = "jobId", skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    #[serde(flatten)]
+    pub status: StatusResponse,
+}
+
+/// Response for batch submission and batch status queries
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResponse {
+    #[serde(rename = "batchId")]
+    pub batch_id: String,
+    pub items: Vec<BatchItemStatus>,
+}
+
+/// Server-sent event payload for job status streaming
+///
+/// Wraps the regular `StatusResponse` so stream consumers can reuse the same parser
+/// as `GET /status/:job_id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatusEvent {
+    #[serde(flatten)]
+    pub status: StatusResponse,
+    /// Estimated seconds until completion, based on recent proof durations
+    #[serde(rename = "etaSeconds", skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<u64>,
 }
 
 /// Health check response
@@ -105,10 +366,14 @@ pub struct HealthResponse {
     pub prover_mode: String,
     pub verify_proof: bool,
     pub verify_onchain: bool,
+    /// Verification key of the embedded program (bytes32 hex)
+    pub program_vkey: String,
     pub jobs: usize,
     pub cache_size: usize,
     pub queue_size: usize,
     pub queue_capacity: usize,
+    /// Waiting jobs per priority class (retry, deadline, keyed, standard)
+    pub queue_depth: BTreeMap<String, usize>,
     /// Proof generation metrics for monitoring
     pub proof_metrics: ProofMetricsSnapshot,
 }
@@ -121,10 +386,13 @@ pub enum JobStatus {
     Completed {
         zk_proof: String,
         public_values: String,
+        proof_system: ProofSystem,
     },
     Failed {
         error: String,
     },
+    /// Cancelled by the client before a worker picked it up
+    Cancelled,
 }
 
 /// Job entry in the jobs map
@@ -132,6 +400,25 @@ pub enum JobStatus {
 pub struct JobEntry {
     pub status: JobStatus,
     pub updated_at: u64,
+    /// Unix timestamp of the transition to `Running`
+    pub started_at: Option<u64>,
+    /// Name of the API key that submitted the job, if any
+    pub api_key: Option<String>,
+    /// Batch this job belongs to, if it was submitted through `POST /batch`
+    pub batch: Option<BatchRef>,
+    /// Failed attempts, including those that were retried
+    pub attempts: Vec<AttemptRecord>,
+    /// Proof of a failed attempt whose on-chain verification is retried
+    pub unverified_proof: Option<ProofOutput>,
+}
+
+/// Link from a job to its batch submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchRef {
+    pub batch_id: String,
+    /// Position of the claim in the submitted batch
+    pub index: usize,
+    pub ss58_address: String,
 }
 
 impl JobEntry {
@@ -139,8 +426,41 @@ impl JobEntry {
         Self {
             status,
             updated_at: now_ts(),
+            started_at: None,
+            api_key: None,
+            batch: None,
+            attempts: Vec::new(),
+            unverified_proof: None,
+        }
+    }
+
+    /// Create an entry for a claim submitted as part of a batch
+    pub fn for_batch(status: JobStatus, api_key: &str, batch: BatchRef) -> Self {
+        Self {
+            batch: Some(batch),
+            ..Self::new(status).owned_by(Some(api_key))
         }
     }
+
+    /// Record the API key that submitted the job (only that key can read it back)
+    pub fn owned_by(mut self, api_key: Option<&str>) -> Self {
+        self.api_key = api_key.map(str::to_string);
+        self
+    }
+
+    /// Update the status, recording the start time when the job begins running
+    ///
+    /// Going back to `Pending` (for a retry) clears the start time.
+    pub fn set_status(&mut self, status: JobStatus) {
+        let now = now_ts();
+        match status {
+            JobStatus::Running => self.started_at = Some(now),
+            JobStatus::Pending => self.started_at = None,
+            _ => {}
+        }
+        self.status = status;
+        self.updated_at = now;
+    }
 }
 
 /// Cached proof result
@@ -148,14 +468,16 @@ impl JobEntry {
 pub struct CachedProof {
     pub zk_proof: String,
     pub public_values: String,
+    pub proof_system: ProofSystem,
     pub created_at: u64,
 }
 
 impl CachedProof {
-    pub fn new(zk_proof: String, public_values: String) -> Self {
+    pub fn new(zk_proof: String, public_values: String, proof_system: ProofSystem) -> Self {
         Self {
             zk_proof,
             public_values,
+            proof_system,
             created_at: now_ts(),
         }
     }
@@ -170,6 +492,10 @@ impl CachedProof {
 pub struct RateLimitEntry {
     pub last_request_at: u64,
     pub request_count: u32,
+    /// Timestamps of requests in the trailing window (sliding window strategy)
+    pub request_log: VecDeque<u64>,
+    /// Remaining tokens (token bucket strategy)
+    pub tokens: f64,
 }
 
 impl RateLimitEntry {
@@ -177,6 +503,8 @@ impl RateLimitEntry {
         Self {
             last_request_at: now_ts(),
             request_count: 1,
+            request_log: VecDeque::new(),
+            tokens: 0.0,
         }
     }
 }
@@ -187,6 +515,9 @@ impl Default for RateLimitEntry {
     }
 }
 
+/// Number of recent proof durations kept for ETA estimation
+const RECENT_DURATIONS_WINDOW: usize = 20;
+
 /// Metrics for monitoring proof generation
 ///
 /// Tracks completions, timeouts, and timed-out tasks that continue running
@@ -199,6 +530,17 @@ pub struct ProofMetrics {
     /// Number of timed-out tasks still running in background
     /// Note: This counter increases on timeout and decreases when the task eventually completes
     pub timed_out_still_running: AtomicUsize,
+    /// Durations of the most recent successful proofs per prover mode (oldest first)
+    recent_durations: std::sync::Mutex<HashMap<String, VecDeque<Duration>>>,
+    /// Prover setups per prover mode, kept apart from proof durations
+    setups: std::sync::Mutex<HashMap<String, SetupStats>>,
+}
+
+/// Number and total duration of prover setups
+#[derive(Debug, Default, Clone, Copy)]
+struct SetupStats {
+    count: usize,
+    total: Duration,
 }
 
 impl ProofMetrics {
@@ -207,6 +549,8 @@ impl ProofMetrics {
             total_completions: AtomicUsize::new(0),
             total_timeouts: AtomicUsize::new(0),
             timed_out_still_running: AtomicUsize::new(0),
+            recent_durations: std::sync::Mutex::new(HashMap::new()),
+            setups: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
@@ -227,16 +571,75 @@ impl ProofMetrics {
         self.timed_out_still_running.fetch_sub(1, Ordering::Relaxed);
     }
 
+    /// Record how long a successful proof took in the given prover mode
+    pub fn record_proof_duration(&self, prover_mode: &str, duration: Duration) {
+        let mut by_mode = self
+            .recent_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let durations = by_mode.entry(prover_mode.to_string()).or_default();
+        if durations.len() == RECENT_DURATIONS_WINDOW {
+            durations.pop_front();
+        }
+        durations.push_back(duration);
+    }
+
+    /// Record how long setting up a prover (client and proving key) took
+    pub fn record_setup_duration(&self, prover_mode: &str, duration: Duration) {
+        let mut setups = self.setups.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = setups.entry(prover_mode.to_string()).or_default();
+        stats.count += 1;
+        stats.total += duration;
+    }
+
+    /// Rolling average of recent proof durations for a prover mode, if any proofs have completed
+    pub fn average_proof_duration(&self, prover_mode: &str) -> Option<Duration> {
+        let by_mode = self
+            .recent_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        by_mode.get(prover_mode).and_then(average)
+    }
+
     /// Get current metrics snapshot
     pub fn snapshot(&self) -> ProofMetricsSnapshot {
         ProofMetricsSnapshot {
             total_completions: self.total_completions.load(Ordering::Relaxed),
             total_timeouts: self.total_timeouts.load(Ordering::Relaxed),
             timed_out_still_running: self.timed_out_still_running.load(Ordering::Relaxed),
+            average_proof_seconds: self
+                .recent_durations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .filter_map(|(mode, durations)| {
+                    average(durations).map(|avg| (mode.clone(), avg.as_secs_f64()))
+                })
+                .collect(),
+            setups: self
+                .setups
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(mode, stats)| {
+                    let snapshot = SetupMetricsSnapshot {
+                        count: stats.count,
+                        average_seconds: (stats.total / stats.count.max(1) as u32).as_secs_f64(),
+                    };
+                    (mode.clone(), snapshot)
+                })
+                .collect(),
         }
     }
 }
 
+fn average(durations: &VecDeque<Duration>) -> Option<Duration> {
+    if durations.is_empty() {
+        return None;
+    }
+    Some(durations.iter().sum::<Duration>() / durations.len() as u32)
+}
+
 impl Default for ProofMetrics {
     fn default() -> Self {
         Self::new()
@@ -249,6 +652,17 @@ pub struct ProofMetricsSnapshot {
     pub total_completions: usize,
     pub total_timeouts: usize,
     pub timed_out_still_running: usize,
+    /// Rolling average proof time in seconds, keyed by prover mode
+    pub average_proof_seconds: BTreeMap<String, f64>,
+    /// Prover setups (proving key preparation), keyed by prover mode
+    pub setups: BTreeMap<String, SetupMetricsSnapshot>,
+}
+
+/// Prover setup metrics for one prover mode
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupMetricsSnapshot {
+    pub count: usize,
+    pub average_seconds: f64,
 }
 
 /// Job queue message
@@ -256,6 +670,12 @@ pub struct ProofMetricsSnapshot {
 pub struct JobMessage {
     pub job_id: String,
     pub request: ProveRequest,
+    /// Queue priority from the submitting API key (0 for anonymous jobs)
+    pub priority: u8,
+    /// Client the job is scheduled fairly against (API key or IP rate limit key)
+    pub client: String,
+    /// 1-based attempt number (above 1 for automatic retries)
+    pub attempt: u32,
 }
 
 /// Shared application state
@@ -266,37 +686,73 @@ pub struct AppState {
     pub rate_limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
     /// IP-based rate limits (separate from pubkey rate limits)
     pub ip_rate_limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
+    /// Per-API-key quotas (one entry per configured key, so no cleanup is needed)
+    pub api_key_rate_limits: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
+    /// API keys for authenticated endpoints
+    pub api_keys: Arc<ApiKeyStore>,
     /// Eligibility data loaded from merkle-tree.json
     pub eligibility: Arc<EligibilityData>,
     pub config: Arc<AppConfig>,
-    pub job_sender: Option<tokio::sync::mpsc::Sender<JobMessage>>,
-    pub queue_size: Option<Arc<std::sync::atomic::AtomicUsize>>,
+    /// Job queue feeding the worker pool (`None` spawns each job directly)
+    pub job_queue: Option<JobQueue>,
+    /// Prover for jobs spawned directly, prepared at startup
+    pub backend: Arc<dyn ProverBackend>,
     /// Metrics for monitoring proof generation
     pub metrics: Arc<ProofMetrics>,
+    /// Verification key of the embedded program
+    pub program_vkey: [u8; 32],
 }
 
 /// Application configuration
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub prover_mode: String,
+    /// Proving backend selected by `prover_mode`
+    pub prover_backend: ProverBackendConfig,
+    /// Proof system for requests that don't choose one
+    pub proof_system: ProofSystem,
     pub verify_proof: bool,
     pub verify_onchain: Option<VerifyOnchainConfig>,
     pub claim_contract: Option<ClaimContractConfig>,
+    /// Contract and chain that request challenges must be computed for
+    pub challenge_domain: Option<ChallengeDomain>,
+    /// Network prefixes accepted for claim addresses
+    pub prefix_policy: PrefixPolicy,
     pub cache_ttl_seconds: u64,
     pub rate_limit_window_seconds: u64,
     pub rate_limit_max_requests: u32,
+    /// Algorithm for the per-pubkey rate limiter
+    pub rate_limit_strategy: RateLimitStrategy,
     /// IP-based rate limit window in seconds
     pub ip_rate_limit_window_seconds: u64,
     /// Maximum requests per IP per window
     pub ip_rate_limit_max_requests: u32,
+    /// Algorithm for the IP rate limiter
+    pub ip_rate_limit_strategy: RateLimitStrategy,
+    /// Algorithm for per-API-key quotas
+    pub api_key_rate_limit_strategy: RateLimitStrategy,
     pub queue_capacity: usize,
     pub worker_count: usize,
     pub proof_timeout_seconds: u64,
     pub rpc_timeout_seconds: u64,
     pub max_body_bytes: usize,
+    /// Body limit for `POST /batch`, which carries many claims
+    pub max_batch_body_bytes: usize,
     pub jobs_ttl_seconds: u64,
     /// Path to the eligibility file (merkle-tree.json)
     pub eligibility_file: String,
+    /// Path to the API keys file (batch submission is disabled without it)
+    pub api_keys_file: Option<String>,
+    /// Whether requests without an API key are accepted on `POST /` and `GET /status`
+    pub allow_anonymous: bool,
+    /// Unix timestamp of the migration contract's `claimDeadline`, if known
+    pub claim_deadline: Option<u64>,
+    /// Jobs submitted within this many seconds of the claim deadline are prioritized
+    pub deadline_priority_window_seconds: u64,
+    /// Attempt limits and backoff for failed jobs, per error class
+    pub retry_policy: RetryPolicy,
+    /// Prove and verify a canned claim with the mock prover at startup
+    pub self_test: bool,
     /// Whether to verify signatures before proof generation
     pub verify_signatures: bool,
     /// Whether to trust proxy headers (X-Forwarded-For, X-Real-IP) for client IP extraction
@@ -309,27 +765,78 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             prover_mode: "local".to_string(),
+            prover_backend: ProverBackendConfig::Local,
+            proof_system: ProofSystem::Groth16,
             verify_proof: false,
             verify_onchain: None,
             claim_contract: None,
+            challenge_domain: None,
+            prefix_policy: PrefixPolicy::Any,
             cache_ttl_seconds: 3600,          // 1 hour
             rate_limit_window_seconds: 300,   // 5 minutes
             rate_limit_max_requests: 3,       // 3 requests per window
             ip_rate_limit_window_seconds: 60, // 1 minute
             ip_rate_limit_max_requests: 10,   // 10 requests per IP per minute
+            rate_limit_strategy: RateLimitStrategy::FixedWindow,
+            ip_rate_limit_strategy: RateLimitStrategy::FixedWindow,
+            api_key_rate_limit_strategy: RateLimitStrategy::FixedWindow,
             queue_capacity: 50,
             worker_count: 4,
             proof_timeout_seconds: 600, // 10 minutes
             rpc_timeout_seconds: 10,
-            max_body_bytes: 4096,   // 4 KB
-            jobs_ttl_seconds: 3600, // 1 hour
+            max_body_bytes: 4096,         // 4 KB
+            max_batch_body_bytes: 262144, // 256 KB
+            jobs_ttl_seconds: 3600,       // 1 hour
             eligibility_file: "../merkle-tree.json".to_string(),
+            api_keys_file: None,
+            allow_anonymous: true,
+            claim_deadline: None,
+            deadline_priority_window_seconds: 86400, // 1 day
+            retry_policy: RetryPolicy::default(),
+            self_test: true,
             verify_signatures: true,    // Enabled by default
             trust_proxy_headers: false, // Disabled by default for security
         }
     }
 }
 
+/// Proving backend, chosen once at startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProverBackendConfig {
+    /// SP1 mock prover (`SP1_PROVER=mock`)
+    Mock,
+    /// SP1 CPU prover (`SP1_PROVER=local`)
+    Local,
+    /// External prover service (`SP1_PROVER=remote`)
+    Remote(RemoteProverConfig),
+}
+
+impl ProverBackendConfig {
+    /// `SP1_PROVER` value selecting this backend
+    pub fn mode(&self) -> &'static str {
+        match self {
+            ProverBackendConfig::Mock => "mock",
+            ProverBackendConfig::Local => "local",
+            ProverBackendConfig::Remote(_) => "remote",
+        }
+    }
+}
+
+/// Remote prover service configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteProverConfig {
+    /// Base URL of the prover service
+    pub url: String,
+    /// Sent as a bearer token, if set
+    pub api_key: Option<String>,
+    /// How often to poll a submitted proof
+    pub poll_interval_ms: u64,
+    /// Timeout for each HTTP request
+    pub request_timeout_seconds: u64,
+    /// How long to wait for a submitted proof
+    pub timeout_seconds: u64,
+}
+
 /// On-chain verification configuration
 #[derive(Debug, Clone)]
 pub struct VerifyOnchainConfig {
@@ -347,6 +854,13 @@ pub struct ClaimContractConfig {
     pub contract_address: [u8; 20],
 }
 
+/// Contract and chain a claim challenge is bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChallengeDomain {
+    pub contract_address: [u8; 20],
+    pub chain_id: u64,
+}
+
 /// Get current unix timestamp
 pub fn now_ts() -> u64 {
     SystemTime::now()
@@ -370,8 +884,16 @@ pub mod error_codes {
     pub const NOT_ELIGIBLE: &str = "not_eligible";
     /// Requested amount doesn't match the eligible balance
     pub const AMOUNT_MISMATCH: &str = "amount_mismatch";
+    /// Challenge wasn't computed for the configured contract and chain
+    pub const CHALLENGE_MISMATCH: &str = "challenge_mismatch";
+    /// Address uses a network prefix the prefix policy doesn't allow
+    pub const PREFIX_NOT_ALLOWED: &str = "prefix_not_allowed";
     /// Signature verification failed
     pub const INVALID_SIGNATURE: &str = "invalid_signature";
+    /// Missing or unknown API key
+    pub const UNAUTHORIZED: &str = "unauthorized";
+    /// Job is running or finished and can no longer be cancelled
+    pub const NOT_CANCELLABLE: &str = "not_cancellable";
 }
 
 #[cfg(test)]
@@ -388,10 +910,37 @@ mod tests {
 
     #[test]
     fn test_status_response_completed() {
-        let resp = StatusResponse::completed("0x123".to_string(), "0x456".to_string());
+        let resp = StatusResponse::completed(
+            "0x00".to_string(),
+            "0x456".to_string(),
+            ProofSystem::Groth16,
+        );
         assert_eq!(resp.status, "completed");
-        assert_eq!(resp.zk_proof.unwrap(), "0x123");
+        assert_eq!(resp.zk_proof.unwrap(), "0x00");
         assert_eq!(resp.public_values.unwrap(), "0x456");
+        assert_eq!(resp.proof_system, Some(ProofSystem::Groth16));
+        // Only the scheme byte: mock proofs are empty
+        assert!(resp.verifier_selector.is_none());
+    }
+
+    #[test]
+    fn test_verifier_selector_per_proof_system() {
+        let proof = format!("0x0011223344{}", "ab".repeat(32));
+        assert_eq!(
+            ProofSystem::Groth16.verifier_selector(&proof).as_deref(),
+            Some("0x11223344")
+        );
+        assert_eq!(
+            ProofSystem::Plonk.verifier_selector(&proof).as_deref(),
+            Some("0x11223344")
+        );
+        assert!(ProofSystem::Compressed.verifier_selector(&proof).is_none());
+        assert!(ProofSystem::Core.verifier_selector(&proof).is_none());
+
+        let resp = StatusResponse::completed(proof, "0x".to_string(), ProofSystem::Plonk);
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["proofSystem"], "plonk");
+        assert_eq!(json["verifierSelector"], "0x11223344");
     }
 
     #[test]
@@ -402,11 +951,155 @@ mod tests {
         assert_eq!(resp.error.unwrap(), "bad data");
     }
 
+    #[test]
+    fn test_job_status_event_flattens_status() {
+        let event = JobStatusEvent {
+            status: StatusResponse::pending().with_progress(Some(3), None, Some(1_000)),
+            eta_seconds: Some(120),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["status"], "pending");
+        assert_eq!(json["queuePosition"], 3);
+        assert_eq!(json["estimatedCompletion"], 1_000);
+        assert_eq!(json["etaSeconds"], 120);
+        assert!(json.get("zkProof").is_none());
+        assert!(json.get("startedAt").is_none());
+    }
+
+    #[test]
+    fn test_average_proof_duration_rolls_over() {
+        let metrics = ProofMetrics::new();
+        assert!(metrics.average_proof_duration("local").is_none());
+
+        metrics.record_proof_duration("local", Duration::from_secs(10));
+        metrics.record_proof_duration("local", Duration::from_secs(30));
+        assert_eq!(
+            metrics.average_proof_duration("local"),
+            Some(Duration::from_secs(20))
+        );
+
+        // Fill the window with 100s proofs; the older samples drop out
+        for _ in 0..RECENT_DURATIONS_WINDOW {
+            metrics.record_proof_duration("local", Duration::from_secs(100));
+        }
+        assert_eq!(
+            metrics.average_proof_duration("local"),
+            Some(Duration::from_secs(100))
+        );
+    }
+
+    #[test]
+    fn test_average_proof_duration_per_mode() {
+        let metrics = ProofMetrics::new();
+        metrics.record_proof_duration("local", Duration::from_secs(300));
+        metrics.record_proof_duration("mock", Duration::from_secs(2));
+
+        assert!(metrics.average_proof_duration("network").is_none());
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.average_proof_seconds.len(), 2);
+        assert_eq!(snapshot.average_proof_seconds["local"], 300.0);
+        assert_eq!(snapshot.average_proof_seconds["mock"], 2.0);
+    }
+
+    #[test]
+    fn test_setup_durations_kept_apart_from_proofs() {
+        let metrics = ProofMetrics::new();
+        metrics.record_setup_duration("local", Duration::from_secs(20));
+        metrics.record_setup_duration("local", Duration::from_secs(40));
+
+        assert!(metrics.average_proof_duration("local").is_none());
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.setups["local"].count, 2);
+        assert_eq!(snapshot.setups["local"].average_seconds, 30.0);
+        assert!(snapshot.average_proof_seconds.is_empty());
+    }
+
+    #[test]
+    fn test_job_entry_records_start_time() {
+        let mut entry = JobEntry::new(JobStatus::Pending);
+        assert!(entry.started_at.is_none());
+
+        entry.set_status(JobStatus::Running);
+        let started_at = entry.started_at.expect("started_at set when running");
+
+        // Completing keeps the original start time
+        entry.set_status(JobStatus::Failed {
+            error: "err".to_string(),
+        });
+        assert_eq!(entry.started_at, Some(started_at));
+    }
+
+    #[test]
+    fn test_job_entry_retry_clears_start_time() {
+        let mut entry = JobEntry::new(JobStatus::Pending);
+        entry.set_status(JobStatus::Running);
+        assert!(entry.started_at.is_some());
+
+        entry.set_status(JobStatus::Pending);
+        assert!(entry.started_at.is_none());
+    }
+
+    #[test]
+    fn test_status_response_attempts_serialization() {
+        let resp = StatusResponse::pending();
+        let json = serde_json::to_value(&resp).unwrap();
+        assert!(json.get("attempts").is_none());
+
+        let resp = StatusResponse::pending().with_attempts(vec![AttemptRecord {
+            attempt: 1,
+            started_at: Some(100),
+            failed_at: 160,
+            error_class: ErrorClass::Transient,
+            error: "proof_failed: On-chain verify failed: RPC HTTP error 502".to_string(),
+        }]);
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["attempts"][0]["attempt"], 1);
+        assert_eq!(json["attempts"][0]["failedAt"], 160);
+        assert_eq!(json["attempts"][0]["errorClass"], "transient");
+    }
+
+    #[test]
+    fn test_job_entry_ownership() {
+        assert!(JobEntry::new(JobStatus::Pending).api_key.is_none());
+
+        let entry = JobEntry::new(JobStatus::Pending).owned_by(Some("wallet"));
+        assert_eq!(entry.api_key.as_deref(), Some("wallet"));
+        assert!(entry.batch.is_none());
+
+        let batch = BatchRef {
+            batch_id: "b1".to_string(),
+            index: 3,
+            ss58_address: "5Grw".to_string(),
+        };
+        let entry = JobEntry::for_batch(JobStatus::Pending, "exchange", batch.clone());
+        assert_eq!(entry.api_key.as_deref(), Some("exchange"));
+        assert_eq!(entry.batch, Some(batch));
+    }
+
+    #[test]
+    fn test_batch_item_status_serialization() {
+        let item = BatchItemStatus {
+            index: 2,
+            ss58_address: "5Grw".to_string(),
+            job_id: None,
+            status: StatusResponse::failed(error_codes::NOT_ELIGIBLE, "nope".to_string()),
+        };
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["index"], 2);
+        assert_eq!(json["ss58Address"], "5Grw");
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["code"], "not_eligible");
+        assert!(json.get("jobId").is_none());
+    }
+
     #[test]
     fn test_cached_proof_expiry() {
         let proof = CachedProof {
             zk_proof: "0x".to_string(),
             public_values: "0x".to_string(),
+            proof_system: ProofSystem::Groth16,
             created_at: now_ts() - 100,
         };
         assert!(!proof.is_expired(200));
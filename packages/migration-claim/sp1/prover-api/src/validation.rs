@@ -1,8 +1,13 @@
 use alloy_primitives::U256;
 use axum::http::HeaderMap;
-use sr25519_claim_lib::ss58_decode;
+use serde::Serialize;
+use sr25519_claim_lib::{
+    compute_claim_challenge, ss58_decode_with_prefix, ss58_encode, SignatureScheme,
+    TANGLE_SS58_PREFIX,
+};
+use std::str::FromStr;
 
-use crate::types::{error_codes, ProveRequest};
+use crate::types::{error_codes, ChallengeDomain, ProveRequest};
 
 /// Validation error with code and message
 #[derive(Debug, Clone)]
@@ -32,17 +37,86 @@ impl std::fmt::Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
+/// Which SS58 network prefixes claim addresses may use
+///
+/// Eligibility is looked up by public key, so any prefix identifies the same account;
+/// restricting it catches typos and addresses copied from the wrong network.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PrefixPolicy {
+    /// Any valid network prefix
+    #[default]
+    Any,
+    /// Only the listed network ids
+    Allow(Vec<u16>),
+    /// Only the Tangle prefix (5845)
+    Tangle,
+}
+
+impl PrefixPolicy {
+    pub fn allows(&self, network_id: u16) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Allow(ids) => ids.contains(&network_id),
+            Self::Tangle => network_id == TANGLE_SS58_PREFIX,
+        }
+    }
+}
+
+impl FromStr for PrefixPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "any" => Ok(Self::Any),
+            "tangle" => Ok(Self::Tangle),
+            list => {
+                let ids = list
+                    .split(',')
+                    .map(|id| id.trim().parse::<u16>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| {
+                        format!(
+                            "unknown prefix policy `{list}` (expected any, tangle or a comma-separated list of network ids)"
+                        )
+                    })?;
+                Ok(Self::Allow(ids))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PrefixPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => f.write_str("any"),
+            Self::Tangle => f.write_str("tangle"),
+            Self::Allow(ids) => {
+                let ids: Vec<String> = ids.iter().map(u16::to_string).collect();
+                f.write_str(&ids.join(","))
+            }
+        }
+    }
+}
+
 /// Validated proof request with parsed fields
 #[derive(Debug, Clone)]
 pub struct ValidatedRequest {
     pub pubkey: [u8; 32],
-    pub signature: [u8; 64],
+    pub scheme: SignatureScheme,
+    pub signature: Vec<u8>,
     pub challenge: [u8; 32],
     pub amount: [u8; 32],
 }
 
 /// Validate and parse a proof request
-pub fn validate_request(request: &ProveRequest) -> Result<ValidatedRequest, ValidationError> {
+///
+/// With a `domain`, the challenge must be the one `TangleMigration` recomputes for
+/// that contract and chain, so a mismatch is caught before an expensive proof.
+pub fn validate_request(
+    request: &ProveRequest,
+    domain: Option<&ChallengeDomain>,
+    prefix_policy: &PrefixPolicy,
+) -> Result<ValidatedRequest, ValidationError> {
     // Check for empty fields
     if request.ss58_address.trim().is_empty() {
         return Err(ValidationError::invalid_input("ss58Address is required"));
@@ -68,15 +142,25 @@ pub fn validate_request(request: &ProveRequest) -> Result<ValidatedRequest, Vali
     }
 
     // Parse and validate SS58 address
-    let pubkey = ss58_decode(&request.ss58_address)
+    let (pubkey, network_id) = ss58_decode_with_prefix(&request.ss58_address)
         .map_err(|e| ValidationError::invalid_input(format!("Invalid ss58Address: {e}")))?;
+    if !prefix_policy.allows(network_id) {
+        return Err(ValidationError::new(
+            error_codes::PREFIX_NOT_ALLOWED,
+            format!(
+                "ss58Address uses network prefix {network_id}, allowed: {prefix_policy}. \
+                 Use GET /normalize/:address for the Tangle form"
+            ),
+        ));
+    }
 
-    // Parse and validate signature (64 bytes)
-    let signature = parse_hex_bytes::<64>(&request.signature)
+    // Parse and validate signature (64 bytes, 65 for ECDSA)
+    let scheme = request.signature_scheme;
+    let signature = parse_signature(&request.signature, scheme)
         .map_err(|e| ValidationError::invalid_input(format!("Invalid signature: {e}")))?;
 
-    // Validate EVM address format (20 bytes) - we check but don't store
-    parse_hex_bytes::<20>(&request.evm_address)
+    // Validate EVM address format (20 bytes)
+    let evm_address = parse_hex_bytes::<20>(&request.evm_address)
         .map_err(|e| ValidationError::invalid_input(format!("Invalid evmAddress: {e}")))?;
 
     // Parse and validate challenge (32 bytes)
@@ -87,14 +171,61 @@ pub fn validate_request(request: &ProveRequest) -> Result<ValidatedRequest, Vali
     let amount = parse_amount(&request.amount)
         .map_err(|e| ValidationError::invalid_input(format!("Invalid amount: {e}")))?;
 
+    if let Some(domain) = domain {
+        let expected = compute_claim_challenge(
+            domain.contract_address,
+            domain.chain_id,
+            evm_address,
+            amount,
+        );
+        if challenge != expected {
+            return Err(ValidationError::new(
+                error_codes::CHALLENGE_MISMATCH,
+                format!(
+                    "challenge must be keccak256(abi.encode(0x{}, {}, evmAddress, amount)) = 0x{}",
+                    hex::encode(domain.contract_address),
+                    domain.chain_id,
+                    hex::encode(expected)
+                ),
+            ));
+        }
+    }
+
     Ok(ValidatedRequest {
         pubkey,
+        scheme,
         signature,
         challenge,
         amount,
     })
 }
 
+/// An address re-encoded with the Tangle prefix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NormalizedAddress {
+    /// Canonical Tangle SS58 form
+    #[serde(rename = "ss58Address")]
+    pub ss58_address: String,
+    /// Account id, hex encoded
+    pub pubkey: String,
+    /// Network prefix of the input address
+    #[serde(rename = "networkId")]
+    pub network_id: u16,
+}
+
+/// Re-encode an SS58 address of any network with the Tangle prefix
+pub fn normalize_address(address: &str) -> Result<NormalizedAddress, ValidationError> {
+    let (pubkey, network_id) = ss58_decode_with_prefix(address.trim())
+        .map_err(|e| ValidationError::invalid_input(format!("Invalid ss58Address: {e}")))?;
+    let ss58_address = ss58_encode(&pubkey, TANGLE_SS58_PREFIX)
+        .map_err(|e| ValidationError::new(error_codes::INTERNAL_ERROR, e.to_string()))?;
+    Ok(NormalizedAddress {
+        ss58_address,
+        pubkey: format!("0x{}", hex::encode(pubkey)),
+        network_id,
+    })
+}
+
 /// Parse hex string to fixed-size byte array
 pub fn parse_hex_bytes<const N: usize>(value: &str) -> Result<[u8; N], String> {
     let trimmed = value.strip_prefix("0x").unwrap_or(value);
@@ -107,6 +238,21 @@ pub fn parse_hex_bytes<const N: usize>(value: &str) -> Result<[u8; N], String> {
     Ok(out)
 }
 
+/// Parse a hex signature, checking its length for the signature scheme
+pub fn parse_signature(value: &str, scheme: SignatureScheme) -> Result<Vec<u8>, String> {
+    let trimmed = value.strip_prefix("0x").unwrap_or(value);
+    let bytes = hex::decode(trimmed).map_err(|e| format!("invalid hex: {e}"))?;
+    if bytes.len() != scheme.signature_len() {
+        return Err(format!(
+            "expected {} bytes for {}, got {}",
+            scheme.signature_len(),
+            scheme.as_str(),
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
 /// Parse decimal string to U256 bytes
 pub fn parse_amount(value: &str) -> Result<[u8; 32], String> {
     let amount: U256 = value.parse().map_err(|_| "invalid decimal number")?;
@@ -119,10 +265,19 @@ pub fn is_decimal(value: &str) -> bool {
 }
 
 /// Generate cache key from request fields
+///
+/// Includes the signature scheme and wrapping, which the proof commits to or depends
+/// on, and the proof system, so proofs in different formats are cached apart.
 pub fn cache_key(request: &ProveRequest) -> String {
     format!(
-        "{}|{}|{}|{}",
-        request.ss58_address, request.evm_address, request.amount, request.challenge
+        "{}|{}|{}|{}|{}|{}|{}",
+        request.ss58_address,
+        request.evm_address,
+        request.amount,
+        request.challenge,
+        request.signature_scheme.as_str(),
+        request.signature_wrapping.as_str(),
+        request.proof_system()
     )
 }
 
@@ -210,6 +365,7 @@ fn strip_port(addr: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{ProofSystem, WrappingMode};
 
     fn valid_request() -> ProveRequest {
         ProveRequest {
@@ -218,13 +374,16 @@ mod tests {
             evm_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f4a3b2".to_string(),
             challenge: format!("0x{}", "12".repeat(32)),
             amount: "1000000000000000000".to_string(),
+            signature_scheme: SignatureScheme::Sr25519,
+            signature_wrapping: WrappingMode::Auto,
+            proof_system: None,
         }
     }
 
     #[test]
     fn test_validate_request_valid() {
         let request = valid_request();
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_ok());
         let validated = result.unwrap();
         assert_eq!(validated.signature.len(), 64);
@@ -237,7 +396,7 @@ mod tests {
     fn test_validate_request_empty_ss58() {
         let mut request = valid_request();
         request.ss58_address = "".to_string();
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("ss58Address"));
     }
@@ -246,7 +405,7 @@ mod tests {
     fn test_validate_request_empty_signature() {
         let mut request = valid_request();
         request.signature = "  ".to_string();
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("signature"));
     }
@@ -255,7 +414,7 @@ mod tests {
     fn test_validate_request_invalid_ss58() {
         let mut request = valid_request();
         request.ss58_address = "invalid_address".to_string();
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("ss58Address"));
     }
@@ -264,18 +423,111 @@ mod tests {
     fn test_validate_request_wrong_signature_length() {
         let mut request = valid_request();
         request.signature = "0x1234".to_string(); // Only 2 bytes
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.message.contains("signature"));
         assert!(err.message.contains("64 bytes"));
     }
 
+    #[test]
+    fn test_validate_request_signature_length_per_scheme() {
+        let mut request = valid_request();
+        request.signature_scheme = SignatureScheme::Ecdsa;
+        let err = validate_request(&request, None, &PrefixPolicy::Any).unwrap_err();
+        assert!(err.message.contains("65 bytes for ecdsa"));
+
+        request.signature = format!("0x{}", "ab".repeat(65));
+        let validated = validate_request(&request, None, &PrefixPolicy::Any).unwrap();
+        assert_eq!(validated.scheme, SignatureScheme::Ecdsa);
+        assert_eq!(validated.signature.len(), 65);
+    }
+
+    #[test]
+    fn test_validate_request_challenge_domain() {
+        let domain = ChallengeDomain {
+            contract_address: [0x11; 20],
+            chain_id: 8453,
+        };
+        let mut request = valid_request();
+        let err = validate_request(&request, Some(&domain), &PrefixPolicy::Any).unwrap_err();
+        assert_eq!(err.code, error_codes::CHALLENGE_MISMATCH);
+        assert!(err.message.contains("8453"));
+
+        let evm_address = parse_hex_bytes::<20>(&request.evm_address).unwrap();
+        let amount = parse_amount(&request.amount).unwrap();
+        let challenge = compute_claim_challenge([0x11; 20], 8453, evm_address, amount);
+        request.challenge = format!("0x{}", hex::encode(challenge));
+        assert!(validate_request(&request, Some(&domain), &PrefixPolicy::Any).is_ok());
+
+        // The same challenge is rejected on another chain
+        let other_chain = ChallengeDomain {
+            chain_id: 1,
+            ..domain
+        };
+        assert!(validate_request(&request, Some(&other_chain), &PrefixPolicy::Any).is_err());
+    }
+
+    #[test]
+    fn test_validate_request_prefix_policy() {
+        // valid_request() uses a generic Substrate (42) address
+        let request = valid_request();
+        let err = validate_request(&request, None, &PrefixPolicy::Tangle).unwrap_err();
+        assert_eq!(err.code, error_codes::PREFIX_NOT_ALLOWED);
+        assert!(err.message.contains("prefix 42"));
+
+        assert!(validate_request(&request, None, &PrefixPolicy::Allow(vec![0, 42])).is_ok());
+        assert!(validate_request(&request, None, &PrefixPolicy::Allow(vec![0, 2])).is_err());
+
+        let tangle = ProveRequest {
+            ss58_address: normalize_address(&request.ss58_address)
+                .unwrap()
+                .ss58_address,
+            ..request
+        };
+        assert!(validate_request(&tangle, None, &PrefixPolicy::Tangle).is_ok());
+    }
+
+    #[test]
+    fn test_prefix_policy_from_str() {
+        assert_eq!("ANY".parse::<PrefixPolicy>().unwrap(), PrefixPolicy::Any);
+        assert_eq!(
+            "tangle".parse::<PrefixPolicy>().unwrap(),
+            PrefixPolicy::Tangle
+        );
+        assert_eq!(
+            "0,5845".parse::<PrefixPolicy>().unwrap(),
+            PrefixPolicy::Allow(vec![0, 5845])
+        );
+        assert_eq!(PrefixPolicy::Allow(vec![0, 5845]).to_string(), "0,5845");
+        assert!("0,kusama".parse::<PrefixPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        let normalized =
+            normalize_address("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+        assert_eq!(normalized.network_id, 42);
+        assert!(normalized.ss58_address.starts_with("tg"));
+        assert_eq!(
+            normalized.pubkey,
+            "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+
+        // Tangle addresses are returned unchanged
+        let again = normalize_address(&normalized.ss58_address).unwrap();
+        assert_eq!(again.ss58_address, normalized.ss58_address);
+        assert_eq!(again.network_id, TANGLE_SS58_PREFIX);
+
+        let err = normalize_address("5GrwvaEF").unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_INPUT);
+    }
+
     #[test]
     fn test_validate_request_wrong_evm_address_length() {
         let mut request = valid_request();
         request.evm_address = "0x1234".to_string(); // Only 2 bytes
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.message.contains("evmAddress"));
@@ -286,7 +538,7 @@ mod tests {
     fn test_validate_request_wrong_challenge_length() {
         let mut request = valid_request();
         request.challenge = "0x1234".to_string(); // Only 2 bytes
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.message.contains("challenge"));
@@ -297,7 +549,7 @@ mod tests {
     fn test_validate_request_hex_amount() {
         let mut request = valid_request();
         request.amount = "0x1234".to_string(); // Hex, not decimal
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("amount"));
     }
@@ -306,7 +558,7 @@ mod tests {
     fn test_validate_request_negative_amount() {
         let mut request = valid_request();
         request.amount = "-100".to_string();
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("amount"));
     }
@@ -315,7 +567,7 @@ mod tests {
     fn test_validate_request_invalid_hex() {
         let mut request = valid_request();
         request.signature = "0xGGGG".to_string(); // Invalid hex
-        let result = validate_request(&request);
+        let result = validate_request(&request, None, &PrefixPolicy::Any);
         assert!(result.is_err());
         assert!(result.unwrap_err().message.contains("signature"));
     }
@@ -374,6 +626,27 @@ mod tests {
         assert!(key.contains(&request.evm_address));
         assert!(key.contains(&request.amount));
         assert!(key.contains(&request.challenge));
+
+        let plonk = ProveRequest {
+            proof_system: Some(ProofSystem::Plonk),
+            ..request.clone()
+        };
+        assert_ne!(cache_key(&plonk), key);
+        assert!(cache_key(&plonk).ends_with("|plonk"));
+
+        let ed25519 = ProveRequest {
+            signature_scheme: SignatureScheme::Ed25519,
+            ..request.clone()
+        };
+        assert_ne!(cache_key(&ed25519), key);
+        assert!(cache_key(&ed25519).contains("|ed25519|"));
+
+        let raw = ProveRequest {
+            signature_wrapping: WrappingMode::Raw,
+            ..request.clone()
+        };
+        assert_ne!(cache_key(&raw), key);
+        assert!(cache_key(&raw).contains("|raw|"));
     }
 
     #[test]
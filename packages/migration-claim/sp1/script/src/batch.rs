@@ -0,0 +1,344 @@
+//! Bulk proving from a JSONL file of signed claims
+//!
+//! Each input line is a signed claim as written by `sign` (the prover API's `/prove`
+//! body). Results are appended to the output JSONL file as they finish, one line per
+//! claim, so an interrupted run can be resumed: claims that already have a proof in the
+//! output are skipped, and failed ones are retried.
+
+use crate::{ProofOutput, SignedClaim};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sr25519_claim_lib::{ss58_decode, ProgramInput};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Outcome of one claim, written as a line of the output file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    /// Line of the claim in the input file (1-based)
+    pub line: usize,
+    pub ss58_address: String,
+    pub challenge: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<ProofOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Account id and normalized challenge of a claim
+type ClaimKey = ([u8; 32], String);
+
+/// Claims are identified by account and challenge, which binds the recipient, amount,
+/// contract and chain
+///
+/// The account is decoded, so the same claim under another SS58 prefix is still
+/// recognised. `None` for an address that doesn't decode.
+fn claim_key(ss58_address: &str, challenge: &str) -> Option<ClaimKey> {
+    let pubkey = ss58_decode(ss58_address).ok()?;
+    let challenge = challenge.trim_start_matches("0x").to_lowercase();
+    Some((pubkey, challenge))
+}
+
+impl BatchResult {
+    fn key(&self) -> Option<ClaimKey> {
+        claim_key(&self.ss58_address, &self.challenge)
+    }
+}
+
+/// Counts of a batch run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Claims already proven by an earlier run
+    pub skipped: usize,
+    pub proved: usize,
+    pub failed: usize,
+}
+
+/// Read signed claims from a JSONL file, with their line numbers
+pub fn load_claims(path: &str) -> Result<Vec<(usize, SignedClaim)>> {
+    let file = File::open(path).with_context(|| format!("Failed to read {path}"))?;
+    let mut claims = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read {path}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let claim = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid claim on line {} of {path}", index + 1))?;
+        claims.push((index + 1, claim));
+    }
+    Ok(claims)
+}
+
+/// Keep the proven results of an earlier run and open the output for appending
+///
+/// Failed results and a line cut short by an interrupted write are dropped, so the
+/// file is rewritten with only the proofs before new results are appended. The
+/// rewrite goes to `<path>.tmp` first and is renamed into place, so an interruption
+/// never loses the earlier proofs.
+fn resume(path: &str) -> Result<(HashSet<ClaimKey>, File)> {
+    let mut done = HashSet::new();
+    let mut proven = Vec::new();
+    if let Ok(file) = File::open(path) {
+        for line in BufReader::new(file).lines() {
+            let line = line.with_context(|| format!("Failed to read {path}"))?;
+            if let Ok(result) = serde_json::from_str::<BatchResult>(&line) {
+                if result.proof.is_some() {
+                    done.extend(result.key());
+                    proven.push(line);
+                }
+            }
+        }
+    }
+
+    let mut contents = proven.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    let tmp_path = format!("{path}.tmp");
+    std::fs::write(&tmp_path, contents).with_context(|| format!("Failed to write {tmp_path}"))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to move {tmp_path} to {path}"))?;
+    let file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {path}"))?;
+    Ok((done, file))
+}
+
+/// Prove `claims` with up to `parallelism` proofs in flight, appending results to `output`
+///
+/// `prove` is shared by all workers, so they use the same proving key.
+pub fn run_batch<F>(
+    claims: &[(usize, SignedClaim)],
+    output: &str,
+    parallelism: usize,
+    prove: F,
+) -> Result<BatchSummary>
+where
+    F: Fn(&ProgramInput) -> Result<ProofOutput> + Sync,
+{
+    let (done, file) = resume(output)?;
+    let pending: Vec<_> = claims
+        .iter()
+        .filter(|(_, claim)| {
+            !claim_key(&claim.ss58_address, &claim.challenge).is_some_and(|key| done.contains(&key))
+        })
+        .collect();
+    let skipped = claims.len() - pending.len();
+    if skipped > 0 {
+        println!("Skipping {skipped} claims already proven in {output}");
+    }
+
+    let next = AtomicUsize::new(0);
+    let file = Mutex::new(file);
+    let summary = Mutex::new(BatchSummary {
+        skipped,
+        ..BatchSummary::default()
+    });
+
+    let work = || -> Result<()> {
+        while let Some((line, claim)) = pending.get(next.fetch_add(1, Ordering::SeqCst)) {
+            let proof = claim.program_input().and_then(|input| prove(&input));
+            let result = BatchResult {
+                line: *line,
+                ss58_address: claim.ss58_address.clone(),
+                challenge: claim.challenge.clone(),
+                error: proof.as_ref().err().map(|e| format!("{e:#}")),
+                proof: proof.ok(),
+            };
+
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            writeln!(file, "{}", serde_json::to_string(&result)?)?;
+            file.flush()?;
+
+            let mut summary = summary.lock().unwrap_or_else(|e| e.into_inner());
+            match &result.error {
+                None => summary.proved += 1,
+                Some(_) => summary.failed += 1,
+            }
+            println!(
+                "[{}/{}] line {} {}: {}",
+                summary.proved + summary.failed,
+                pending.len(),
+                line,
+                claim.ss58_address,
+                result.error.as_deref().unwrap_or("proved")
+            );
+        }
+        Ok(())
+    };
+
+    let workers = parallelism.clamp(1, pending.len().max(1));
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(work)).collect();
+        handles.into_iter().try_for_each(|handle| {
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(anyhow!("Batch worker panicked")))
+        })
+    })?;
+
+    Ok(summary.into_inner().unwrap_or_else(|e| e.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sr25519_claim_lib::{ss58_encode, MessageWrapping, SignatureScheme, TANGLE_SS58_PREFIX};
+
+    fn claim(challenge: u8) -> SignedClaim {
+        SignedClaim {
+            ss58_address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+            signature: format!("0x{}", "ab".repeat(64)),
+            evm_address: format!("0x{}", "12".repeat(20)),
+            challenge: format!("0x{}", hex::encode([challenge; 32])),
+            amount: "100".to_string(),
+            signature_scheme: SignatureScheme::Sr25519,
+            signature_wrapping: MessageWrapping::Wrapped,
+        }
+    }
+
+    /// Fake prover that fails for challenges starting with 0xff
+    fn fake_prove(input: &ProgramInput) -> Result<ProofOutput> {
+        if input.challenge[0] == 0xff {
+            anyhow::bail!("signature verification failed");
+        }
+        Ok(ProofOutput {
+            proof: format!("0x00{}", hex::encode(&input.challenge[..1])),
+            public_values: "0x".to_string(),
+            vkey: "0x00aa".to_string(),
+            generation_time_secs: 0.0,
+        })
+    }
+
+    fn read_results(path: &str) -> Vec<BatchResult> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{name}-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_run_batch_writes_results() {
+        let output = temp_path("batch-results");
+        let claims: Vec<_> = [1, 2, 0xff, 3]
+            .into_iter()
+            .enumerate()
+            .map(|(i, challenge)| (i + 1, claim(challenge)))
+            .collect();
+
+        let summary = run_batch(&claims, &output, 3, fake_prove).unwrap();
+        assert_eq!(
+            summary,
+            BatchSummary {
+                skipped: 0,
+                proved: 3,
+                failed: 1
+            }
+        );
+
+        let mut results = read_results(&output);
+        results.sort_by_key(|result| result.line);
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1].proof.as_ref().unwrap().proof, "0x0002");
+        assert!(results[2].proof.is_none());
+        assert!(results[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("signature verification failed"));
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn test_run_batch_resumes() {
+        let output = temp_path("batch-resume");
+        let claims: Vec<_> = (1..=3).map(|i| (i, claim(i as u8))).collect();
+        run_batch(&claims[..2], &output, 1, fake_prove).unwrap();
+
+        // A failed result and a line cut short by an interrupted run
+        let failed = BatchResult {
+            line: 3,
+            ss58_address: claims[2].1.ss58_address.clone(),
+            challenge: claims[2].1.challenge.clone(),
+            proof: None,
+            error: Some("timed out".to_string()),
+        };
+        let mut file = OpenOptions::new().append(true).open(&output).unwrap();
+        writeln!(file, "{}", serde_json::to_string(&failed).unwrap()).unwrap();
+        write!(file, "{{\"line\":4,\"ss58Add").unwrap();
+        drop(file);
+
+        let proved = AtomicUsize::new(0);
+        let summary = run_batch(&claims, &output, 2, |input: &ProgramInput| {
+            proved.fetch_add(1, Ordering::SeqCst);
+            fake_prove(input)
+        })
+        .unwrap();
+
+        assert_eq!(proved.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            summary,
+            BatchSummary {
+                skipped: 2,
+                proved: 1,
+                failed: 0
+            }
+        );
+        let results = read_results(&output);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.proof.is_some()));
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn test_run_batch_resumes_across_address_formats() {
+        let output = temp_path("batch-formats");
+        let claims = [(1, claim(1))];
+        run_batch(&claims, &output, 1, fake_prove).unwrap();
+
+        // The same claim with the Tangle prefix and an unprefixed, uppercase challenge
+        let pubkey = ss58_decode(&claims[0].1.ss58_address).unwrap();
+        let mut same = claim(1);
+        same.ss58_address = ss58_encode(&pubkey, TANGLE_SS58_PREFIX).unwrap();
+        same.challenge = same.challenge.trim_start_matches("0x").to_uppercase();
+
+        let summary = run_batch(&[(1, same)], &output, 1, fake_prove).unwrap();
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(read_results(&output).len(), 1);
+        assert!(!std::path::Path::new(&format!("{output}.tmp")).exists());
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn test_load_claims() {
+        let input = temp_path("batch-claims");
+        let lines = [
+            serde_json::to_string(&claim(1)).unwrap(),
+            String::new(),
+            serde_json::to_string(&claim(2)).unwrap(),
+        ];
+        std::fs::write(&input, lines.join("\n")).unwrap();
+
+        let claims = load_claims(&input).unwrap();
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[1].0, 3);
+
+        std::fs::write(&input, "{\"ss58Address\": 1}\n").unwrap();
+        let err = load_claims(&input).unwrap_err();
+        assert!(format!("{err:#}").contains("line 1"));
+        std::fs::remove_file(&input).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! `claimWithZKProof` calldata from a proof and the Merkle tree
+//!
+//! Reads the `merkle-tree.json` written by `scripts/generateMerkleTree.ts` to find the
+//! claimant's allocation and Merkle proof.
+
+use alloy_primitives::{Address, Bytes, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+sol! {
+    function claimWithZKProof(
+        bytes32 pubkey,
+        uint256 amount,
+        bytes32[] merkleProof,
+        bytes zkProof,
+        address recipient
+    ) external;
+}
+
+/// Allocation of one account in the Merkle tree
+#[derive(Debug, Clone, Deserialize)]
+pub struct MerkleEntry {
+    /// Balance in wei (decimal string)
+    pub balance: String,
+    /// Merkle proof for `(pubkey, balance)`
+    pub proof: Vec<String>,
+    /// Public key (bytes32 hex); only set on entries keyed by SS58 address
+    pub pubkey: Option<String>,
+}
+
+/// The parts of `merkle-tree.json` needed to build a claim
+#[derive(Debug, Clone, Deserialize)]
+pub struct MerkleTree {
+    /// Entries keyed by SS58 address
+    #[serde(default)]
+    pub entries: HashMap<String, MerkleEntry>,
+    /// Entries keyed by lowercase pubkey, present in newer trees
+    #[serde(rename = "entriesByPubkey", default)]
+    pub entries_by_pubkey: HashMap<String, MerkleEntry>,
+}
+
+impl MerkleTree {
+    pub fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {path}"))
+    }
+
+    /// Find the allocation of a public key, whatever SS58 prefix it was listed under
+    pub fn entry(&self, pubkey: &[u8; 32]) -> Option<&MerkleEntry> {
+        let key = format!("0x{}", hex::encode(pubkey));
+        self.entries_by_pubkey.get(&key).or_else(|| {
+            self.entries
+                .values()
+                .find(|entry| entry.pubkey.as_deref().map(str::to_lowercase) == Some(key.clone()))
+        })
+    }
+}
+
+/// Arguments of a `claimWithZKProof` call, with the encoded calldata
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimCalldata {
+    pub pubkey: String,
+    pub amount: String,
+    pub merkle_proof: Vec<String>,
+    pub zk_proof: String,
+    pub recipient: String,
+    /// ABI-encoded call, selector included
+    pub calldata: String,
+}
+
+/// Build `claimWithZKProof` arguments for a proven claim
+///
+/// `amount` must match the allocation in the tree, or the contract would reject the
+/// Merkle proof.
+pub fn claim_calldata(
+    tree: &MerkleTree,
+    pubkey: [u8; 32],
+    amount: [u8; 32],
+    recipient: [u8; 20],
+    zk_proof: Vec<u8>,
+) -> Result<ClaimCalldata> {
+    let entry = tree
+        .entry(&pubkey)
+        .with_context(|| format!("0x{} is not in the Merkle tree", hex::encode(pubkey)))?;
+
+    let amount = U256::from_be_bytes(amount);
+    let balance: U256 = entry
+        .balance
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid balance in Merkle tree: {}: {e}", entry.balance))?;
+    if balance != amount {
+        bail!("Proof is for {amount} wei but the Merkle tree allocates {balance} wei");
+    }
+
+    let merkle_proof = entry
+        .proof
+        .iter()
+        .map(|node| {
+            let node = node.strip_prefix("0x").unwrap_or(node);
+            let mut bytes = [0u8; 32];
+            hex::decode_to_slice(node, &mut bytes)
+                .with_context(|| format!("Invalid Merkle proof node: {node}"))?;
+            Ok(FixedBytes::from(bytes))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let call = claimWithZKProofCall {
+        pubkey: FixedBytes::from(pubkey),
+        amount,
+        merkleProof: merkle_proof.clone(),
+        zkProof: Bytes::from(zk_proof.clone()),
+        recipient: Address::from(recipient),
+    };
+
+    Ok(ClaimCalldata {
+        pubkey: format!("0x{}", hex::encode(pubkey)),
+        amount: amount.to_string(),
+        merkle_proof: merkle_proof.iter().map(|node| node.to_string()).collect(),
+        zk_proof: format!("0x{}", hex::encode(&zk_proof)),
+        recipient: Address::from(recipient).to_checksum(None),
+        calldata: format!("0x{}", hex::encode(call.abi_encode())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> MerkleTree {
+        serde_json::from_value(serde_json::json!({
+            "entries": {
+                "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY": {
+                    "pubkey": "0xD43593C715FDD31C61141ABD04A99FD6822C8558854CCDE39A5684E7A56DA27D",
+                    "balance": "100",
+                    "proof": [format!("0x{}", "11".repeat(32)), format!("0x{}", "22".repeat(32))]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn alice() -> [u8; 32] {
+        let mut pubkey = [0u8; 32];
+        hex::decode_to_slice(
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+            &mut pubkey,
+        )
+        .unwrap();
+        pubkey
+    }
+
+    fn amount(value: u8) -> [u8; 32] {
+        let mut amount = [0u8; 32];
+        amount[31] = value;
+        amount
+    }
+
+    #[test]
+    fn test_claim_calldata() {
+        let calldata =
+            claim_calldata(&tree(), alice(), amount(100), [0x33; 20], vec![0x00, 0xab]).unwrap();
+
+        assert_eq!(calldata.merkle_proof.len(), 2);
+        assert_eq!(calldata.amount, "100");
+        assert_eq!(calldata.zk_proof, "0x00ab");
+
+        let encoded = hex::decode(&calldata.calldata[2..]).unwrap();
+        assert_eq!(encoded[..4], claimWithZKProofCall::SELECTOR);
+        let decoded = claimWithZKProofCall::abi_decode(&encoded).unwrap();
+        assert_eq!(decoded.pubkey.0, alice());
+        assert_eq!(decoded.amount, U256::from(100));
+        assert_eq!(decoded.merkleProof[1].0, [0x22; 32]);
+        assert_eq!(decoded.zkProof.to_vec(), vec![0x00, 0xab]);
+        assert_eq!(decoded.recipient, Address::from([0x33; 20]));
+    }
+
+    #[test]
+    fn test_claim_calldata_checks_tree() {
+        let err = claim_calldata(&tree(), alice(), amount(99), [0x33; 20], vec![]).unwrap_err();
+        assert!(err.to_string().contains("allocates 100 wei"));
+
+        let err = claim_calldata(&tree(), [0x01; 32], amount(100), [0x33; 20], vec![]).unwrap_err();
+        assert!(err.to_string().contains("not in the Merkle tree"));
+    }
+}
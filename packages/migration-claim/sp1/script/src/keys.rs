@@ -0,0 +1,169 @@
+//! SR25519 keys from Substrate secret URIs
+//!
+//! Supports the `subkey` URI forms `<mnemonic>[//hard][/soft][///password]`,
+//! `0x<32-byte seed>[...]`, and dev accounts such as `//Alice`, which derive from the
+//! well-known development phrase.
+
+use anyhow::{bail, Context, Result};
+use schnorrkel::derive::{ChainCode, Derivation};
+use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey};
+use sr25519_claim_lib::blake2_256;
+
+/// Mnemonic behind the `//Alice`, `//Bob`, ... development accounts
+pub const DEV_PHRASE: &str =
+    "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+/// A step of a derivation path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Junction {
+    Hard([u8; 32]),
+    Soft([u8; 32]),
+}
+
+impl Junction {
+    /// Chain code for a path segment: a `u64` or the SCALE-encoded string, hashed if
+    /// longer than 32 bytes
+    fn chain_code(segment: &str) -> [u8; 32] {
+        let encoded = match segment.parse::<u64>() {
+            Ok(index) => index.to_le_bytes().to_vec(),
+            Err(_) => scale_encode_str(segment),
+        };
+        let mut code = [0u8; 32];
+        if encoded.len() > 32 {
+            code = blake2_256(&encoded);
+        } else {
+            code[..encoded.len()].copy_from_slice(&encoded);
+        }
+        code
+    }
+}
+
+/// SCALE encoding of a string: compact length prefix followed by the bytes
+fn scale_encode_str(value: &str) -> Vec<u8> {
+    let len = value.len();
+    let mut out = match len {
+        0..=0x3f => vec![(len as u8) << 2],
+        0x40..=0x3fff => ((len as u16) << 2 | 0b01).to_le_bytes().to_vec(),
+        _ => ((len as u32) << 2 | 0b10).to_le_bytes().to_vec(),
+    };
+    out.extend_from_slice(value.as_bytes());
+    out
+}
+
+/// Derive an SR25519 keypair from a secret URI
+pub fn keypair_from_suri(suri: &str) -> Result<Keypair> {
+    let (rest, password) = match suri.split_once("///") {
+        Some((rest, password)) => (rest, Some(password)),
+        None => (suri, None),
+    };
+
+    // Split the phrase or seed from the derivation path
+    let path_start = rest.find('/').unwrap_or(rest.len());
+    let (phrase, path) = rest.split_at(path_start);
+    let phrase = match phrase.trim() {
+        "" => DEV_PHRASE,
+        phrase => phrase,
+    };
+
+    let mini_secret = if let Some(seed) = phrase.strip_prefix("0x") {
+        if password.is_some() {
+            bail!("A password can only be used with a mnemonic");
+        }
+        let seed = hex::decode(seed).context("Invalid hex seed")?;
+        MiniSecretKey::from_bytes(&seed).map_err(|e| anyhow::anyhow!("Invalid seed: {e}"))?
+    } else {
+        let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, phrase)
+            .context("Invalid mnemonic")?;
+        substrate_bip39::mini_secret_from_entropy(&mnemonic.to_entropy(), password.unwrap_or(""))
+            .map_err(|e| anyhow::anyhow!("Invalid mnemonic entropy: {e:?}"))?
+    };
+
+    let mut keypair = mini_secret.expand_to_keypair(ExpansionMode::Ed25519);
+    for junction in parse_path(path)? {
+        keypair = match junction {
+            Junction::Hard(code) => keypair
+                .secret
+                .hard_derive_mini_secret_key(Some(ChainCode(code)), b"")
+                .0
+                .expand_to_keypair(ExpansionMode::Ed25519),
+            Junction::Soft(code) => keypair.derived_key_simple(ChainCode(code), []).0,
+        };
+    }
+    Ok(keypair)
+}
+
+/// Parse `//hard/soft` segments into junctions
+fn parse_path(path: &str) -> Result<Vec<Junction>> {
+    let mut junctions = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        let (hard, tail) = match rest.strip_prefix("//") {
+            Some(tail) => (true, tail),
+            None => match rest.strip_prefix('/') {
+                Some(tail) => (false, tail),
+                None => bail!("Invalid derivation path: {path}"),
+            },
+        };
+        let end = tail.find('/').unwrap_or(tail.len());
+        let segment = &tail[..end];
+        if segment.is_empty() {
+            bail!("Empty junction in derivation path: {path}");
+        }
+        let code = Junction::chain_code(segment);
+        junctions.push(if hard {
+            Junction::Hard(code)
+        } else {
+            Junction::Soft(code)
+        });
+        rest = &tail[end..];
+    }
+    Ok(junctions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public_hex(suri: &str) -> String {
+        hex::encode(keypair_from_suri(suri).unwrap().public.to_bytes())
+    }
+
+    #[test]
+    fn test_dev_accounts() {
+        // `subkey inspect //Alice` and `subkey inspect //Bob`
+        assert_eq!(
+            public_hex("//Alice"),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+        assert_eq!(
+            public_hex("//Bob"),
+            "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"
+        );
+        assert_eq!(
+            public_hex(&format!("{DEV_PHRASE}//Alice")),
+            public_hex("//Alice")
+        );
+    }
+
+    #[test]
+    fn test_dev_phrase_root() {
+        // `subkey inspect "bottom drive obey ... walk"`
+        assert_eq!(
+            public_hex(DEV_PHRASE),
+            "46ebddef8cd9bb167dc30878d7113b7e168e6f0646beffd77d69d39bad76b47a"
+        );
+    }
+
+    #[test]
+    fn test_invalid_suris() {
+        assert!(keypair_from_suri("not a valid mnemonic").is_err());
+        assert!(keypair_from_suri("//Alice//").is_err());
+        assert!(keypair_from_suri("0x1234").is_err());
+    }
+
+    #[test]
+    fn test_scale_encode_str() {
+        assert_eq!(scale_encode_str("Alice"), b"\x14Alice".to_vec());
+        assert_eq!(scale_encode_str(&"a".repeat(64))[..2], [0x01, 0x01]);
+    }
+}
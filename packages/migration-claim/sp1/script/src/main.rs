@@ -1,40 +1,123 @@
 //! SP1 Host Script: Generate SR25519 Verification Proofs
 //!
-//! This script generates ZK proofs for SR25519 signature verification.
-//! It can be used as a CLI tool or integrated into a prover service.
+//! This script signs claim challenges, generates ZK proofs for SR25519 signature
+//! verification, verifies them offline and encodes the resulting `claimWithZKProof`
+//! calldata. It can be used
+//! as a CLI tool or integrated into a prover service.
+
+mod batch;
+mod calldata;
+mod keys;
+mod verify;
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use sp1_sdk::blocking::{ProveRequest, Prover, ProverClient, SP1Stdin};
+use calldata::{ClaimCalldata, MerkleTree};
+use clap::{Args, Parser, Subcommand};
+use schnorrkel::{signing_context, Keypair};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::blocking::{EnvProver, EnvProvingKey, ProveRequest, Prover, ProverClient, SP1Stdin};
 use sp1_sdk::{include_elf, HashableKey, ProvingKey};
-use sr25519_claim_lib::ProgramInput;
+use sr25519_claim_lib::{
+    compute_claim_challenge, encode_zk_proof, ss58_encode, MessageWrapping, ProgramInput,
+    PublicValues, SignatureScheme, TANGLE_SS58_PREFIX,
+};
 use std::time::Instant;
 
 /// The compiled ELF binary of the guest program.
 const ELF: sp1_sdk::Elf = include_elf!("sr25519-claim-program");
 
 #[derive(Parser, Debug)]
-#[command(author, version, about = "Generate SR25519 verification proofs")]
-struct Args {
-    /// The SS58 Substrate address (public key is derived from this)
-    #[arg(long)]
-    substrate_address: String,
+#[command(
+    author,
+    version,
+    about = "Sign, prove and encode SR25519 migration claims"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Sign the claim challenge with a Substrate secret URI
+    Sign(SignArgs),
+    /// Generate a claim proof
+    Prove(ProveArgs),
+    /// Prove many signed claims from a JSONL file with one proving key
+    Batch(BatchArgs),
+    /// Verify a proof offline against the embedded program
+    Verify(VerifyArgs),
+    /// Encode `claimWithZKProof` calldata for a proof
+    Calldata(CalldataArgs),
+}
 
-    /// The SR25519 signature (128 hex chars)
+#[derive(Args, Debug)]
+struct SignArgs {
+    /// Secret URI of the Substrate account: a mnemonic, a 0x seed or a dev account such as //Alice
     #[arg(long)]
-    signature: String,
+    suri: String,
 
     /// The EVM address claiming tokens (40 hex chars, with or without 0x)
     #[arg(long)]
     evm_address: String,
 
-    /// The claim amount in wei (decimal string)
+    /// The claim contract address the challenge is bound to
     #[arg(long)]
-    amount: String,
+    contract: String,
 
-    /// The challenge hash (64 hex chars)
+    /// The chain id the challenge is bound to
     #[arg(long)]
-    challenge: String,
+    chain_id: u64,
+
+    /// The claim amount in wei (decimal string)
+    #[arg(long, required_unless_present = "merkle_tree")]
+    amount: Option<String>,
+
+    /// Read the claim amount from this merkle-tree.json instead of --amount
+    #[arg(long, conflicts_with = "amount")]
+    merkle_tree: Option<String>,
+
+    /// Output file for the signed claim (JSON format)
+    #[arg(long, default_value = "claim.json")]
+    output: String,
+}
+
+#[derive(Args, Debug)]
+struct ProveArgs {
+    /// A signed claim written by `sign`, instead of the individual claim flags
+    #[arg(
+        long,
+        conflicts_with_all = ["substrate_address", "signature", "evm_address", "amount", "challenge"]
+    )]
+    claim: Option<String>,
+
+    /// The SS58 Substrate address (public key is derived from this)
+    #[arg(long, required_unless_present = "claim")]
+    substrate_address: Option<String>,
+
+    /// The signature over the challenge (64 bytes hex, 65 for ecdsa)
+    #[arg(long, required_unless_present = "claim")]
+    signature: Option<String>,
+
+    /// The signature scheme of the Substrate account (sr25519, ed25519 or ecdsa)
+    #[arg(long, default_value = "sr25519", value_parser = parse_scheme)]
+    scheme: SignatureScheme,
+
+    /// How the challenge was signed: wrapped in <Bytes>...</Bytes> (polkadot.js) or raw
+    #[arg(long, default_value = "wrapped", value_parser = parse_wrapping)]
+    wrapping: MessageWrapping,
+
+    /// The EVM address claiming tokens (40 hex chars, with or without 0x)
+    #[arg(long, required_unless_present = "claim")]
+    evm_address: Option<String>,
+
+    /// The claim amount in wei (decimal string)
+    #[arg(long, required_unless_present = "claim")]
+    amount: Option<String>,
+
+    /// The challenge hash (64 hex chars)
+    #[arg(long, required_unless_present = "claim")]
+    challenge: Option<String>,
 
     /// Output file for the proof (JSON format)
     #[arg(long, default_value = "proof.json")]
@@ -45,18 +128,127 @@ struct Args {
     mock: bool,
 }
 
-#[derive(serde::Serialize)]
+#[derive(Args, Debug)]
+struct BatchArgs {
+    /// JSONL file with one signed claim (as written by `sign`) per line
+    #[arg(long)]
+    input: String,
+
+    /// JSONL file the results are appended to; claims already proven in it are skipped
+    #[arg(long, default_value = "proofs.jsonl")]
+    output: String,
+
+    /// Maximum number of proofs generated at once
+    #[arg(long, default_value = "1")]
+    parallelism: usize,
+
+    /// Use mock prover for testing (no actual proof generation)
+    #[arg(long, default_value = "false")]
+    mock: bool,
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Proof file written by `prove`, or a prover API `/status` response
+    #[arg(long, default_value = "proof.json")]
+    proof: String,
+
+    /// Expected EVM recipient committed in the public values
+    #[arg(long)]
+    recipient: Option<String>,
+
+    /// Expected claim amount in wei (decimal string)
+    #[arg(long)]
+    amount: Option<String>,
+
+    /// Expected challenge hash (64 hex chars)
+    #[arg(long)]
+    challenge: Option<String>,
+
+    /// Accept mock proofs, which carry no Groth16 or PLONK proof to verify
+    #[arg(long, default_value = "false")]
+    allow_mock: bool,
+}
+
+#[derive(Args, Debug)]
+struct CalldataArgs {
+    /// Proof file written by `prove`
+    #[arg(long, default_value = "proof.json")]
+    proof: String,
+
+    /// Merkle tree with the claimant's allocation and Merkle proof
+    #[arg(long, default_value = "merkle-tree.json")]
+    merkle_tree: String,
+
+    /// Output file for the calldata (JSON format); printed to stdout if omitted
+    #[arg(long)]
+    output: Option<String>,
+}
+
+/// A signed claim, in the shape of the prover API's `/prove` request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedClaim {
+    ss58_address: String,
+    signature: String,
+    evm_address: String,
+    challenge: String,
+    amount: String,
+    #[serde(default)]
+    signature_scheme: SignatureScheme,
+    #[serde(default)]
+    signature_wrapping: MessageWrapping,
+}
+
+impl SignedClaim {
+    fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {path}"))
+    }
+
+    fn program_input(&self) -> Result<ProgramInput> {
+        Ok(ProgramInput {
+            substrate_address: self.ss58_address.clone(),
+            scheme: self.signature_scheme,
+            signature: parse_signature(&self.signature, self.signature_scheme)
+                .context("Invalid signature")?,
+            wrapping: self.signature_wrapping,
+            evm_address: parse_hex_bytes(&self.evm_address).context("Invalid EVM address")?,
+            amount: parse_u256_string(&self.amount).context("Invalid amount")?,
+            challenge: parse_hex_bytes(&self.challenge).context("Invalid challenge")?,
+        })
+    }
+}
+
+/// A proof as written by `prove`
+///
+/// Also reads the prover API's `/status` response, which names the fields `zkProof` and
+/// `publicValues` and carries no vkey.
+#[derive(Debug, Serialize, Deserialize)]
 struct ProofOutput {
-    /// The proof bytes (hex encoded)
+    /// The signature scheme byte followed by the proof bytes (hex encoded)
+    #[serde(alias = "zkProof")]
     proof: String,
     /// The public values (hex encoded)
+    #[serde(alias = "publicValues")]
     public_values: String,
     /// The verification key (hex encoded)
+    #[serde(default)]
     vkey: String,
     /// Proof generation time in seconds
+    #[serde(default)]
     generation_time_secs: f64,
 }
 
+impl ProofOutput {
+    fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {path}"))
+    }
+}
+
 fn parse_hex_bytes<const N: usize>(s: &str) -> Result<[u8; N]> {
     let s = s.strip_prefix("0x").unwrap_or(s);
     let bytes = hex::decode(s).context("Invalid hex string")?;
@@ -68,100 +260,192 @@ fn parse_hex_bytes<const N: usize>(s: &str) -> Result<[u8; N]> {
     Ok(arr)
 }
 
+fn parse_scheme(s: &str) -> std::result::Result<SignatureScheme, String> {
+    SignatureScheme::ALL
+        .into_iter()
+        .find(|scheme| scheme.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("unknown signature scheme: {s}"))
+}
+
+fn parse_wrapping(s: &str) -> std::result::Result<MessageWrapping, String> {
+    MessageWrapping::ALL
+        .into_iter()
+        .find(|wrapping| wrapping.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("unknown wrapping: {s}"))
+}
+
+fn parse_signature(s: &str, scheme: SignatureScheme) -> Result<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s).context("Invalid hex string")?;
+    if bytes.len() != scheme.signature_len() {
+        anyhow::bail!(
+            "Expected {} bytes for {}, got {}",
+            scheme.signature_len(),
+            scheme.as_str(),
+            bytes.len()
+        );
+    }
+    Ok(bytes)
+}
+
 fn parse_u256_string(s: &str) -> Result<[u8; 32]> {
     // Parse decimal string to U256 and convert to big-endian bytes
-    let value: primitive_types::U256 = s.parse().context("Invalid U256 string")?;
+    // `FromStr` for primitive-types' U256 parses hex, so parse decimal explicitly
+    let value = primitive_types::U256::from_dec_str(s).context("Invalid U256 string")?;
     let mut bytes = [0u8; 32];
     value.to_big_endian(&mut bytes);
     Ok(bytes)
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenv::dotenv().ok();
+/// Sign the claim challenge for `contract` on `chain_id` the way polkadot.js `signRaw` does
+fn sign_claim(
+    keypair: &Keypair,
+    contract: [u8; 20],
+    chain_id: u64,
+    evm_address: [u8; 20],
+    amount: &str,
+) -> Result<SignedClaim> {
+    let amount_bytes = parse_u256_string(amount).context("Invalid amount")?;
+    let challenge = compute_claim_challenge(contract, chain_id, evm_address, amount_bytes);
+    let wrapping = MessageWrapping::Wrapped;
+    let signature =
+        keypair.sign(signing_context(b"substrate").bytes(&wrapping.message(&challenge)));
+
+    Ok(SignedClaim {
+        ss58_address: ss58_encode(&keypair.public.to_bytes(), TANGLE_SS58_PREFIX)?,
+        signature: format!("0x{}", hex::encode(signature.to_bytes())),
+        evm_address: format!("0x{}", hex::encode(evm_address)),
+        challenge: format!("0x{}", hex::encode(challenge)),
+        amount: primitive_types::U256::from_big_endian(&amount_bytes).to_string(),
+        signature_scheme: SignatureScheme::Sr25519,
+        signature_wrapping: wrapping,
+    })
+}
 
-    let args = Args::parse();
-    match std::env::var("SP1_PROVER").as_deref() {
-        _ if args.mock => std::env::set_var("SP1_PROVER", "mock"),
-        Ok("network") => anyhow::bail!(
-            "SP1_PROVER=network is disabled in this build. Use SP1_PROVER=local or --mock."
-        ),
-        Ok("local") | Err(_) => std::env::set_var("SP1_PROVER", "cpu"),
-        _ => {}
-    }
+fn sign(args: SignArgs) -> Result<()> {
+    let keypair = keys::keypair_from_suri(&args.suri).context("Invalid secret URI")?;
+    let evm_address: [u8; 20] =
+        parse_hex_bytes(&args.evm_address).context("Invalid EVM address")?;
+    let contract: [u8; 20] = parse_hex_bytes(&args.contract).context("Invalid contract address")?;
+
+    let amount = match (args.amount, args.merkle_tree) {
+        (Some(amount), _) => amount,
+        (None, Some(path)) => {
+            let pubkey = keypair.public.to_bytes();
+            MerkleTree::load(&path)?
+                .entry(&pubkey)
+                .with_context(|| format!("0x{} is not in {path}", hex::encode(pubkey)))?
+                .balance
+                .clone()
+        }
+        (None, None) => anyhow::bail!("Either --amount or --merkle-tree is required"),
+    };
 
-    println!("SR25519 Claim Proof Generator");
-    println!("=============================");
+    let claim = sign_claim(&keypair, contract, args.chain_id, evm_address, &amount)?;
+    println!("Substrate Address: {}", claim.ss58_address);
+    println!("EVM Address: {}", claim.evm_address);
+    println!("Amount: {} wei", claim.amount);
+    println!("Challenge: {}", claim.challenge);
 
-    // Parse inputs
-    let signature: [u8; 64] = parse_hex_bytes(&args.signature)
-        .context("Invalid signature")?;
-    let evm_address: [u8; 20] = parse_hex_bytes(&args.evm_address)
-        .context("Invalid EVM address")?;
-    let amount: [u8; 32] = parse_u256_string(&args.amount)
-        .context("Invalid amount")?;
-    let challenge: [u8; 32] = parse_hex_bytes(&args.challenge)
-        .context("Invalid challenge")?;
-
-    println!("Substrate Address: {}", args.substrate_address);
-    println!("EVM Address: 0x{}", hex::encode(&evm_address));
-    println!("Amount: {} wei", args.amount);
-    println!("Challenge: 0x{}", hex::encode(&challenge));
-
-    // Create program input (public key is derived from SS58 inside the ZK circuit)
-    let input = ProgramInput {
-        substrate_address: args.substrate_address.clone(),
-        signature,
-        evm_address,
-        amount,
-        challenge,
-    };
+    std::fs::write(&args.output, serde_json::to_string_pretty(&claim)?)?;
+    println!("\nSigned claim written to: {}", args.output);
+    Ok(())
+}
 
+/// Prove a claim input with the prover selected by `SP1_PROVER`
+fn generate_proof(input: &ProgramInput) -> Result<ProofOutput> {
     // Initialize the SP1 prover client
     let client = ProverClient::from_env();
 
-    // Prepare the stdin with our inputs
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&input);
-
     // Setup the program (get proving and verification keys)
     println!("\nSetting up program...");
     let pk = client.setup(ELF)?;
-    let vkey_bytes = pk.verifying_key().bytes32();
-    println!("Verification Key: 0x{}", hex::encode(&vkey_bytes));
+    println!("Verification Key: {}", pk.verifying_key().bytes32());
 
-    // Generate the proof
+    // Generate the proof. Groth16 is what the contract verifies; the mock prover
+    // returns an empty Groth16 proof, which a mock verifier accepts.
     println!("\nGenerating proof...");
-    let start = Instant::now();
+    println!("Using Groth16 prover (this may take a while)...");
+    let output = prove_with_key(&client, &pk, input)?;
+    println!("Proof generated in {:.2}s", output.generation_time_secs);
+    println!("Public Values: {}", output.public_values);
+    println!("Proof verified successfully!");
 
-    let proof = if args.mock {
-        println!("Using mock prover (no real proof)");
-        client.prove(&pk, stdin).run()?
-    } else {
-        // Use Groth16 for on-chain verification
-        println!("Using Groth16 prover (this may take a while)...");
-        client.prove(&pk, stdin).groth16().run()?
-    };
+    Ok(output)
+}
 
-    let generation_time = start.elapsed();
-    println!("Proof generated in {:.2?}", generation_time);
+/// Prove and verify a claim input with a proving key that is already set up
+fn prove_with_key(
+    client: &EnvProver,
+    pk: &EnvProvingKey,
+    input: &ProgramInput,
+) -> Result<ProofOutput> {
+    // Prepare the stdin with our inputs
+    let mut stdin = SP1Stdin::new();
+    stdin.write(input);
 
-    // Extract public values
-    let public_values_bytes = proof.public_values.to_vec();
-    println!("Public Values: 0x{}", hex::encode(&public_values_bytes));
+    let start = Instant::now();
+    let proof = client.prove(pk, stdin).groth16().run()?;
+    let generation_time = start.elapsed();
 
-    // Verify the proof
-    println!("\nVerifying proof...");
     client.verify(&proof, pk.verifying_key(), None)?;
-    println!("Proof verified successfully!");
 
-    // Create output
-    let output = ProofOutput {
-        proof: format!("0x{}", hex::encode(proof.bytes())),
-        public_values: format!("0x{}", hex::encode(&public_values_bytes)),
-        vkey: format!("0x{}", hex::encode(&vkey_bytes)),
+    let zk_proof = encode_zk_proof(input.scheme, &proof.bytes());
+
+    Ok(ProofOutput {
+        proof: format!("0x{}", hex::encode(&zk_proof)),
+        public_values: format!("0x{}", hex::encode(proof.public_values.to_vec())),
+        vkey: pk.verifying_key().bytes32(),
         generation_time_secs: generation_time.as_secs_f64(),
+    })
+}
+
+/// Select the SP1 prover from `SP1_PROVER`, or the mock prover with `--mock`
+fn select_prover(mock: bool) -> Result<()> {
+    match std::env::var("SP1_PROVER").as_deref() {
+        _ if mock => std::env::set_var("SP1_PROVER", "mock"),
+        Ok("network") => anyhow::bail!(
+            "SP1_PROVER=network is disabled in this build. Use SP1_PROVER=local or --mock."
+        ),
+        Ok("local") | Err(_) => std::env::set_var("SP1_PROVER", "cpu"),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn prove(args: ProveArgs) -> Result<()> {
+    select_prover(args.mock)?;
+
+    println!("SR25519 Claim Proof Generator");
+    println!("=============================");
+
+    // Parse inputs, either from a signed claim or from the individual flags
+    let claim = match &args.claim {
+        Some(path) => SignedClaim::load(path)?,
+        None => SignedClaim {
+            ss58_address: args.substrate_address.unwrap_or_default(),
+            signature: args.signature.unwrap_or_default(),
+            evm_address: args.evm_address.unwrap_or_default(),
+            challenge: args.challenge.unwrap_or_default(),
+            amount: args.amount.unwrap_or_default(),
+            signature_scheme: args.scheme,
+            signature_wrapping: args.wrapping,
+        },
     };
+    // Public key is derived from SS58 inside the ZK circuit
+    let input = claim.program_input()?;
+
+    println!("Substrate Address: {}", input.substrate_address);
+    println!("Signature Scheme: {}", input.scheme.as_str());
+    println!("Challenge Wrapping: {}", input.wrapping.as_str());
+    println!("EVM Address: 0x{}", hex::encode(input.evm_address));
+    println!("Amount: {} wei", claim.amount);
+    println!("Challenge: 0x{}", hex::encode(input.challenge));
+    if args.mock {
+        println!("Using mock prover (no real proof)");
+    }
+
+    let output = generate_proof(&input)?;
 
     // Write to file
     let json = serde_json::to_string_pretty(&output)?;
@@ -171,6 +455,143 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn batch(args: BatchArgs) -> Result<()> {
+    select_prover(args.mock)?;
+
+    println!("SR25519 Claim Batch Prover");
+    println!("==========================");
+
+    let claims = batch::load_claims(&args.input)?;
+    println!("Loaded {} claims from {}", claims.len(), args.input);
+
+    // Set up once; every worker proves with the same key
+    let client = ProverClient::from_env();
+    println!("\nSetting up program...");
+    let pk = client.setup(ELF)?;
+    println!("Verification Key: {}", pk.verifying_key().bytes32());
+
+    println!(
+        "\nProving with parallelism {}{}...",
+        args.parallelism,
+        if args.mock { " (mock prover)" } else { "" }
+    );
+    let summary = batch::run_batch(&claims, &args.output, args.parallelism, |input| {
+        prove_with_key(&client, &pk, input)
+    })?;
+
+    println!(
+        "\nProved {}, failed {}, skipped {} already proven. Results in {}",
+        summary.proved, summary.failed, summary.skipped, args.output
+    );
+    if summary.failed > 0 {
+        anyhow::bail!(
+            "{} claims failed; rerun the same command to retry them",
+            summary.failed
+        );
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<()> {
+    println!("SR25519 Claim Proof Verifier");
+    println!("============================");
+
+    let output = ProofOutput::load(&args.proof)?;
+    let expected = verify::Expected {
+        recipient: args
+            .recipient
+            .as_deref()
+            .map(parse_hex_bytes)
+            .transpose()
+            .context("Invalid recipient")?,
+        amount: args
+            .amount
+            .as_deref()
+            .map(parse_u256_string)
+            .transpose()
+            .context("Invalid amount")?,
+        challenge: args
+            .challenge
+            .as_deref()
+            .map(parse_hex_bytes)
+            .transpose()
+            .context("Invalid challenge")?,
+    };
+
+    // The verification key of the program this binary was built with
+    let client = ProverClient::from_env();
+    let pk = client.setup(ELF)?;
+    let vkey = pk.verifying_key().bytes32();
+    println!("Verification Key: {vkey}");
+
+    let verified = verify::verify_proof_output(&output, &vkey, args.allow_mock)?;
+    match verified.system {
+        Some(system) => println!("{} proof verified successfully!", system.as_str()),
+        None => println!("Mock proof accepted (no proof to verify)"),
+    }
+    let values = verified.values;
+
+    println!("\nPublic Values:");
+    println!("  Public Key: 0x{}", hex::encode(values.pubkey));
+    println!("  EVM Address: 0x{}", hex::encode(values.evm_address));
+    println!(
+        "  Amount: {} wei",
+        primitive_types::U256::from_big_endian(&values.amount)
+    );
+    println!("  Challenge: 0x{}", hex::encode(values.challenge));
+    println!("  Signature Scheme: {}", values.scheme.as_str());
+
+    expected.check(&values)?;
+    println!("\nAll checks passed");
+    Ok(())
+}
+
+/// Build `claimWithZKProof` arguments from a proof and the Merkle tree
+fn encode_claim(proof: &ProofOutput, tree: &MerkleTree) -> Result<ClaimCalldata> {
+    let public_values = hex::decode(proof.public_values.trim_start_matches("0x"))
+        .context("Invalid public values hex")?;
+    let public_values =
+        PublicValues::abi_decode(&public_values).context("Invalid public values")?;
+    let zk_proof =
+        hex::decode(proof.proof.trim_start_matches("0x")).context("Invalid proof hex")?;
+
+    calldata::claim_calldata(
+        tree,
+        public_values.pubkey,
+        public_values.amount,
+        public_values.evm_address,
+        zk_proof,
+    )
+}
+
+fn calldata(args: CalldataArgs) -> Result<()> {
+    let proof = ProofOutput::load(&args.proof)?;
+    let tree = MerkleTree::load(&args.merkle_tree)?;
+    let json = serde_json::to_string_pretty(&encode_claim(&proof, &tree)?)?;
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, &json)?;
+            println!("Calldata written to: {path}");
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    match Cli::parse().command {
+        Command::Sign(args) => sign(args),
+        Command::Prove(args) => prove(args),
+        Command::Batch(args) => batch(args),
+        Command::Verify(args) => verify(args),
+        Command::Calldata(args) => calldata(args),
+    }
+}
+
 /// Test module for the SP1 script
 ///
 /// Note: These tests require the SP1 toolchain due to serde/alloy compatibility.
@@ -194,7 +615,7 @@ mod tests {
         let public_key = keypair.public.to_bytes();
 
         // Encode as SS58 with Tangle prefix (5845)
-        let substrate_address = ss58_encode(&public_key, 5845);
+        let substrate_address = ss58_encode(&public_key, 5845).unwrap();
 
         // Create a test challenge (simulating keccak256(contractAddress, chainId, evmAddress))
         let challenge = [42u8; 32];
@@ -212,7 +633,10 @@ mod tests {
 
         let input = ProgramInput {
             substrate_address,
-            signature: signature_bytes,
+            scheme: SignatureScheme::Sr25519,
+            signature: signature_bytes.to_vec(),
+            // Signed over the bare challenge above
+            wrapping: MessageWrapping::Raw,
             evm_address,
             amount,
             challenge,
@@ -249,6 +673,33 @@ mod tests {
         assert!(err.is_err());
     }
 
+    #[test]
+    fn test_parse_signature_length_per_scheme() {
+        let sig64 = format!("0x{}", "ab".repeat(64));
+        let sig65 = format!("0x{}", "ab".repeat(65));
+
+        assert_eq!(
+            parse_signature(&sig64, SignatureScheme::Sr25519)
+                .unwrap()
+                .len(),
+            64
+        );
+        assert_eq!(
+            parse_signature(&sig64, SignatureScheme::Ed25519)
+                .unwrap()
+                .len(),
+            64
+        );
+        assert_eq!(
+            parse_signature(&sig65, SignatureScheme::Ecdsa)
+                .unwrap()
+                .len(),
+            65
+        );
+        assert!(parse_signature(&sig64, SignatureScheme::Ecdsa).is_err());
+        assert!(parse_signature(&sig65, SignatureScheme::Sr25519).is_err());
+    }
+
     #[test]
     fn test_parse_u256_string() {
         // Test small value
@@ -266,6 +717,68 @@ mod tests {
         assert!(result.iter().all(|&b| b == 0xFF));
     }
 
+    #[test]
+    fn test_sign_claim() {
+        let keypair = keys::keypair_from_suri("//Alice").unwrap();
+        let contract = [0x11u8; 20];
+        let evm_address = [0x12u8; 20];
+        let claim = sign_claim(&keypair, contract, 3799, evm_address, "100").unwrap();
+
+        assert_eq!(
+            claim.ss58_address,
+            ss58_encode(&keypair.public.to_bytes(), 5845).unwrap()
+        );
+        assert_eq!(claim.signature_wrapping, MessageWrapping::Wrapped);
+
+        // The claim parses into a program input bound to the computed challenge
+        let input = claim.program_input().unwrap();
+        assert_eq!(
+            input.challenge,
+            compute_claim_challenge(contract, 3799, evm_address, input.amount)
+        );
+        assert_eq!(input.amount[31], 100);
+
+        // The signature covers the <Bytes>-wrapped challenge, as the program checks
+        let signature = schnorrkel::Signature::from_bytes(&input.signature).unwrap();
+        let message = input.wrapping.message(&input.challenge);
+        assert!(keypair
+            .public
+            .verify(signing_context(b"substrate").bytes(&message), &signature)
+            .is_ok());
+    }
+
+    /// Integration test: sign, prove and encode calldata with the mock prover, offline
+    #[test]
+    #[ignore = "Requires SP1 program ELF to be built first (cargo prove build)"]
+    fn test_claim_kit_flow_mock() {
+        let keypair = keys::keypair_from_suri("//Alice").unwrap();
+        let pubkey = keypair.public.to_bytes();
+        let claim = sign_claim(&keypair, [0x11; 20], 3799, [0x12; 20], "100").unwrap();
+
+        std::env::set_var("SP1_PROVER", "mock");
+        let proof = generate_proof(&claim.program_input().unwrap()).expect("Mock proving failed");
+
+        let tree: MerkleTree = serde_json::from_value(serde_json::json!({
+            "entriesByPubkey": {
+                format!("0x{}", hex::encode(pubkey)): {
+                    "balance": "100",
+                    "proof": [format!("0x{}", "ab".repeat(32))]
+                }
+            }
+        }))
+        .unwrap();
+        let calldata = encode_claim(&proof, &tree).unwrap();
+
+        assert_eq!(calldata.pubkey, format!("0x{}", hex::encode(pubkey)));
+        assert_eq!(calldata.amount, "100");
+        assert_eq!(calldata.merkle_proof.len(), 1);
+        // Mock Groth16 proofs are empty; only the scheme byte remains
+        assert_eq!(calldata.zk_proof, "0x00");
+
+        let verified = verify::verify_proof_output(&proof, &proof.vkey, true).unwrap();
+        assert_eq!(verified.values.pubkey, pubkey);
+    }
+
     /// Integration test: Run the full ZK program with mock prover
     /// This verifies the entire flow from input creation to proof verification
     #[test]
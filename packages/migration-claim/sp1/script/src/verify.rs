@@ -0,0 +1,299 @@
+//! Offline verification of a `proof.json`
+//!
+//! Checks a proof written by `prove` (or returned by the prover API) against the
+//! embedded program without proving again.
+
+use crate::ProofOutput;
+use anyhow::{anyhow, bail, Context, Result};
+use sha2::{Digest, Sha256};
+use sp1_verifier::{Groth16Verifier, PlonkVerifier, GROTH16_VK_BYTES, PLONK_VK_BYTES};
+use sr25519_claim_lib::{decode_zk_proof, PublicValues};
+
+/// Proof system of an on-chain proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofSystem {
+    Groth16,
+    Plonk,
+}
+
+impl ProofSystem {
+    pub const ALL: [ProofSystem; 2] = [ProofSystem::Groth16, ProofSystem::Plonk];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProofSystem::Groth16 => "Groth16",
+            ProofSystem::Plonk => "PLONK",
+        }
+    }
+
+    fn vk_bytes(&self) -> &'static [u8] {
+        match self {
+            ProofSystem::Groth16 => *GROTH16_VK_BYTES,
+            ProofSystem::Plonk => *PLONK_VK_BYTES,
+        }
+    }
+
+    /// The 4-byte verifier selector proofs of this system start with
+    ///
+    /// It is the start of the SHA-256 of the verifying key, so it also changes with the
+    /// SP1 version.
+    pub fn selector(&self) -> [u8; 4] {
+        let digest = Sha256::digest(self.vk_bytes());
+        [digest[0], digest[1], digest[2], digest[3]]
+    }
+
+    /// The proof system whose verifier selector the proof starts with
+    pub fn of_proof(proof: &[u8]) -> Option<Self> {
+        let selector = proof.get(..4)?;
+        Self::ALL
+            .into_iter()
+            .find(|system| system.selector() == selector)
+    }
+
+    fn verify(&self, proof: &[u8], public_values: &[u8], vkey: &str) -> Result<()> {
+        match self {
+            ProofSystem::Groth16 => {
+                Groth16Verifier::verify(proof, public_values, vkey, self.vk_bytes())
+                    .map_err(|e| anyhow!("Groth16 verification failed: {e}"))
+            }
+            ProofSystem::Plonk => {
+                PlonkVerifier::verify(proof, public_values, vkey, self.vk_bytes())
+                    .map_err(|e| anyhow!("PLONK verification failed: {e}"))
+            }
+        }
+    }
+}
+
+/// Public values a proof is expected to commit to; unset fields are not checked
+#[derive(Debug, Clone, Default)]
+pub struct Expected {
+    pub recipient: Option<[u8; 20]>,
+    pub amount: Option<[u8; 32]>,
+    pub challenge: Option<[u8; 32]>,
+}
+
+impl Expected {
+    /// Compare the public values against the expected ones, listing every mismatch
+    pub fn check(&self, values: &PublicValues) -> Result<()> {
+        let mut mismatches = Vec::new();
+        if let Some(recipient) = self.recipient {
+            if recipient != values.evm_address {
+                mismatches.push(format!(
+                    "recipient: expected 0x{}, proof commits 0x{}",
+                    hex::encode(recipient),
+                    hex::encode(values.evm_address)
+                ));
+            }
+        }
+        if let Some(amount) = self.amount {
+            if amount != values.amount {
+                mismatches.push(format!(
+                    "amount: expected {} wei, proof commits {} wei",
+                    primitive_types::U256::from_big_endian(&amount),
+                    primitive_types::U256::from_big_endian(&values.amount)
+                ));
+            }
+        }
+        if let Some(challenge) = self.challenge {
+            if challenge != values.challenge {
+                mismatches.push(format!(
+                    "challenge: expected 0x{}, proof commits 0x{}",
+                    hex::encode(challenge),
+                    hex::encode(values.challenge)
+                ));
+            }
+        }
+
+        if !mismatches.is_empty() {
+            bail!("Public values mismatch:\n  {}", mismatches.join("\n  "));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of [`verify_proof_output`]
+#[derive(Debug, Clone)]
+pub struct VerifiedProof {
+    /// The decoded public values
+    pub values: PublicValues,
+    /// The proof system that was verified, `None` for an accepted mock proof
+    pub system: Option<ProofSystem>,
+}
+
+/// Verify a proof for the program with verification key `vkey` and decode its public values
+///
+/// The verifier selector at the start of the proof picks Groth16 or PLONK. Mock proofs
+/// carry no proof and are only accepted with `allow_mock`. A proof without a vkey (as
+/// returned by the prover API) is checked against `vkey` by the verification alone.
+pub fn verify_proof_output(
+    output: &ProofOutput,
+    vkey: &str,
+    allow_mock: bool,
+) -> Result<VerifiedProof> {
+    if !output.vkey.is_empty() && !output.vkey.eq_ignore_ascii_case(vkey) {
+        bail!(
+            "Proof is for vkey {} but the embedded program has vkey {vkey}",
+            output.vkey
+        );
+    }
+
+    let public_values = hex::decode(output.public_values.trim_start_matches("0x"))
+        .context("Invalid public values hex")?;
+    let values = PublicValues::abi_decode(&public_values).context("Invalid public values")?;
+
+    let proof = hex::decode(output.proof.trim_start_matches("0x")).context("Invalid proof hex")?;
+    let (scheme, sp1_proof) = decode_zk_proof(&proof).map_err(|e| anyhow!("Invalid proof: {e}"))?;
+    if scheme != values.scheme {
+        bail!(
+            "Proof scheme {} does not match the committed scheme {}",
+            scheme.as_str(),
+            values.scheme.as_str()
+        );
+    }
+
+    if sp1_proof.is_empty() {
+        if !allow_mock {
+            bail!(
+                "Proof is empty after the scheme byte (mock proof); pass --allow-mock to accept it"
+            );
+        }
+        return Ok(VerifiedProof {
+            values,
+            system: None,
+        });
+    }
+
+    let system = ProofSystem::of_proof(sp1_proof).with_context(|| {
+        format!(
+            "Proof selector 0x{} is not the Groth16 or PLONK verifier of this SP1 version",
+            hex::encode(&sp1_proof[..sp1_proof.len().min(4)])
+        )
+    })?;
+    system.verify(sp1_proof, &public_values, vkey)?;
+    Ok(VerifiedProof {
+        values,
+        system: Some(system),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sr25519_claim_lib::SignatureScheme;
+
+    const VKEY: &str = "0x00aa000000000000000000000000000000000000000000000000000000000000";
+
+    fn values() -> PublicValues {
+        let mut amount = [0u8; 32];
+        amount[31] = 100;
+        PublicValues {
+            pubkey: [0x01; 32],
+            evm_address: [0x12; 20],
+            amount,
+            challenge: [0x42; 32],
+            scheme: SignatureScheme::Ed25519,
+        }
+    }
+
+    fn output(proof: &[u8]) -> ProofOutput {
+        ProofOutput {
+            proof: format!("0x{}", hex::encode(proof)),
+            public_values: format!("0x{}", hex::encode(values().abi_encode())),
+            vkey: VKEY.to_string(),
+            generation_time_secs: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_verify_mock_proof() {
+        let output = output(&[SignatureScheme::Ed25519 as u8]);
+
+        let verified = verify_proof_output(&output, &VKEY.to_uppercase(), true).unwrap();
+        assert_eq!(verified.system, None);
+        assert_eq!(verified.values.evm_address, [0x12; 20]);
+        assert_eq!(verified.values.scheme, SignatureScheme::Ed25519);
+
+        let err = verify_proof_output(&output, VKEY, false).unwrap_err();
+        assert!(err.to_string().contains("mock proof"));
+    }
+
+    #[test]
+    fn test_verify_api_response() {
+        let response = serde_json::json!({
+            "status": "completed",
+            "zkProof": format!("0x0{}", SignatureScheme::Ed25519 as u8),
+            "publicValues": format!("0x{}", hex::encode(values().abi_encode())),
+        });
+        let output: ProofOutput = serde_json::from_value(response).unwrap();
+        assert!(output.vkey.is_empty());
+
+        let verified = verify_proof_output(&output, VKEY, true).unwrap();
+        assert_eq!(verified.values.challenge, [0x42; 32]);
+    }
+
+    #[test]
+    fn test_verify_rejects_mismatched_proofs() {
+        let err = verify_proof_output(&output(&[1]), &format!("0x{}", "bb".repeat(32)), true)
+            .unwrap_err();
+        assert!(err.to_string().contains("embedded program has vkey"));
+
+        let err = verify_proof_output(&output(&[]), VKEY, true).unwrap_err();
+        assert!(err.to_string().contains("empty"));
+
+        let err = verify_proof_output(&output(&[0]), VKEY, true).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("does not match the committed scheme"));
+
+        let err = verify_proof_output(&output(&[7]), VKEY, true).unwrap_err();
+        assert!(err.to_string().contains("Unknown signature scheme"));
+
+        let mut garbage = vec![SignatureScheme::Ed25519 as u8];
+        garbage.extend_from_slice(&[0x5a; 260]);
+        let err = verify_proof_output(&output(&garbage), VKEY, true).unwrap_err();
+        assert!(err.to_string().contains("Proof selector 0x5a5a5a5a"));
+    }
+
+    #[test]
+    fn test_verify_dispatches_on_selector() {
+        assert_ne!(
+            ProofSystem::Groth16.selector(),
+            ProofSystem::Plonk.selector()
+        );
+
+        for system in ProofSystem::ALL {
+            let mut proof = vec![SignatureScheme::Ed25519 as u8];
+            proof.extend_from_slice(&system.selector());
+            proof.extend_from_slice(&[0x5a; 256]);
+            assert_eq!(ProofSystem::of_proof(&proof[1..]), Some(system));
+
+            let err = verify_proof_output(&output(&proof), VKEY, true).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("{} verification failed", system.as_str())));
+        }
+    }
+
+    #[test]
+    fn test_expected_check() {
+        let values = values();
+        assert!(Expected::default().check(&values).is_ok());
+
+        let expected = Expected {
+            recipient: Some([0x12; 20]),
+            amount: Some(values.amount),
+            challenge: Some([0x42; 32]),
+        };
+        assert!(expected.check(&values).is_ok());
+
+        let expected = Expected {
+            recipient: Some([0x34; 20]),
+            amount: Some([0u8; 32]),
+            challenge: None,
+        };
+        let err = expected.check(&values).unwrap_err().to_string();
+        assert!(err.contains("recipient: expected 0x3434"));
+        assert!(err.contains("amount: expected 0 wei, proof commits 100 wei"));
+        assert!(!err.contains("challenge"));
+    }
+}
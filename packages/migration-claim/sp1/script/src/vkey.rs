@@ -1,14 +1,64 @@
 //! Output the verification key for the SR25519 program
 //!
-//! This is used to get the vkey for deploying the SP1ZKVerifier contract.
+//! This is used to get the vkey for deploying the SP1ZKVerifier contract. It also
+//! writes a manifest pinning the vkey to the ELF and SP1 version it came from, and can
+//! check recorded deployments for drift.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use sp1_sdk::blocking::{Prover, ProverClient};
-use sp1_sdk::{include_elf, HashableKey, ProvingKey};
+use sp1_sdk::{include_elf, HashableKey, ProvingKey, SP1_CIRCUIT_VERSION};
 
 const ELF: sp1_sdk::Elf = include_elf!("sr25519-claim-program");
 
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "Output the SR25519 claim program verification key"
+)]
+struct Args {
+    /// Output file for the vkey manifest (JSON format)
+    #[arg(long, default_value = "vkey.json")]
+    output: String,
+
+    /// Deployment or manifest files to check for a different vkey (`programVKey` or `vkey`)
+    #[arg(long)]
+    check: Vec<String>,
+}
+
+/// The vkey of the embedded program and what it was derived from
+///
+/// Has no timestamps, so rebuilding the same program gives the same manifest.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VkeyManifest {
+    /// The verification key (bytes32 hex)
+    vkey: String,
+    /// SHA-256 of the program ELF
+    elf_sha256: String,
+    /// SP1 circuit version the vkey depends on
+    sp1_version: String,
+    /// Version of the program crate (shared across the workspace)
+    program_version: String,
+}
+
+/// The vkey recorded in a deployment (`programVKey`) or manifest (`vkey`) file
+fn recorded_vkey(content: &str) -> Result<String> {
+    let value: serde_json::Value = serde_json::from_str(content).context("Invalid JSON")?;
+    value
+        .get("programVKey")
+        .or_else(|| value.get("vkey"))
+        .and_then(|vkey| vkey.as_str())
+        .map(str::to_string)
+        .context("No programVKey or vkey field")
+}
+
 fn main() -> Result<()> {
+    let args = Args::parse();
+
     println!("SR25519 Claim Program - Verification Key");
     println!("=========================================");
 
@@ -23,5 +73,53 @@ fn main() -> Result<()> {
     println!("\nUse this value as the `sr25519Vkey` constructor parameter");
     println!("when deploying the SP1ZKVerifier contract.");
 
+    let manifest = VkeyManifest {
+        vkey: vkey_hex.clone(),
+        elf_sha256: format!("0x{}", hex::encode(Sha256::digest(&*ELF))),
+        sp1_version: SP1_CIRCUIT_VERSION.trim().to_string(),
+        program_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    std::fs::write(
+        &args.output,
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+    println!("\nManifest written to: {}", args.output);
+
+    let mut drifted = Vec::new();
+    for path in &args.check {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+        let recorded = recorded_vkey(&content).with_context(|| format!("In {path}"))?;
+        if recorded.eq_ignore_ascii_case(&vkey_hex) {
+            println!("{path}: matches");
+        } else {
+            println!("{path}: records {recorded}");
+            drifted.push(path.as_str());
+        }
+    }
+    if !drifted.is_empty() {
+        bail!(
+            "Vkey drift: {} do not match the embedded program's vkey {vkey_hex}",
+            drifted.join(", ")
+        );
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_vkey() {
+        let deployment = r#"{ "zkVerifier": "0x46ce", "programVKey": "0x000d" }"#;
+        assert_eq!(recorded_vkey(deployment).unwrap(), "0x000d");
+
+        let manifest = r#"{ "vkey": "0x00aa", "elfSha256": "0x1234" }"#;
+        assert_eq!(recorded_vkey(manifest).unwrap(), "0x00aa");
+
+        assert!(recorded_vkey(r#"{ "merkleRoot": "0x54ec" }"#).is_err());
+        assert!(recorded_vkey("not json").is_err());
+    }
+}
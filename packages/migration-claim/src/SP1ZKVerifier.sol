@@ -21,26 +21,38 @@ contract SP1ZKVerifier is IZKVerifier {
     /// Base Sepolia & Mainnet: 0x397A5f7f3dBd538f23DE225B51f532c34448dA9B
     ISP1Verifier public immutable sp1Verifier;
 
-    /// @notice Verification key for the SR25519 claim program
+    /// @notice Verification key for the Substrate claim program
     bytes32 public immutable programVKey;
 
+    /// @notice Highest signature scheme byte the claim program commits (2 = ecdsa)
+    uint8 public constant MAX_SCHEME = 2;
+
     constructor(address _sp1Verifier, bytes32 _programVKey) {
         sp1Verifier = ISP1Verifier(_sp1Verifier);
         programVKey = _programVKey;
     }
 
     /// @notice Verifies a ZK proof of Substrate key ownership
-    /// @param proof The SP1 Groth16 proof bytes
+    /// @param proof The signature scheme byte (0 = sr25519, 1 = ed25519, 2 = ecdsa)
+    ///        followed by the SP1 Groth16 proof bytes
     /// @param publicInputs The ABI-encoded public inputs:
-    ///        - pubkey (bytes32): The SR25519 public key
+    ///        - pubkey (bytes32): The Substrate account id
     ///        - evmAddress (address): The recipient EVM address
     ///        - amount (uint256): The claim amount
     ///        - challenge (bytes32): keccak256(abi.encode(migrationContract, chainId, evmAddress, amount))
-    /// @return valid True if the proof is valid
+    /// @return valid True if the proof is valid; false for an empty proof or an unknown scheme
     function verifyProof(
         bytes calldata proof,
         bytes calldata publicInputs
     ) external view override returns (bool valid) {
+        if (proof.length == 0) {
+            return false;
+        }
+        uint8 scheme = uint8(proof[0]);
+        if (scheme > MAX_SCHEME) {
+            return false;
+        }
+
         // Decode the public inputs
         (
             bytes32 pubkey,
@@ -50,17 +62,18 @@ contract SP1ZKVerifier is IZKVerifier {
         ) = abi.decode(publicInputs, (bytes32, address, uint256, bytes32));
 
         // Reconstruct the public values for SP1
-        // The SP1 program commits: (pubkey, evmAddress, amount, challenge)
+        // The SP1 program commits: (pubkey, evmAddress, amount, challenge, scheme)
         bytes memory sp1PublicValues = abi.encode(
             pubkey,
             evmAddress,
             amount,
-            challenge
+            challenge,
+            scheme
         );
 
         // Verify with SP1
         // The verifyProof function reverts on failure
-        try sp1Verifier.verifyProof(programVKey, sp1PublicValues, proof) {
+        try sp1Verifier.verifyProof(programVKey, sp1PublicValues, proof[1:]) {
             return true;
         } catch {
             return false;
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+import {Test} from "forge-std/Test.sol";
+import {SP1ZKVerifier, ISP1Verifier} from "../src/SP1ZKVerifier.sol";
+
+/// @notice SP1 gateway stand-in that only accepts one expected call
+contract MockSP1Verifier is ISP1Verifier {
+    bytes32 public expectedVKey;
+    bytes public expectedPublicValues;
+    bytes public expectedProofBytes;
+
+    function expect(
+        bytes32 programVKey,
+        bytes calldata publicValues,
+        bytes calldata proofBytes
+    ) external {
+        expectedVKey = programVKey;
+        expectedPublicValues = publicValues;
+        expectedProofBytes = proofBytes;
+    }
+
+    function verifyProof(
+        bytes32 programVKey,
+        bytes calldata publicValues,
+        bytes calldata proofBytes
+    ) external view override {
+        require(programVKey == expectedVKey, "vkey");
+        require(keccak256(publicValues) == keccak256(expectedPublicValues), "public values");
+        require(keccak256(proofBytes) == keccak256(expectedProofBytes), "proof bytes");
+    }
+}
+
+contract SP1ZKVerifierTest is Test {
+    SP1ZKVerifier public verifier;
+    MockSP1Verifier public gateway;
+
+    bytes32 constant PROGRAM_VKEY = bytes32(uint256(0x1234));
+    bytes32 constant PUBKEY = bytes32(0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d);
+    address constant EVM_ADDRESS = address(0xBEEF);
+    uint256 constant AMOUNT = 1000 ether;
+    bytes32 constant CHALLENGE = bytes32(uint256(0x42));
+
+    bytes constant GROTH16_PROOF = hex"a4594c59bbc142f3b81c3ecb7f50a7c34bc9af7c4c444b5d48b795427e285913";
+
+    function setUp() public {
+        gateway = new MockSP1Verifier();
+        verifier = new SP1ZKVerifier(address(gateway), PROGRAM_VKEY);
+    }
+
+    function _publicInputs() internal pure returns (bytes memory) {
+        return abi.encode(PUBKEY, EVM_ADDRESS, AMOUNT, CHALLENGE);
+    }
+
+    function test_VerifyProof_RebuildsPublicValuesWithScheme() public {
+        for (uint8 scheme = 0; scheme <= verifier.MAX_SCHEME(); scheme++) {
+            bytes memory publicValues = abi.encode(PUBKEY, EVM_ADDRESS, AMOUNT, CHALLENGE, scheme);
+            assertEq(publicValues.length, 160);
+            gateway.expect(PROGRAM_VKEY, publicValues, GROTH16_PROOF);
+
+            bytes memory proof = bytes.concat(bytes1(scheme), GROTH16_PROOF);
+            assertTrue(verifier.verifyProof(proof, _publicInputs()));
+        }
+    }
+
+    function test_VerifyProof_StripsSchemeByte() public {
+        bytes memory publicValues = abi.encode(PUBKEY, EVM_ADDRESS, AMOUNT, CHALLENGE, uint8(0));
+        gateway.expect(PROGRAM_VKEY, publicValues, GROTH16_PROOF);
+
+        // Only the scheme byte is stripped before the proof reaches the gateway
+        assertTrue(verifier.verifyProof(bytes.concat(bytes1(0), GROTH16_PROOF), _publicInputs()));
+        assertFalse(verifier.verifyProof(bytes.concat(bytes2(0), GROTH16_PROOF), _publicInputs()));
+    }
+
+    function test_VerifyProof_SchemeMismatch() public {
+        bytes memory publicValues = abi.encode(PUBKEY, EVM_ADDRESS, AMOUNT, CHALLENGE, uint8(0));
+        gateway.expect(PROGRAM_VKEY, publicValues, GROTH16_PROOF);
+
+        // An sr25519 proof relabelled as ed25519 commits different public values
+        assertFalse(verifier.verifyProof(bytes.concat(bytes1(uint8(1)), GROTH16_PROOF), _publicInputs()));
+    }
+
+    function test_VerifyProof_RejectsEmptyProof() public view {
+        assertFalse(verifier.verifyProof("", _publicInputs()));
+    }
+
+    function test_VerifyProof_RejectsUnknownScheme() public {
+        bytes memory publicValues = abi.encode(PUBKEY, EVM_ADDRESS, AMOUNT, CHALLENGE, uint8(3));
+        gateway.expect(PROGRAM_VKEY, publicValues, GROTH16_PROOF);
+
+        assertFalse(verifier.verifyProof(bytes.concat(bytes1(uint8(3)), GROTH16_PROOF), _publicInputs()));
+        assertFalse(verifier.verifyProof(bytes.concat(bytes1(uint8(0xff)), GROTH16_PROOF), _publicInputs()));
+    }
+
+    function test_VerifyProof_GatewayRejects() public {
+        gateway.expect(PROGRAM_VKEY, "", "");
+        assertFalse(verifier.verifyProof(bytes.concat(bytes1(0), GROTH16_PROOF), _publicInputs()));
+    }
+}
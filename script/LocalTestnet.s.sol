@@ -117,6 +117,9 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
     bool public operatorQaRegistrationMode; // If true, create registration-specific QA blueprints
     uint64 public operatorQaEmptySchemaBlueprintId;
     uint64 public operatorQaRequiredSchemaBlueprintId;
+    bool public bareDeployMode; // If true, only deploy contracts (no operators, blueprints or services)
+    bool public slashingMode; // If true, propose a slash against operator1 on the primary service
+    uint64 public slashId;
 
     function run() external {
         _executeSetup(true);
@@ -147,6 +150,14 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
             rewardsQaMode = true;
             console2.log("Rewards QA mode enabled: will seed Tangle Payments rewards for frontend testing");
         }
+        if (_envBoolOrFalse("BARE_DEPLOY")) {
+            bareDeployMode = true;
+            console2.log("Bare deploy mode enabled: skipping operators, blueprints and services");
+        }
+        if (_envBoolOrFalse("SLASHING_SCENARIO")) {
+            slashingMode = true;
+            console2.log("Slashing scenario enabled: will propose a slash against operator1");
+        }
         if (_envBoolOrFalse("OPERATOR_QA_REGISTRATION")) {
             operatorQaRegistrationMode = true;
             console2.log("Operator QA registration mode enabled: creating extra registration fixture blueprints");
@@ -183,6 +194,13 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
         _deployMockTokens();
         _configureRewardVaults();
         _deployPodManager();
+        if (bareDeployMode) {
+            console2.log("\n=== Local Testnet Ready (bare deploy) ===");
+            console2.log("Tangle:", tangleProxy);
+            console2.log("MultiAssetDelegation:", stakingProxy);
+            console2.log("OperatorStatusRegistry:", statusRegistry);
+            return 0;
+        }
         _registerOperatorsStaking();
         _setupDelegationModes();
         _deployLiquidDelegation();
@@ -200,6 +218,9 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
         if (rewardsQaMode) {
             _setupRewardsQA();
         }
+        if (slashingMode) {
+            _proposeScenarioSlash();
+        }
 
         console2.log("\n=== Local Testnet Ready ===");
         console2.log("Tangle:", tangleProxy);
@@ -261,6 +282,10 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
             console2.log("Payment split: 20% dev, 5% protocol, 65% operator, 10% staker");
             console2.log("Shell script will execute 3 claims from Op3 for claim history after indexer sync");
         }
+        if (slashingMode) {
+            console2.log("\n=== Slashing Scenario ===");
+            console2.log("Pending slash against Operator1 on service", serviceId, "- slash ID:", slashId);
+        }
         console2.log("\nService is active and ready for jobs");
 
         return serviceId;
@@ -1307,6 +1332,24 @@ contract LocalTestnetSetup is Script, BlueprintDefinitionHelper {
         }
     }
 
+    function _proposeScenarioSlash() internal {
+        console2.log("\n=== Proposing Slash ===");
+        if (useBroadcastKeys) {
+            vm.startBroadcast(DEPLOYER_KEY);
+        } else {
+            vm.startPrank(deployer);
+        }
+        // The deployer owns the primary service, so it may propose slashes on it.
+        slashId = ITangleFull(payable(tangleProxy))
+            .proposeSlash(serviceId, operator1, 1000, keccak256("localtestnet-slashing-scenario"));
+        console2.log("Proposed 10% slash against Operator1, slash ID:", slashId);
+        if (useBroadcastKeys) {
+            vm.stopBroadcast();
+        } else {
+            vm.stopPrank();
+        }
+    }
+
     function _envBoolOrFalse(string memory key) internal view returns (bool) {
         try vm.envString(key) returns (string memory raw) {
             return keccak256(bytes(raw)) == keccak256(bytes("true"));
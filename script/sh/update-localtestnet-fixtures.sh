@@ -3,8 +3,24 @@ set -euo pipefail
 
 ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/../.." && pwd)"
 FIXTURES_DIR="${FIXTURES_DIR:-$ROOT_DIR/fixtures/fixtures}"
-STATE_PATH="${ANVIL_STATE_PATH:-$FIXTURES_DIR/localtestnet-state.json}"
-BROADCAST_PATH="${BROADCAST_PATH:-$FIXTURES_DIR/localtestnet-broadcast.json}"
+# Named snapshot to generate; each one is written to <name>-{state,broadcast}.json.zst
+# and <name>-metadata.json
+#   localtestnet           deployment with operators, a blueprint and an active service
+#   localtestnet-bare      contracts only (BARE_DEPLOY)
+#   localtestnet-slashing  seeded services plus a pending slash (SLASHING_SCENARIO)
+SNAPSHOT="${SNAPSHOT:-localtestnet}"
+case "$SNAPSHOT" in
+  localtestnet) ;;
+  localtestnet-bare) export BARE_DEPLOY=true ;;
+  localtestnet-slashing) export SLASHING_SCENARIO=true ;;
+  *)
+    echo "error: unknown snapshot '$SNAPSHOT' (localtestnet, localtestnet-bare, localtestnet-slashing)" >&2
+    exit 1
+    ;;
+esac
+STATE_PATH="${ANVIL_STATE_PATH:-$FIXTURES_DIR/$SNAPSHOT-state.json.zst}"
+BROADCAST_PATH="${BROADCAST_PATH:-$FIXTURES_DIR/$SNAPSHOT-broadcast.json.zst}"
+METADATA_PATH="${METADATA_PATH:-$FIXTURES_DIR/$SNAPSHOT-metadata.json}"
 ANVIL_PORT="${ANVIL_PORT:-9545}"
 ANVIL_URL="http://127.0.0.1:${ANVIL_PORT}"
 KEEP_LOGS="${KEEP_FIXTURE_LOGS:-0}"
@@ -17,6 +33,12 @@ KEEP_LOGS="${KEEP_FIXTURE_LOGS:-0}"
 # to be comfortably above the script's own size.
 export FOUNDRY_CODE_SIZE_LIMIT="${FOUNDRY_CODE_SIZE_LIMIT:-1000000}"
 
+# The fixtures crate embeds the state and broadcast zstd-compressed.
+if ! command -v zstd >/dev/null 2>&1; then
+  echo "error: zstd is required to compress the fixtures" >&2
+  exit 1
+fi
+
 mkdir -p "$FIXTURES_DIR"
 TMP_STATE="$(mktemp -t anvil-state.XXXXXX.json)"
 ANVIL_LOG="$(mktemp -t anvil-log.XXXXXX.txt)"
@@ -72,19 +94,51 @@ if ! forge script script/LocalTestnet.s.sol:LocalTestnetSetup \
   exit 1
 fi
 
+CHAIN_ID="$(cast chain-id --rpc-url "$ANVIL_URL")"
+BLOCK_NUMBER="$(cast block-number --rpc-url "$ANVIL_URL")"
+
 echo "Stopping Anvil and writing snapshot..."
 kill "$ANVIL_PID" >/dev/null 2>&1 || true
 wait "$ANVIL_PID" >/dev/null 2>&1 || true
 
-mv "$TMP_STATE" "$STATE_PATH"
+zstd -q -19 -f --rm "$TMP_STATE" -o "$STATE_PATH"
 echo "Snapshot written to $STATE_PATH"
 
 SOURCE_BROADCAST="${ROOT_DIR}/broadcast/LocalTestnet.s.sol/31337/run-latest.json"
 if [[ -f "$SOURCE_BROADCAST" ]]; then
-  cp "$SOURCE_BROADCAST" "$BROADCAST_PATH"
+  zstd -q -19 -f "$SOURCE_BROADCAST" -o "$BROADCAST_PATH"
   echo "Broadcast written to $BROADCAST_PATH"
 else
   echo "warning: broadcast not found at $SOURCE_BROADCAST" >&2
 fi
+
+# Metadata lets consumers check the snapshot against the bindings they compile with.
+# Contracts are keyed like `DeployedContracts`: proxies under their implementation's
+# name, mock tokens by symbol.
+jq --arg name "$SNAPSHOT" \
+  --arg gitRev "$(git -C "$ROOT_DIR" rev-parse HEAD)" \
+  --arg bindingsVersion "$(tr -d '[:space:]' <"$ROOT_DIR/bindings/TNT_CORE_VERSION")" \
+  --argjson chainId "$CHAIN_ID" \
+  --argjson blockNumber "$BLOCK_NUMBER" '
+  [.transactions[] | select(.transactionType == "CREATE" and .contractName != null)] as $creates
+  | (reduce ($creates[] | select(.contractName != "ERC1967Proxy" and .contractName != "MockToken")) as $tx
+      ({}; .[$tx.contractAddress | ascii_downcase] = $tx.contractName)) as $names
+  | {
+      name: $name,
+      gitRev: $gitRev,
+      bindingsVersion: $bindingsVersion,
+      chainId: $chainId,
+      blockNumber: $blockNumber,
+      contracts: (reduce $creates[] as $tx ({};
+        if $tx.contractName == "ERC1967Proxy" then
+          ($names[$tx.arguments[0] | ascii_downcase]) as $impl
+          | if $impl then .[$impl] = $tx.contractAddress else . end
+        elif $tx.contractName == "MockToken" then .
+        else .[$tx.contractName] = $tx.contractAddress
+        end)),
+      tokens: (reduce ($creates[] | select(.contractName == "MockToken")) as $tx ({};
+        .[$tx.arguments[1]] = $tx.contractAddress))
+    }' "$SOURCE_BROADCAST" >"$METADATA_PATH"
+echo "Metadata written to $METADATA_PATH"
 echo "Forge log: $FORGE_LOG"
 echo "Anvil log: $ANVIL_LOG"
@@ -0,0 +1,705 @@
+//! `cargo xtask abi-diff`: compares `bindings/abi/*.json` against an older revision and
+//! prints the changes as a CHANGELOG section.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{parse_binding_version, read_binding_version, workspace_root};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Function,
+    Event,
+    Error,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Function => "function",
+            Kind::Event => "event",
+            Kind::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Param {
+    name: String,
+    /// Canonical ABI type, with tuples expanded (`(uint64,address)[]`).
+    ty: String,
+    /// Type as written in Solidity, with structs by name (`Types.Asset[]`).
+    display: String,
+    indexed: bool,
+}
+
+/// A function, event or error of one contract.
+#[derive(Debug, Clone)]
+struct Item {
+    kind: Kind,
+    name: String,
+    inputs: Vec<Param>,
+    outputs: Vec<Param>,
+    state_mutability: Option<String>,
+}
+
+impl Item {
+    fn signature(&self) -> String {
+        let types: Vec<_> = self.inputs.iter().map(|p| p.ty.as_str()).collect();
+        format!("{}({})", self.name, types.join(","))
+    }
+
+    /// The signature with structs by name, which is easier to read than nested tuples.
+    fn display_signature(&self) -> String {
+        let types: Vec<_> = self.inputs.iter().map(|p| p.display.as_str()).collect();
+        format!("{}({})", self.name, types.join(","))
+    }
+
+    /// Function and error selectors; events are identified by their signature.
+    fn selector(&self) -> Option<String> {
+        match self.kind {
+            Kind::Function | Kind::Error => Some(format!(
+                "0x{}",
+                hex(&keccak256(self.signature().as_bytes())[..4])
+            )),
+            Kind::Event => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.selector() {
+            Some(selector) => format!(
+                "{} `{}` (`{selector}`)",
+                self.kind.label(),
+                self.display_signature()
+            ),
+            None => format!("{} `{}`", self.kind.label(), self.display_signature()),
+        }
+    }
+}
+
+/// The functions, events, errors and structs of one ABI file.
+#[derive(Debug, Default)]
+struct ContractAbi {
+    /// Keyed by kind and signature, so overloads are separate entries.
+    items: BTreeMap<(Kind, String), Item>,
+    /// Structs by Solidity name (`Types.Service`), as their fields.
+    structs: BTreeMap<String, Vec<Param>>,
+}
+
+impl ContractAbi {
+    fn parse(json: &str) -> Result<Self> {
+        let artifact: Value = serde_json::from_str(json)?;
+        // Forge artifacts wrap the ABI; plain ABI arrays are accepted too.
+        let entries = artifact
+            .get("abi")
+            .unwrap_or(&artifact)
+            .as_array()
+            .ok_or_else(|| anyhow!("expected an ABI array"))?;
+
+        let mut abi = ContractAbi::default();
+        for entry in entries {
+            let kind = match entry["type"].as_str() {
+                Some("function") => Kind::Function,
+                Some("event") => Kind::Event,
+                Some("error") => Kind::Error,
+                _ => continue,
+            };
+            let inputs = abi.params(&entry["inputs"])?;
+            let outputs = abi.params(&entry["outputs"])?;
+            let item = Item {
+                kind,
+                name: entry["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("ABI {} without a name", kind.label()))?
+                    .to_string(),
+                inputs,
+                outputs,
+                state_mutability: entry["stateMutability"].as_str().map(str::to_string),
+            };
+            abi.items.insert((kind, item.signature()), item);
+        }
+        Ok(abi)
+    }
+
+    /// Parses a parameter list, recording the structs it uses.
+    fn params(&mut self, params: &Value) -> Result<Vec<Param>> {
+        let Some(params) = params.as_array() else {
+            return Ok(Vec::new());
+        };
+        params.iter().map(|param| self.param(param)).collect()
+    }
+
+    fn param(&mut self, param: &Value) -> Result<Param> {
+        let ty = param["type"]
+            .as_str()
+            .ok_or_else(|| anyhow!("ABI parameter without a type"))?;
+        let ty = match ty.strip_prefix("tuple") {
+            Some(array_suffix) => {
+                let fields = self.params(&param["components"])?;
+                if let Some(name) = param["internalType"]
+                    .as_str()
+                    .and_then(|internal| internal.strip_prefix("struct "))
+                {
+                    let name = name.split('[').next().unwrap_or(name);
+                    self.structs.insert(name.to_string(), fields.clone());
+                }
+                let types: Vec<_> = fields.iter().map(|f| f.ty.as_str()).collect();
+                format!("({}){array_suffix}", types.join(","))
+            }
+            None => ty.to_string(),
+        };
+        let display = match param["internalType"].as_str() {
+            Some(internal) if internal.starts_with("struct ") => {
+                internal.trim_start_matches("struct ").to_string()
+            }
+            _ => ty.clone(),
+        };
+        Ok(Param {
+            name: param["name"].as_str().unwrap_or_default().to_string(),
+            ty,
+            display,
+            indexed: param["indexed"].as_bool().unwrap_or(false),
+        })
+    }
+}
+
+/// Where the old ABIs come from.
+enum Source {
+    Dir(PathBuf),
+    Rev(String),
+}
+
+impl Source {
+    fn new(arg: &str) -> Self {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            // Accept a checkout of the repo as well as an ABI directory.
+            let abi_dir = path.join("bindings/abi");
+            Source::Dir(if abi_dir.is_dir() {
+                abi_dir
+            } else {
+                path.to_path_buf()
+            })
+        } else {
+            Source::Rev(arg.to_string())
+        }
+    }
+
+    fn load(&self, repo_root: &Path) -> Result<BTreeMap<String, ContractAbi>> {
+        match self {
+            Source::Dir(dir) => load_dir(dir),
+            Source::Rev(rev) => {
+                let listing = git(repo_root, &["ls-tree", "--name-only", rev, "bindings/abi/"])?;
+                let mut contracts = BTreeMap::new();
+                for path in listing.lines().filter(|path| path.ends_with(".json")) {
+                    let json = git(repo_root, &["show", &format!("{rev}:{path}")])?;
+                    let abi = ContractAbi::parse(&json).with_context(|| format!("{rev}:{path}"))?;
+                    contracts.insert(contract_name(Path::new(path)), abi);
+                }
+                if contracts.is_empty() {
+                    return Err(anyhow!("no ABI files under bindings/abi at {rev}"));
+                }
+                Ok(contracts)
+            }
+        }
+    }
+
+    /// The bindings version the old ABIs were released as, if known.
+    fn bindings_version(&self, repo_root: &Path) -> Option<String> {
+        match self {
+            Source::Dir(dir) => read_binding_version(dir.parent()?).ok(),
+            Source::Rev(rev) => {
+                let manifest =
+                    git(repo_root, &["show", &format!("{rev}:bindings/Cargo.toml")]).ok()?;
+                parse_binding_version(&manifest).ok()
+            }
+        }
+    }
+}
+
+fn load_dir(dir: &Path) -> Result<BTreeMap<String, ContractAbi>> {
+    let mut contracts = BTreeMap::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let abi = ContractAbi::parse(&json).with_context(|| path.display().to_string())?;
+            contracts.insert(contract_name(&path), abi);
+        }
+    }
+    if contracts.is_empty() {
+        return Err(anyhow!("no ABI files in {}", dir.display()));
+    }
+    Ok(contracts)
+}
+
+fn contract_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn git(repo_root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(repo_root)
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Breaking and additive changes, as CHANGELOG bullets.
+#[derive(Debug, Default)]
+struct Report {
+    breaking: Vec<String>,
+    additive: Vec<String>,
+}
+
+impl Report {
+    fn compare(old: &BTreeMap<String, ContractAbi>, new: &BTreeMap<String, ContractAbi>) -> Self {
+        let mut report = Report::default();
+        for (name, old_abi) in old {
+            match new.get(name) {
+                Some(new_abi) => report.compare_contract(name, old_abi, new_abi),
+                None => report
+                    .breaking
+                    .push(format!("`{name}`: removed from the bindings")),
+            }
+        }
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            report
+                .additive
+                .push(format!("`{name}`: added to the bindings"));
+        }
+
+        // Every binding module has its own copy of the structs it uses, so layouts are
+        // compared per contract and reported once.
+        for (name, old_abi) in old {
+            let Some(new_abi) = new.get(name) else {
+                continue;
+            };
+            for (name, old_fields) in &old_abi.structs {
+                match new_abi.structs.get(name) {
+                    Some(new_fields) if new_fields != old_fields => report.breaking.push(format!(
+                        "struct `{name}`: layout changed from `{}` to `{}`",
+                        fields(old_fields),
+                        fields(new_fields)
+                    )),
+                    _ => {}
+                }
+            }
+        }
+        let old_structs = struct_names(old);
+        let new_structs = struct_names(new);
+        for name in old_structs.difference(&new_structs) {
+            report.breaking.push(format!("struct `{name}`: removed"));
+        }
+        for name in new_structs.difference(&old_structs) {
+            report.additive.push(format!("struct `{name}`: added"));
+        }
+
+        report.breaking.sort();
+        report.breaking.dedup();
+        report.additive.sort();
+        report.additive.dedup();
+        report
+    }
+
+    fn compare_contract(&mut self, contract: &str, old: &ContractAbi, new: &ContractAbi) {
+        let mut removed: BTreeMap<(Kind, &str), Vec<&Item>> = BTreeMap::new();
+        let mut added: BTreeMap<(Kind, &str), Vec<&Item>> = BTreeMap::new();
+        for (key, old_item) in &old.items {
+            match new.items.get(key) {
+                Some(new_item) => self.compare_item(contract, old_item, new_item),
+                None => removed
+                    .entry((old_item.kind, old_item.name.as_str()))
+                    .or_default()
+                    .push(old_item),
+            }
+        }
+        for (key, new_item) in &new.items {
+            if !old.items.contains_key(key) {
+                added
+                    .entry((new_item.kind, new_item.name.as_str()))
+                    .or_default()
+                    .push(new_item);
+            }
+        }
+
+        // A single overload that was removed and re-added is reported as a changed selector.
+        for (key, old_items) in removed {
+            match added.get(&key).map(Vec::as_slice) {
+                Some(&[new_item]) if old_items.len() == 1 => {
+                    added.remove(&key);
+                    self.breaking.push(format!(
+                        "`{contract}`: changed {} to `{}`{}",
+                        old_items[0].describe(),
+                        new_item.display_signature(),
+                        new_item
+                            .selector()
+                            .map(|selector| format!(" (`{selector}`)"))
+                            .unwrap_or_default()
+                    ));
+                }
+                _ => {
+                    for item in old_items {
+                        self.breaking
+                            .push(format!("`{contract}`: removed {}", item.describe()));
+                    }
+                }
+            }
+        }
+        for item in added.into_values().flatten() {
+            self.additive
+                .push(format!("`{contract}`: added {}", item.describe()));
+        }
+    }
+
+    /// Compares an item whose signature did not change.
+    fn compare_item(&mut self, contract: &str, old: &Item, new: &Item) {
+        let item = old.describe();
+        let types = |params: &[Param]| params.iter().map(|p| p.ty.clone()).collect::<Vec<_>>();
+        let names = |params: &[Param]| params.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        let displayed = |params: &[Param]| {
+            params
+                .iter()
+                .map(|p| p.display.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        if types(&old.outputs) != types(&new.outputs) {
+            self.breaking.push(format!(
+                "`{contract}`: {item} now returns `({})` instead of `({})`",
+                displayed(&new.outputs),
+                displayed(&old.outputs)
+            ));
+        }
+        if old.state_mutability != new.state_mutability {
+            let change = format!(
+                "`{contract}`: {item} is now `{}` (was `{}`)",
+                new.state_mutability.as_deref().unwrap_or("unknown"),
+                old.state_mutability.as_deref().unwrap_or("unknown")
+            );
+            // Accepting value is the only mutability change existing callers cannot notice.
+            if old.state_mutability.as_deref() == Some("nonpayable")
+                && new.state_mutability.as_deref() == Some("payable")
+            {
+                self.additive.push(change);
+            } else {
+                self.breaking.push(change);
+            }
+        }
+        let indexed = |params: &[Param]| params.iter().map(|p| p.indexed).collect::<Vec<_>>();
+        if indexed(&old.inputs) != indexed(&new.inputs) {
+            self.breaking.push(format!(
+                "`{contract}`: {item} changed which fields are indexed"
+            ));
+        }
+        // The bindings expose parameter names as struct fields.
+        if names(&old.inputs) != names(&new.inputs) || names(&old.outputs) != names(&new.outputs) {
+            self.breaking.push(format!(
+                "`{contract}`: {item} renamed parameters from `{}` to `{}`",
+                param_names(old),
+                param_names(new)
+            ));
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.breaking.is_empty() && self.additive.is_empty()
+    }
+}
+
+fn struct_names(contracts: &BTreeMap<String, ContractAbi>) -> BTreeSet<&str> {
+    contracts
+        .values()
+        .flat_map(|abi| abi.structs.keys().map(String::as_str))
+        .collect()
+}
+
+fn fields(fields: &[Param]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|f| format!("{} {}", f.ty, f.name))
+        .collect();
+    format!("({})", fields.join(", "))
+}
+
+fn param_names(item: &Item) -> String {
+    let names = |params: &[Param]| {
+        params
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if item.outputs.is_empty() {
+        format!("({})", names(&item.inputs))
+    } else {
+        format!("({}) -> ({})", names(&item.inputs), names(&item.outputs))
+    }
+}
+
+/// Bump level implied by the changes, following Cargo's semver rules for `0.x` versions.
+fn suggest_version(version: &str, report: &Report) -> Result<(&'static str, String)> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| anyhow!("invalid bindings version `{version}`"))?;
+    let [major, minor, patch] = parts[..] else {
+        return Err(anyhow!("invalid bindings version `{version}`"));
+    };
+    Ok(match (report.breaking.is_empty(), major) {
+        (false, 0) => ("minor", format!("0.{}.0", minor + 1)),
+        (false, _) => ("major", format!("{}.0.0", major + 1)),
+        (true, 0) => ("patch", format!("0.{minor}.{}", patch + 1)),
+        (true, _) => ("minor", format!("{major}.{}.0", minor + 1)),
+    })
+}
+
+pub fn abi_diff(old: &str) -> Result<()> {
+    let repo_root = workspace_root()?;
+    let bindings_crate = repo_root.join("bindings");
+
+    let source = Source::new(old);
+    let old_abis = source.load(&repo_root)?;
+    let new_abis = load_dir(&bindings_crate.join("abi"))?;
+    let report = Report::compare(&old_abis, &new_abis);
+
+    let mut out = String::new();
+    if report.is_empty() {
+        writeln!(out, "No ABI changes since `{old}`.")?;
+        print!("{out}");
+        return Ok(());
+    }
+
+    let base_version = match source.bindings_version(&repo_root) {
+        Some(version) => version,
+        None => read_binding_version(&bindings_crate)?,
+    };
+    let (bump, next_version) = suggest_version(&base_version, &report)?;
+    writeln!(out, "## [{next_version}] - ABI changes since `{old}`")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Suggested bump: **{bump}** ({base_version} → {next_version}), {} breaking and {} additive changes.",
+        report.breaking.len(),
+        report.additive.len()
+    )?;
+    for (heading, changes) in [
+        ("ABI — breaking", &report.breaking),
+        ("ABI — additive", &report.additive),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(out, "### {heading}")?;
+        writeln!(out)?;
+        for change in changes {
+            writeln!(out, "- {change}")?;
+        }
+    }
+    print!("{out}");
+    Ok(())
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn param(ty: &str, name: &str) -> Value {
+        json!({ "type": ty, "name": name, "internalType": ty })
+    }
+
+    fn function(name: &str, inputs: Vec<Value>, mutability: &str) -> Value {
+        json!({
+            "type": "function",
+            "name": name,
+            "inputs": inputs,
+            "outputs": [],
+            "stateMutability": mutability,
+        })
+    }
+
+    fn asset(components: Vec<Value>) -> Value {
+        json!({
+            "type": "tuple",
+            "name": "asset",
+            "internalType": "struct Types.Asset",
+            "components": components,
+        })
+    }
+
+    fn contracts(entries: Vec<Value>) -> BTreeMap<String, ContractAbi> {
+        let abi = ContractAbi::parse(&Value::Array(entries).to_string()).unwrap();
+        BTreeMap::from([("Tangle".to_string(), abi)])
+    }
+
+    fn compare(old: Vec<Value>, new: Vec<Value>) -> Report {
+        Report::compare(&contracts(old), &contracts(new))
+    }
+
+    #[test]
+    fn test_selector() {
+        let abi = contracts(vec![function(
+            "transfer",
+            vec![param("address", "to"), param("uint256", "amount")],
+            "nonpayable",
+        )]);
+        let item = abi["Tangle"].items.values().next().unwrap();
+        assert_eq!(item.selector().as_deref(), Some("0xa9059cbb"));
+    }
+
+    #[test]
+    fn test_removed_overload() {
+        let report = compare(
+            vec![
+                function("slash", vec![param("uint64", "serviceId")], "nonpayable"),
+                function("slash", vec![param("address", "operator")], "nonpayable"),
+            ],
+            vec![function(
+                "slash",
+                vec![param("uint64", "serviceId")],
+                "nonpayable",
+            )],
+        );
+        assert_eq!(report.breaking.len(), 1);
+        assert!(report.breaking[0].starts_with("`Tangle`: removed function `slash(address)`"));
+        assert!(report.additive.is_empty());
+    }
+
+    #[test]
+    fn test_changed_selector() {
+        let report = compare(
+            vec![function(
+                "slash",
+                vec![param("uint64", "serviceId")],
+                "nonpayable",
+            )],
+            vec![function(
+                "slash",
+                vec![param("uint256", "serviceId")],
+                "nonpayable",
+            )],
+        );
+        assert_eq!(report.breaking.len(), 1);
+        assert!(report.breaking[0].starts_with("`Tangle`: changed function `slash(uint64)`"));
+        assert!(report.breaking[0].contains("to `slash(uint256)`"));
+        assert!(report.additive.is_empty());
+    }
+
+    #[test]
+    fn test_struct_layout_change() {
+        let old_asset = asset(vec![param("uint8", "kind"), param("address", "token")]);
+        let new_asset = asset(vec![
+            param("uint8", "kind"),
+            param("address", "token"),
+            param("uint256", "amount"),
+        ]);
+        let report = compare(
+            vec![function("deposit", vec![old_asset], "nonpayable")],
+            vec![function("deposit", vec![new_asset], "nonpayable")],
+        );
+        assert!(report.breaking.contains(
+            &"struct `Types.Asset`: layout changed from `(uint8 kind, address token)` to \
+              `(uint8 kind, address token, uint256 amount)`"
+                .to_string()
+        ));
+        assert!(report
+            .breaking
+            .iter()
+            .any(|change| change.starts_with("`Tangle`: changed function `deposit(Types.Asset)`")));
+    }
+
+    #[test]
+    fn test_payable_is_additive() {
+        let report = compare(
+            vec![function("deposit", vec![], "nonpayable")],
+            vec![function("deposit", vec![], "payable")],
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(report.additive.len(), 1);
+        assert!(report.additive[0].ends_with("is now `payable` (was `nonpayable`)"));
+
+        let report = compare(
+            vec![function("deposit", vec![], "payable")],
+            vec![function("deposit", vec![], "nonpayable")],
+        );
+        assert_eq!(report.breaking.len(), 1);
+    }
+
+    #[test]
+    fn test_parameter_rename() {
+        let report = compare(
+            vec![function(
+                "stake",
+                vec![param("uint256", "amount")],
+                "nonpayable",
+            )],
+            vec![function(
+                "stake",
+                vec![param("uint256", "value")],
+                "nonpayable",
+            )],
+        );
+        assert_eq!(report.breaking.len(), 1);
+        assert!(report.breaking[0].ends_with("renamed parameters from `(amount)` to `(value)`"));
+    }
+
+    #[test]
+    fn test_suggest_version() {
+        let breaking = Report {
+            breaking: vec!["removed".to_string()],
+            additive: Vec::new(),
+        };
+        let additive = Report {
+            breaking: Vec::new(),
+            additive: vec!["added".to_string()],
+        };
+
+        let suggest = |version, report| suggest_version(version, report).unwrap();
+        assert_eq!(
+            suggest("0.19.1", &breaking),
+            ("minor", "0.20.0".to_string())
+        );
+        assert_eq!(
+            suggest("0.19.1", &additive),
+            ("patch", "0.19.2".to_string())
+        );
+        assert_eq!(suggest("1.2.3", &breaking), ("major", "2.0.0".to_string()));
+        assert_eq!(suggest("1.2.3", &additive), ("minor", "1.3.0".to_string()));
+        assert!(suggest_version("1.2", &additive).is_err());
+        assert!(suggest_version("1.2.x", &additive).is_err());
+    }
+}
@@ -6,6 +6,8 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Instant;
 
+mod abi_diff;
+
 const LOCAL_BUILD_PROFILE: &str = "local_build";
 
 fn main() -> Result<()> {
@@ -19,6 +21,12 @@ fn main() -> Result<()> {
             bump_version(&version)
         }
         Some("publish") => publish(),
+        Some("abi-diff") => {
+            let old = args
+                .next()
+                .ok_or_else(|| anyhow!("usage: cargo xtask abi-diff <old-rev|dir>"))?;
+            abi_diff::abi_diff(&old)
+        }
         Some(cmd) => Err(anyhow!("unknown xtask command `{cmd}`")),
     }
 }
@@ -312,6 +320,10 @@ fn workspace_root() -> Result<PathBuf> {
 fn read_binding_version(bindings_crate: &Path) -> Result<String> {
     let manifest = fs::read_to_string(bindings_crate.join("Cargo.toml"))
         .context("failed to read bindings/Cargo.toml")?;
+    parse_binding_version(&manifest)
+}
+
+fn parse_binding_version(manifest: &str) -> Result<String> {
     for line in manifest.lines() {
         let trimmed = line.trim();
         if trimmed.starts_with("version") {